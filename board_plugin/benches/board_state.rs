@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::{Board, Bounds2, MarkGrid, TileMap, TileSet};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::collections::{HashMap, HashSet};
+
+/// Board state as stored before the flat grids, to compare against
+struct HashedBoard {
+    tile_map: TileMap,
+    covered_tiles: HashSet<Coordinates>,
+    marked_tiles: HashMap<Coordinates, u8>,
+}
+
+impl HashedBoard {
+    fn is_completed(&self) -> bool {
+        let bomb_tiles = self.tile_map.iter().filter(|t| t.is_bomb()).count();
+        bomb_tiles == self.covered_tiles.len()
+            && self
+                .covered_tiles
+                .iter()
+                .all(|coords| self.tile_map.is_bomb_at(*coords))
+    }
+
+    fn marked_safe_count_at(&self, coords: Coordinates) -> u8 {
+        self.tile_map
+            .neighbors(coords)
+            .filter_map(|coords| self.marked_tiles.get(&coords))
+            .sum()
+    }
+}
+
+fn coordinates(tile_map: &TileMap) -> impl Iterator<Item = Coordinates> {
+    let (width, height) = (tile_map.width(), tile_map.height());
+    (0..height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+}
+
+/// Map of `size` x `size` tiles, a bomb on one tile out of `density`
+fn tile_map(size: u16, density: u32) -> TileMap {
+    let mut tile_map = TileMap::empty(size, size);
+    tile_map.set_bombs((u32::from(size) * u32::from(size) / density) as u16);
+    tile_map
+}
+
+/// Fully covered board, with every other bomb marked
+fn flat_board(tile_map: TileMap) -> Board {
+    let mut covered_tiles = TileSet::new(tile_map.width(), tile_map.height());
+    covered_tiles.extend(coordinates(&tile_map));
+    let mut marked_tiles = MarkGrid::new(tile_map.width(), tile_map.height());
+    for coords in coordinates(&tile_map)
+        .filter(|c| tile_map.is_bomb_at(*c))
+        .step_by(2)
+    {
+        marked_tiles.insert(coords, 1);
+    }
+    Board {
+        tile_map,
+        bounds: Bounds2 {
+            position: Vec2::ZERO,
+            size: Vec2::ZERO,
+        },
+        tile_size: 1.,
+        covered_tiles,
+        cover_entities: HashMap::new(),
+        marked_tiles,
+        flag_owners: HashMap::new(),
+        question_marks: None,
+        pending_uncovers: HashSet::new(),
+        players: None,
+        entity: Entity::from_raw(0),
+    }
+}
+
+/// Same board as `flat_board`, in the previous layout
+fn hashed_board(tile_map: TileMap) -> HashedBoard {
+    HashedBoard {
+        covered_tiles: coordinates(&tile_map).collect(),
+        marked_tiles: coordinates(&tile_map)
+            .filter(|c| tile_map.is_bomb_at(*c))
+            .step_by(2)
+            .map(|coords| (coords, 1))
+            .collect(),
+        tile_map,
+    }
+}
+
+/// Uncovers the whole board as `systems::uncover::uncover_tiles` does, checking the completion
+/// after every tile
+fn flood(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flood");
+    group.sample_size(10);
+    for size in [50, 100] {
+        // Without bombs, uncovering a tile uncovers the whole board
+        let tiles = tile_map(size, u32::MAX);
+        group.bench_with_input(BenchmarkId::new("flat", size), &tiles, |b, tiles| {
+            b.iter_batched(
+                || flat_board(tiles.clone()),
+                |mut board| {
+                    let mut pending = vec![Coordinates { x: 0, y: 0 }];
+                    while let Some(coords) = pending.pop() {
+                        if board.try_uncover_tile(&coords) && !board.is_completed() {
+                            pending.extend(board.adjacent_covered_tiles(coords));
+                        }
+                    }
+                    board
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("hashed", size), &tiles, |b, tiles| {
+            b.iter_batched(
+                || hashed_board(tiles.clone()),
+                |mut board| {
+                    let mut pending = vec![Coordinates { x: 0, y: 0 }];
+                    while let Some(coords) = pending.pop() {
+                        if board.covered_tiles.remove(&coords) && !board.is_completed() {
+                            pending.extend(
+                                board
+                                    .tile_map
+                                    .neighbors(coords)
+                                    .filter(|c| board.covered_tiles.contains(c)),
+                            );
+                        }
+                    }
+                    board
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Counts the marked neighbors of every tile, as the chords and assists do
+fn neighbor_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("neighbor_scan");
+    for size in [100, 500] {
+        let tiles = tile_map(size, 6);
+        let flat = flat_board(tiles.clone());
+        let hashed = hashed_board(tiles);
+        group.bench_function(BenchmarkId::new("flat", size), |b| {
+            b.iter(|| {
+                coordinates(&flat.tile_map)
+                    .map(|coords| u32::from(flat.marked_safe_count_at(&coords)))
+                    .sum::<u32>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("hashed", size), |b| {
+            b.iter(|| {
+                coordinates(&hashed.tile_map)
+                    .map(|coords| u32::from(hashed.marked_safe_count_at(coords)))
+                    .sum::<u32>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, flood, neighbor_scan);
+criterion_main!(benches);
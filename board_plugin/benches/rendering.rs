@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use board_plugin::{
+    Board, BoardAssets, BoardOptions, BoardPlugin, RenderMode, SpriteMaterial, TileSize,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum BenchState {
+    Playing,
+}
+
+/// Board sizes to compare, in tiles
+const SIZES: [u16; 3] = [50, 200, 500];
+
+/// App generating a board drawn with `render_mode`, without a window
+fn board_app(size: u16, render_mode: RenderMode) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(bevy::asset::AssetPlugin)
+        .add_asset::<Image>()
+        .add_plugin(bevy::input::InputPlugin)
+        .insert_resource(Windows::default())
+        .insert_resource(BoardOptions {
+            map_size: (size, size),
+            bomb_count: (u32::from(size).pow(2) / 6) as u16,
+            tile_size: TileSize::Fixed(10.),
+            render_mode,
+            ..Default::default()
+        })
+        .insert_resource(BoardAssets {
+            label: "Bench".to_string(),
+            board_material: SpriteMaterial::default(),
+            tile_material: SpriteMaterial::default(),
+            covered_tile_material: SpriteMaterial::default(),
+            bomb_counter_font: Default::default(),
+            bomb_counter_colors: BoardAssets::default_colors(),
+            bomb_counter_atlas: None,
+            flag_material: SpriteMaterial::default(),
+            bomb_material: SpriteMaterial::default(),
+        })
+        .add_plugin(BoardPlugin {
+            running_state: BenchState::Playing,
+        })
+        .add_state(BenchState::Playing);
+    app
+}
+
+/// Runs `app` until the safe start flood fill is over
+fn settle(app: &mut App) {
+    app.update();
+    while !app.world.resource::<Board>().pending_uncovers.is_empty() {
+        app.update();
+    }
+    // Chunk images are drawn on the frame after the last uncover
+    app.update();
+}
+
+fn create_board(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create_board");
+    group.sample_size(10);
+    for size in SIZES {
+        for (name, mode) in [
+            ("sprites", RenderMode::Sprites),
+            ("chunks", RenderMode::Chunks),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, size), &size, |b, &size| {
+                b.iter_batched(
+                    || board_app(size, mode),
+                    |mut app| {
+                        settle(&mut app);
+                        app
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn update_board(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_board");
+    for size in SIZES {
+        for (name, mode) in [
+            ("sprites", RenderMode::Sprites),
+            ("chunks", RenderMode::Chunks),
+        ] {
+            let mut app = board_app(size, mode);
+            settle(&mut app);
+            // Frame after a board change, a tile being marked or unmarked
+            let coords = app
+                .world
+                .resource::<Board>()
+                .covered_tiles
+                .iter()
+                .next()
+                .unwrap();
+            group.bench_with_input(BenchmarkId::new(name, size), &size, |b, _| {
+                b.iter(|| {
+                    app.world.resource_mut::<Board>().try_toggle_mark(&coords);
+                    app.update();
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, create_board, update_board);
+criterion_main!(benches);
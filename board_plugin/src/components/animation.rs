@@ -0,0 +1,26 @@
+use bevy::prelude::Component;
+
+/// Cover of an uncovered tile, fading and flipping away before being despawned
+#[derive(Debug, Copy, Clone, Component)]
+pub struct RevealAnimation {
+    /// Time to wait before the animation starts, in seconds
+    pub delay: f32,
+    /// Time since the tile was uncovered, in seconds
+    pub elapsed: f32,
+}
+
+/// Flag falling on its tile cover
+#[derive(Debug, Copy, Clone, Component)]
+pub struct FlagDrop {
+    /// Fall height, in world units
+    pub height: f32,
+    /// Time since the flag was placed, in seconds
+    pub elapsed: f32,
+}
+
+/// Ring spreading from the exploded bomb, despawned once its animation is over
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Shockwave {
+    /// Time since the explosion, in seconds
+    pub elapsed: f32,
+}
@@ -1,10 +1,24 @@
 use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Sub};
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Copy, Default, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(
+    Debug,
+    Copy,
+    Default,
+    Clone,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Hash,
+    Component,
+    Serialize,
+    Deserialize,
+)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
@@ -1,10 +1,39 @@
 use bevy::prelude::*;
-pub use {bomb::*, bomb_neighbor::*, coordinates::*, uncover::*};
+pub use {animation::*, bomb::*, bomb_neighbor::*, coordinates::*, world_coordinates::*};
 
+mod animation;
 mod bomb;
 mod bomb_neighbor;
 mod coordinates;
-mod uncover;
+mod world_coordinates;
 
 #[derive(Component)]
 pub struct BoardMarker;
+
+/// Parent of the spawned chunks of an endless board
+#[derive(Component)]
+pub struct EndlessBoardMarker;
+
+/// Hint highlight sprite
+#[derive(Component)]
+pub struct HintHighlight;
+
+/// Mine probability text of a covered tile
+#[derive(Component)]
+pub struct ProbabilityLabel(pub Coordinates);
+
+/// Copy of the tile at the given coordinates, drawn past the opposite edge of a wrapping board
+#[derive(Component)]
+pub struct Ghost(pub Coordinates);
+
+/// Cover of a ghost tile
+#[derive(Component)]
+pub struct GhostCover(pub Coordinates);
+
+/// Flag of a ghost tile cover
+#[derive(Component)]
+pub struct GhostFlag(pub Coordinates);
+
+/// Flag sprite placed by a player of a shared board, to be drawn in the player color
+#[derive(Component)]
+pub struct PlayerFlag(pub crate::PlayerId);
@@ -1,6 +0,0 @@
-use bevy::prelude::Component;
-
-/// Uncover component, indicates a covered tile that should be uncovered
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Copy, Clone, Component)]
-pub struct Uncover;
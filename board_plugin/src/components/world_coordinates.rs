@@ -0,0 +1,71 @@
+use crate::components::Coordinates;
+use crate::CHUNK_SIZE;
+use bevy::prelude::Component;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Add;
+
+/// Signed coordinates of a tile on an endless board
+#[derive(Debug, Copy, Default, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct WorldCoordinates {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl WorldCoordinates {
+    /// Chunk holding the tile, and the tile coordinates inside of it
+    #[must_use]
+    pub fn chunk(self) -> (ChunkCoordinates, Coordinates) {
+        let size = i32::from(CHUNK_SIZE);
+        let chunk = ChunkCoordinates {
+            x: self.x.div_euclid(size),
+            y: self.y.div_euclid(size),
+        };
+        let local = Coordinates {
+            x: self.x.rem_euclid(size) as u16,
+            y: self.y.rem_euclid(size) as u16,
+        };
+        (chunk, local)
+    }
+}
+
+impl Add<(i8, i8)> for WorldCoordinates {
+    type Output = Self;
+
+    fn add(self, (x, y): (i8, i8)) -> Self::Output {
+        Self {
+            x: self.x + i32::from(x),
+            y: self.y + i32::from(y),
+        }
+    }
+}
+
+impl Display for WorldCoordinates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Coordinates of a chunk of `CHUNK_SIZE` x `CHUNK_SIZE` tiles on an endless board
+#[derive(Debug, Copy, Default, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct ChunkCoordinates {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl ChunkCoordinates {
+    /// World coordinates of the tile at `local` in the chunk
+    #[must_use]
+    pub fn tile(self, local: Coordinates) -> WorldCoordinates {
+        let size = i32::from(CHUNK_SIZE);
+        WorldCoordinates {
+            x: self.x * size + i32::from(local.x),
+            y: self.y * size + i32::from(local.y),
+        }
+    }
+}
+
+impl Display for ChunkCoordinates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
@@ -0,0 +1,209 @@
+use crate::components::Coordinates;
+use crate::coop::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+use crate::versus::{BoardSpec, Connection};
+use crate::{Board, BoardCommand, BoardCommands, PlayerId};
+use bevy::log;
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::net::ToSocketAddrs;
+
+/// Shared board of a client. Must be used as a resource, along with `BoardCommands::shared`
+#[derive(Debug)]
+pub struct CoopClient {
+    connection: Connection<ClientMessage, ServerMessage>,
+    /// Number given by the server
+    pub player: Option<PlayerId>,
+    /// Board being played, `None` until the server started it
+    pub board: Option<BoardSpec>,
+    /// Names of the connected players, this one included
+    pub players: BTreeMap<PlayerId, String>,
+    /// Tiles hovered by the other players
+    pub cursors: HashMap<PlayerId, Coordinates>,
+    /// Was the last board cleared, once over
+    pub over: Option<bool>,
+    /// Why the connection was refused or closed
+    pub disconnected: Option<String>,
+    /// Commands received for the next board, waiting for it to be generated
+    pending: Vec<BoardCommand>,
+    waiting_for_board: bool,
+    /// Last tile hovered by this player
+    cursor: Option<Coordinates>,
+}
+
+impl CoopClient {
+    /// Connects to the server at `address` as `name`
+    pub fn connect(address: impl ToSocketAddrs, name: &str) -> io::Result<Self> {
+        let mut connection = Connection::connect(address)?;
+        connection.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            name: name.to_string(),
+        })?;
+        Ok(Self {
+            connection,
+            player: None,
+            board: None,
+            players: BTreeMap::new(),
+            cursors: HashMap::new(),
+            over: None,
+            disconnected: None,
+            pending: Vec::new(),
+            waiting_for_board: false,
+            cursor: None,
+        })
+    }
+
+    /// Sends a message, while connected
+    pub fn send(&mut self, message: &ClientMessage) {
+        if self.disconnected.is_some() {
+            return;
+        }
+        if let Err(e) = self.connection.send(message) {
+            self.disconnected = Some(e.to_string());
+        }
+    }
+
+    /// Applies the received messages to the shared board, and returns them
+    pub fn poll(&mut self) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        loop {
+            match self.connection.try_recv() {
+                Ok(Some(message)) => {
+                    self.apply(&message);
+                    messages.push(message);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    if self.disconnected.is_none() {
+                        self.disconnected = Some(e.to_string());
+                    }
+                    break;
+                }
+            }
+        }
+        messages
+    }
+
+    fn apply(&mut self, message: &ServerMessage) {
+        match message {
+            ServerMessage::Welcome { player, .. } => self.player = Some(*player),
+            ServerMessage::Error { message } => log::error!("Co-op server error: {}", message),
+            ServerMessage::Start { board, history } => {
+                self.board = Some(*board);
+                self.over = None;
+                self.cursors.clear();
+                // The history is applied to the new board, once generated
+                self.pending = history.clone();
+                self.waiting_for_board = true;
+            }
+            ServerMessage::Joined { player, name } => {
+                self.players.insert(*player, name.clone());
+            }
+            ServerMessage::Left { player } => {
+                self.players.remove(player);
+                self.cursors.remove(player);
+            }
+            ServerMessage::Command(command) => self.pending.push(*command),
+            ServerMessage::Cursor { player, x, y } => {
+                self.cursors.insert(*player, Coordinates { x: *x, y: *y });
+            }
+            ServerMessage::Over { won } => self.over = Some(*won),
+        }
+    }
+
+    /// Name of `player`
+    #[must_use]
+    pub fn name(&self, player: PlayerId) -> String {
+        self.players
+            .get(&player)
+            .cloned()
+            .unwrap_or_else(|| player.to_string())
+    }
+}
+
+/// Message received from the co-op server
+#[derive(Debug, Clone)]
+pub struct CoopEvent(pub ServerMessage);
+
+/// Plays a board shared with other players through a co-op server: the reveals and flags are sent
+/// to the server, and only the commands it ordered are applied, so that every player has the same
+/// board. The hovered tile is sent as the cursor of the player.
+///
+/// Needs the `CoopClient` and `BoardCommands::shared` resources, the app starting the board of
+/// the `Start` messages, received as `CoopEvent`s
+pub struct CoopClientPlugin;
+
+impl Plugin for CoopClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CoopEvent>()
+            .add_system(send_actions)
+            .add_system(send_cursor)
+            .add_system(receive_messages);
+        log::info!("Loaded Co-op Client Plugin");
+    }
+}
+
+fn send_actions(client: Option<ResMut<CoopClient>>, mut board_commands: ResMut<BoardCommands>) {
+    let mut client = match client {
+        Some(client) => client,
+        None => return,
+    };
+    for action in board_commands.take_outbox() {
+        client.send(&ClientMessage::Action(action));
+    }
+}
+
+fn send_cursor(
+    client: Option<ResMut<CoopClient>>,
+    windows: Res<Windows>,
+    board: Option<Res<Board>>,
+) {
+    let (mut client, board) = match (client, board) {
+        (Some(client), Some(board)) => (client, board),
+        _ => return,
+    };
+    let coords = windows.get_primary().and_then(|window| {
+        window
+            .cursor_position()
+            .and_then(|position| board.mouse_position(window, position))
+    });
+    if coords.is_some() && coords != client.cursor {
+        client.cursor = coords;
+        if let Some(coords) = coords {
+            client.send(&ClientMessage::Cursor(coords));
+        }
+    }
+}
+
+fn receive_messages(
+    client: Option<ResMut<CoopClient>>,
+    board: Option<Res<Board>>,
+    mut board_commands: ResMut<BoardCommands>,
+    mut coop_ewr: EventWriter<CoopEvent>,
+) {
+    let mut client = match client {
+        Some(client) => client,
+        None => return,
+    };
+    for message in client.poll() {
+        if let ServerMessage::Start { .. } = message {
+            board_commands.reset();
+        }
+        coop_ewr.send(CoopEvent(message));
+    }
+    // The commands of a new board wait for it to be generated
+    if client.waiting_for_board {
+        if !board.is_some_and(|board| board.is_added()) {
+            return;
+        }
+        client.waiting_for_board = false;
+    }
+    for command in std::mem::take(&mut client.pending) {
+        if !board_commands.receive(command) {
+            log::error!(
+                "Ignored the co-op command {:?} received out of order",
+                command
+            );
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub use {client::*, protocol::*};
+
+mod client;
+mod protocol;
@@ -0,0 +1,46 @@
+use crate::components::Coordinates;
+use crate::versus::BoardSpec;
+use crate::{BoardAction, BoardCommand, PlayerId};
+use serde::{Deserialize, Serialize};
+
+/// Version of the protocol, bumped on every incompatible change. See `docs/coop_protocol.md`
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Port of the co-op server when none is given
+pub const DEFAULT_PORT: u16 = 7879;
+
+/// Message sent by a client
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// First message of the connection
+    Hello { version: u32, name: String },
+    /// Requests an action on the shared board, applied once ordered by the server
+    Action(BoardAction),
+    /// Moves the cursor of the player over a tile
+    Cursor(Coordinates),
+}
+
+/// Message sent by the server
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Accepts the connection of `player`
+    Welcome { version: u32, player: PlayerId },
+    /// Refuses a message, closing the connection if it was the `Hello`
+    Error { message: String },
+    /// Starts playing on `board`, after the commands of its `history`
+    Start {
+        board: BoardSpec,
+        history: Vec<BoardCommand>,
+    },
+    /// A player joined the board, sent for every connected player after `Start`
+    Joined { player: PlayerId, name: String },
+    /// A player left the board
+    Left { player: PlayerId },
+    /// Next command of the board history, sent to every player
+    Command(BoardCommand),
+    /// Cursor of another player
+    Cursor { player: PlayerId, x: u16, y: u16 },
+    /// The board is over, the next one starting a few seconds later
+    Over { won: bool },
+}
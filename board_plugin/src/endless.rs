@@ -0,0 +1,254 @@
+use crate::components::{ChunkCoordinates, Coordinates, EndlessBoardMarker, WorldCoordinates};
+use crate::{
+    systems, BoardAssets, BoardPlugin, Cell, ChunkMap, EndlessOptions, SpriteMaterial, CHUNK_SIZE,
+};
+use bevy::ecs::schedule::StateData;
+use bevy::log;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// Chunks spawned past the edges of the window
+const SPAWN_MARGIN: i32 = 1;
+/// Chunks kept past the edges of the window, higher than `SPAWN_MARGIN` so that chunks aren't
+/// respawned every time the camera moves back and forth
+const DESPAWN_MARGIN: i32 = 2;
+
+/// Endless board mode, the board being spawned chunk by chunk around the camera.
+///
+/// Shares the events and the `BoardAssets` of the `BoardPlugin`, which must be added as well.
+/// Uncovering a bomb sends a `BombExplosionEvent`, the score being the number of cleared tiles
+pub struct EndlessPlugin<T> {
+    pub running_state: T,
+}
+
+impl<T: StateData> Plugin for EndlessPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EndlessOptions>()
+            .add_system_set(
+                SystemSet::on_enter(self.running_state.clone())
+                    .with_system(Self::create_endless_board),
+            )
+            .add_system_set(
+                SystemSet::on_update(self.running_state.clone())
+                    .with_system(systems::endless::scroll_camera)
+                    .with_system(systems::endless::endless_input_handling),
+            )
+            // The uncovered bomb is drawn even if the state is inactive
+            .add_system_set(
+                SystemSet::on_in_stack_update(self.running_state.clone())
+                    .with_system(Self::stream_chunks),
+            )
+            .add_system_set(
+                SystemSet::on_exit(self.running_state.clone()).with_system(Self::cleanup_board),
+            );
+        log::info!("Loaded Endless Plugin");
+    }
+}
+
+impl<T> EndlessPlugin<T> {
+    /// System generating a new endless board and uncovering its origin
+    pub fn create_endless_board(
+        mut commands: Commands,
+        options: Res<EndlessOptions>,
+        mut cameras: Query<&mut Transform, With<Camera2d>>,
+    ) {
+        let seed = options.seed.unwrap_or_else(rand::random);
+        let mut chunk_map = ChunkMap::new(seed, options.bomb_density);
+        chunk_map.uncover(WorldCoordinates::default());
+        log::info!("Endless board with seed {}", chunk_map.seed());
+        commands.insert_resource(chunk_map);
+        commands
+            .spawn()
+            .insert(Name::new("Endless board"))
+            .insert(EndlessBoardMarker)
+            .insert(Transform::default())
+            .insert(GlobalTransform::default())
+            .insert(Visibility::default())
+            .insert(ComputedVisibility::default());
+        // The camera starts on the origin tile
+        for mut transform in cameras.iter_mut() {
+            transform.translation.x = options.tile_size / 2.;
+            transform.translation.y = options.tile_size / 2.;
+        }
+    }
+
+    /// System spawning the chunks around the camera, despawning the far away ones and drawing
+    /// the changed ones again
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_chunks(
+        mut commands: Commands,
+        windows: Res<Windows>,
+        options: Res<EndlessOptions>,
+        board_assets: Res<BoardAssets>,
+        mut chunk_map: ResMut<ChunkMap>,
+        board: Query<Entity, With<EndlessBoardMarker>>,
+        cameras: Query<&Transform, With<Camera2d>>,
+        chunks: Query<(Entity, &ChunkCoordinates)>,
+    ) {
+        let (board, window, camera) = match (
+            board.get_single(),
+            windows.get_primary(),
+            cameras.get_single(),
+        ) {
+            (Ok(b), Some(w), Ok(c)) => (b, w, c),
+            _ => return,
+        };
+        let chunk_size = f32::from(CHUNK_SIZE) * options.tile_size;
+        let half_window = Vec2::new(window.width(), window.height()) / 2.;
+        let center = camera.translation.xy();
+        // Chunks seen through the window, plus `margin` chunks on every side
+        let range = |margin: i32| -> (RangeInclusive<i32>, RangeInclusive<i32>) {
+            let min = ((center - half_window) / chunk_size).floor();
+            let max = ((center + half_window) / chunk_size).floor();
+            (
+                min.x as i32 - margin..=max.x as i32 + margin,
+                min.y as i32 - margin..=max.y as i32 + margin,
+            )
+        };
+        let dirty = chunk_map.take_dirty();
+        let (kept_x, kept_y) = range(DESPAWN_MARGIN);
+        let mut spawned = HashSet::new();
+        for (entity, chunk) in chunks.iter() {
+            if dirty.contains(chunk) || !kept_x.contains(&chunk.x) || !kept_y.contains(&chunk.y) {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                spawned.insert(*chunk);
+            }
+        }
+        let (xs, ys) = range(SPAWN_MARGIN);
+        for y in ys {
+            for x in xs.clone() {
+                let chunk = ChunkCoordinates { x, y };
+                if spawned.contains(&chunk) {
+                    continue;
+                }
+                let entity =
+                    Self::spawn_chunk(&mut commands, &chunk_map, chunk, &options, &board_assets);
+                commands.entity(board).push_children(&[entity]);
+            }
+        }
+    }
+
+    /// Spawns the tiles of `chunk`, as seen by the player
+    fn spawn_chunk(
+        commands: &mut Commands,
+        chunk_map: &ChunkMap,
+        chunk: ChunkCoordinates,
+        options: &EndlessOptions,
+        board_assets: &Res<BoardAssets>,
+    ) -> Entity {
+        let size = options.tile_size;
+        let tile_size = Vec2::splat(size - options.tile_padding);
+        let chunk_size = f32::from(CHUNK_SIZE) * size;
+        let sprite = |material: &SpriteMaterial, position: Vec3| SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(tile_size),
+                color: material.color,
+                ..Default::default()
+            },
+            texture: material.texture.clone(),
+            transform: Transform::from_translation(position),
+            ..Default::default()
+        };
+        commands
+            .spawn()
+            .insert(Name::new(format!("Chunk {}", chunk)))
+            .insert(chunk)
+            .insert(Transform::from_xyz(
+                chunk.x as f32 * chunk_size,
+                chunk.y as f32 * chunk_size,
+                0.,
+            ))
+            .insert(GlobalTransform::default())
+            .insert(Visibility::default())
+            .insert(ComputedVisibility::default())
+            .with_children(|parent| {
+                // Chunk background, the sprite pivot being centered
+                parent.spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(chunk_size)),
+                        color: board_assets.board_material.color,
+                        ..Default::default()
+                    },
+                    texture: board_assets.board_material.texture.clone(),
+                    transform: Transform::from_xyz(chunk_size / 2., chunk_size / 2., 0.),
+                    ..Default::default()
+                });
+                let cells = chunk_map.chunk_cells(chunk);
+                for y in 0..CHUNK_SIZE {
+                    for x in 0..CHUNK_SIZE {
+                        let local = Coordinates { x, y };
+                        let cell = cells.map_or(Cell::Covered, |cells| {
+                            cells[y as usize * CHUNK_SIZE as usize + x as usize]
+                        });
+                        let position =
+                            Vec2::new((f32::from(x) + 0.5) * size, (f32::from(y) + 0.5) * size);
+                        let mut cmd = match cell {
+                            Cell::Covered | Cell::Marked => parent.spawn_bundle(sprite(
+                                &board_assets.covered_tile_material,
+                                position.extend(2.),
+                            )),
+                            Cell::Revealed(_) | Cell::Exploded => parent.spawn_bundle(sprite(
+                                &board_assets.tile_material,
+                                position.extend(1.),
+                            )),
+                        };
+                        cmd.insert(chunk.tile(local));
+                        match cell {
+                            Cell::Covered => (),
+                            Cell::Marked => {
+                                cmd.with_children(|parent| {
+                                    parent
+                                        .spawn_bundle(systems::mark::flag_bundle(
+                                            board_assets,
+                                            size - options.tile_padding,
+                                        ))
+                                        .insert(Name::new("Flag"));
+                                });
+                            }
+                            Cell::Revealed(0) => (),
+                            Cell::Revealed(count) => {
+                                cmd.with_children(|parent| {
+                                    BoardPlugin::<T>::spawn_bomb_count(
+                                        parent,
+                                        count,
+                                        board_assets.bomb_counter_color(count),
+                                        board_assets,
+                                        size - options.tile_padding,
+                                        1.,
+                                    );
+                                });
+                            }
+                            Cell::Exploded => {
+                                cmd.with_children(|parent| {
+                                    parent.spawn_bundle(sprite(
+                                        &board_assets.bomb_material,
+                                        Vec3::new(0., 0., 1.),
+                                    ));
+                                });
+                            }
+                        }
+                    }
+                }
+            })
+            .id()
+    }
+
+    fn cleanup_board(
+        mut commands: Commands,
+        board: Query<Entity, With<EndlessBoardMarker>>,
+        mut cameras: Query<&mut Transform, With<Camera2d>>,
+    ) {
+        for entity in board.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.remove_resource::<ChunkMap>();
+        // Regular boards are centered on the camera
+        for mut transform in cameras.iter_mut() {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+        }
+    }
+}
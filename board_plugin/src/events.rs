@@ -1,4 +1,6 @@
 use crate::components::Coordinates;
+use crate::solver::Hint;
+use crate::PlayerId;
 
 #[derive(Debug, Copy, Clone)]
 pub struct TileMarkEvent(pub Coordinates);
@@ -6,7 +8,20 @@ pub struct TileMarkEvent(pub Coordinates);
 #[derive(Debug, Copy, Clone)]
 pub struct TileTriggerEvent(pub Coordinates);
 
+/// Sent for every uncovered tile, flood fills uncovering their tiles over several frames. Tiles
+/// revealed by a hot-seat player have their `PlayerId`
+#[derive(Debug, Copy, Clone)]
+pub struct TileUncoveredEvent(pub Coordinates, pub Option<PlayerId>);
+
+/// Sent when the flag count of a tile changed, to 0 when it was unmarked
+#[derive(Debug, Copy, Clone)]
+pub struct TileMarkedEvent(pub Coordinates, pub u8);
+
 #[derive(Debug, Copy, Clone)]
 pub struct BoardCompletedEvent;
 #[derive(Debug, Copy, Clone)]
 pub struct BombExplosionEvent;
+
+/// Displays a hint on the board
+#[derive(Debug, Clone)]
+pub struct HintEvent(pub Hint);
@@ -0,0 +1,196 @@
+use crate::components::Coordinates;
+use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileMarkEvent, TileTriggerEvent};
+use crate::{
+    AnimationOptions, Board, BoardAction, BoardAssets, BoardCommand, BoardCommands, BoardOptions,
+    BoardPlugin, PlayerId, SpriteMaterial, TileSize,
+};
+use bevy::ecs::event::Events;
+use bevy::prelude::*;
+
+/// State used to run the board plugin without a window
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum HeadlessState {
+    Playing,
+}
+
+/// Status of a headless game
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// Board driven by the `BoardPlugin` systems without any window or rendering.
+///
+/// Every action runs the app until all pending uncovers (flood fill, chords) and assist moves are
+/// resolved.
+pub struct HeadlessBoard {
+    app: App,
+}
+
+impl HeadlessBoard {
+    /// Creates and generates a new board from `options`
+    pub fn new(options: BoardOptions) -> Self {
+        Self::with_setup(options, |_| ())
+    }
+
+    /// Creates and generates a new board from `options`, with `plugin` added to the app, such as
+    /// the `sound::SoundPlugin`
+    pub fn with_plugin(options: BoardOptions, plugin: impl Plugin) -> Self {
+        Self::with_setup(options, |app| {
+            app.add_plugin(plugin);
+        })
+    }
+
+    fn with_setup(options: BoardOptions, setup: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(bevy::input::InputPlugin)
+            .insert_resource(Windows::default())
+            .insert_resource(BoardOptions {
+                // Adaptive tile sizes require a window
+                tile_size: TileSize::Fixed(1.),
+                ..options
+            })
+            .insert_resource(Self::assets())
+            // Nothing is drawn, covers are despawned right away
+            .insert_resource(AnimationOptions {
+                reduced_motion: true,
+                ..Default::default()
+            })
+            .insert_resource(GameStatus::Playing)
+            .add_plugin(BoardPlugin {
+                running_state: HeadlessState::Playing,
+            })
+            .add_state(HeadlessState::Playing)
+            .add_system(Self::track_status);
+        setup(&mut app);
+        let mut board = Self { app };
+        board.settle();
+        board
+    }
+
+    /// Assets without any loaded texture or font
+    fn assets() -> BoardAssets {
+        BoardAssets {
+            label: "Headless".to_string(),
+            board_material: SpriteMaterial::default(),
+            tile_material: SpriteMaterial::default(),
+            covered_tile_material: SpriteMaterial::default(),
+            bomb_counter_font: Default::default(),
+            bomb_counter_colors: BoardAssets::default_colors(),
+            bomb_counter_atlas: None,
+            flag_material: SpriteMaterial::default(),
+            bomb_material: SpriteMaterial::default(),
+        }
+    }
+
+    fn track_status(
+        mut status: ResMut<GameStatus>,
+        mut win_events: EventReader<BoardCompletedEvent>,
+        mut bomb_explode_events: EventReader<BombExplosionEvent>,
+    ) {
+        if bomb_explode_events.iter().next().is_some() {
+            *status = GameStatus::Lost;
+        } else if win_events.iter().next().is_some() && *status == GameStatus::Playing {
+            *status = GameStatus::Won;
+        }
+    }
+
+    /// Covered and marked tile counts, to detect board changes
+    fn progress(&self) -> Option<(usize, usize)> {
+        let board = self.app.world.get_resource::<Board>()?;
+        Some((board.covered_tiles.len(), board.marked_count() as usize))
+    }
+
+    /// Runs the app until no tile is waiting to be uncovered and the board stops changing
+    fn settle(&mut self) {
+        // A flood fill uncovers at least one tile per frame, assist moves take a frame to be
+        // requested and another to be played
+        let max_frames = match self.app.world.get_resource::<Board>() {
+            Some(board) => 3 * board.covered_tiles.len() + 2,
+            // The board is generated on the first frame, before a possible safe start
+            None => {
+                let options = self.app.world.resource::<BoardOptions>();
+                let (width, height) = match &options.layout {
+                    Some(layout) => (layout.width, layout.height),
+                    None => options.map_size,
+                };
+                3 * usize::from(width) * usize::from(height) + 2
+            }
+        };
+        let mut quiet_frames = 0;
+        for _ in 0..max_frames {
+            let before = self.progress();
+            self.app.update();
+            let pending = self
+                .app
+                .world
+                .get_resource::<Board>()
+                .is_some_and(|board| !board.pending_uncovers.is_empty());
+            if pending || self.progress() != before {
+                quiet_frames = 0;
+            } else {
+                quiet_frames += 1;
+            }
+            // Tile events sent during a frame may only be read on the next one
+            if quiet_frames == 2 {
+                break;
+            }
+        }
+    }
+
+    /// Tries to uncover the tile at `coords`, chording if it is already uncovered
+    pub fn uncover(&mut self, coords: Coordinates) {
+        if self.status() != GameStatus::Playing {
+            return;
+        }
+        self.app
+            .world
+            .resource_mut::<Events<TileTriggerEvent>>()
+            .send(TileTriggerEvent(coords));
+        self.settle();
+    }
+
+    /// Tries to mark or unmark the tile at `coords`
+    pub fn toggle_mark(&mut self, coords: Coordinates) {
+        if self.status() != GameStatus::Playing {
+            return;
+        }
+        self.app
+            .world
+            .resource_mut::<Events<TileMarkEvent>>()
+            .send(TileMarkEvent(coords));
+        self.settle();
+    }
+
+    /// Applies the `action` of `player` as the next command of the board history, returning the
+    /// command
+    pub fn command(&mut self, player: Option<PlayerId>, action: BoardAction) -> BoardCommand {
+        let command = self
+            .app
+            .world
+            .resource_mut::<BoardCommands>()
+            .push(player, action);
+        self.settle();
+        command
+    }
+
+    /// The board resource
+    #[must_use]
+    pub fn board(&self) -> &Board {
+        self.app.world.resource::<Board>()
+    }
+
+    /// The app world, to read and change the resources of the added plugins
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// Current game status
+    #[must_use]
+    pub fn status(&self) -> GameStatus {
+        *self.app.world.resource::<GameStatus>()
+    }
+}
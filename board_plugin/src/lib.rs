@@ -1,7 +1,8 @@
-use crate::components::{BoardMarker, Bomb, BombNeighbor, Coordinates, Uncover};
+use crate::components::{
+    BoardMarker, Bomb, BombNeighbor, Coordinates, Ghost, GhostCover, GhostFlag,
+};
 use crate::events::*;
-use crate::resources::tile::Tile;
-use crate::tile_map::TileMap;
+use crate::solver::{ActiveHint, Difficulty, ProbabilityOverlay};
 use bevy::ecs::schedule::StateData;
 use bevy::log;
 use bevy::math::Vec3Swizzles;
@@ -9,14 +10,29 @@ use bevy::prelude::*;
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::RegisterInspectable;
 pub use bounds::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 pub use resources::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Opacity of the ghost tiles drawn around wrapping boards
+const GHOST_ALPHA: f32 = 0.4;
 
 mod bounds;
 pub mod components;
+/// Co-op mode: players sharing a board through a server ordering their commands, see
+/// `docs/coop_protocol.md`
+pub mod coop;
+pub mod endless;
 pub mod events;
+pub mod headless;
 mod resources;
+pub mod solver;
+pub mod sound;
 mod systems;
+/// Versus mode: two players racing through a server on boards generated from the same seed, see
+/// `docs/versus_protocol.md`
+pub mod versus;
 
 pub struct BoardPlugin<T> {
     pub running_state: T,
@@ -32,27 +48,50 @@ impl<T: StateData> Plugin for BoardPlugin<T> {
         .add_system_set(
             SystemSet::on_update(self.running_state.clone())
                 .with_system(systems::input::input_handling)
-                .with_system(systems::uncover::trigger_event_handler),
+                .with_system(systems::command::request_commands)
+                .with_system(systems::hint::show_hint)
+                .with_system(systems::hint::clear_hint)
+                .with_system(systems::probability::probability_overlay)
+                .with_system(systems::assist::assist),
         )
-        // We handle uncovering even if the state is inactive
+        // We handle commands and uncovering even if the state is inactive
         .add_system_set(
             SystemSet::on_in_stack_update(self.running_state.clone())
+                .with_system(systems::command::apply_commands)
                 .with_system(systems::uncover::uncover_tiles)
-                .with_system(systems::mark::mark_tiles),
+                .with_system(systems::mark::mark_tiles)
+                .with_system(systems::ghost::ghost_tiles)
+                .with_system(systems::chunks::redraw_chunks)
+                .with_system(systems::animation::animate_reveals)
+                .with_system(systems::animation::animate_flags)
+                .with_system(systems::animation::spawn_shockwave)
+                .with_system(systems::animation::animate_shockwaves),
         )
         .add_system_set(
             SystemSet::on_exit(self.running_state.clone()).with_system(Self::cleanup_board),
         )
         .add_event::<TileTriggerEvent>()
         .add_event::<TileMarkEvent>()
+        .add_event::<TileUncoveredEvent>()
+        .add_event::<TileMarkedEvent>()
         .add_event::<BombExplosionEvent>()
-        .add_event::<BoardCompletedEvent>();
+        .add_event::<BoardCompletedEvent>()
+        .add_event::<HintEvent>()
+        .init_resource::<BoardCommands>()
+        .init_resource::<AnimationOptions>()
+        .init_resource::<RevealOrigin>()
+        .init_resource::<ActiveHint>()
+        .init_resource::<ProbabilityOverlay>();
+        // Puzzles are loaded as assets, headless apps don't have any asset server
+        if app.world.contains_resource::<AssetServer>() {
+            app.add_asset::<Puzzle>()
+                .init_asset_loader::<PuzzleLoader>();
+        }
         #[cfg(feature = "debug")]
         {
             app.register_inspectable::<Bomb>()
                 .register_inspectable::<Coordinates>()
-                .register_inspectable::<BombNeighbor>()
-                .register_inspectable::<Uncover>();
+                .register_inspectable::<BombNeighbor>();
         }
         log::info!("Loaded Board Plugin");
     }
@@ -66,34 +105,99 @@ impl<T> BoardPlugin<T> {
         board_assets: Res<BoardAssets>,
         board_query: Query<Entity, With<BoardMarker>>,
         windows: Res<Windows>,
+        images: Option<ResMut<Assets<Image>>>,
     ) {
         let options = match board_options {
             None => BoardOptions::default(), // If no options is set we use the default one
             Some(o) => o.clone(),
         };
         // Tilemap generation
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
+        let layout = match &options.puzzle {
+            Some(puzzle) => Some(&puzzle.layout),
+            None => options.layout.as_ref(),
+        };
+        let mut tile_map = match (layout, &options.shape) {
+            (Some(layout), _) => TileMap::from_layout(layout),
+            (None, BoardShape::Mask(mask)) => TileMap::empty(mask.width, mask.height),
+            (None, _) => TileMap::empty(options.map_size.0, options.map_size.1),
+        };
+        tile_map.set_topology(options.topology, options.wrap);
+        if let Err(e) = tile_map.set_neighborhood(options.neighborhood.clone()) {
+            log::warn!("{}, using the adjacent neighborhood", e);
+        }
+        tile_map.set_max_mines_per_cell(options.max_mines_per_cell);
+        if let Some(mask) = options.shape.mask((tile_map.width(), tile_map.height())) {
+            tile_map.set_mask(&mask);
+        }
+        if layout.is_none() {
+            let mut rng = match options.seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+            // The solver rating the boards only knows single bomb tiles
+            let solvable = tile_map.max_mines_per_cell() == 1;
+            if !solvable && (options.no_guess || options.rating_band.is_some()) {
+                log::warn!("Multi-mine boards can't be solved, ignoring the no-guess and rating band options");
+            }
+            let generated = if solvable && options.no_guess {
+                Some(Difficulty::generate_no_guess(
+                    &tile_map,
+                    options.bomb_count,
+                    &mut rng,
+                ))
+            } else if solvable {
+                options.rating_band.map(|(min, max)| {
+                    Difficulty::generate(
+                        &tile_map,
+                        options.bomb_count,
+                        options.safe_start,
+                        min..=max,
+                        &mut rng,
+                    )
+                })
+            } else {
+                None
+            };
+            match generated {
+                Some((generated, difficulty)) => {
+                    log::info!(
+                        "Board rated {} (3BV {}, {} guesses, hardest technique {:?})",
+                        difficulty.rating,
+                        difficulty.bbbv,
+                        difficulty.guesses,
+                        difficulty.hardest_technique()
+                    );
+                    if options.no_guess && difficulty.guesses > 0 {
+                        log::warn!("No no-guess board found, the board needs guesses");
+                    }
+                    tile_map = generated;
+                }
+                None => tile_map.set_bombs_with(options.bomb_count, &mut rng),
+            }
+        }
         #[cfg(feature = "debug")]
         // Tilemap debugging
         log::info!("{}", tile_map.console_output());
 
         // Setup
 
+        let topology = tile_map.topology();
+        let map_size = (tile_map.width(), tile_map.height());
+        let ghosts = options.wrap && options.wrap_ghosts;
         // We define the size of our tiles in world space
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
-            TileSize::Adaptive { min, max } => Self::adaptative_tile_size(
-                windows.get_primary().unwrap(),
-                (min, max),
-                (tile_map.width(), tile_map.height()),
-            ),
+            TileSize::Adaptive { min, max } => {
+                let mut tiles = topology.board_size(map_size, 1.);
+                // Ghost tiles add a row or column on each side
+                if ghosts {
+                    tiles += Vec2::splat(2.);
+                }
+                Self::adaptative_tile_size(windows.get_primary().unwrap(), (min, max), tiles)
+            }
         };
         // We deduce the size of the complete board
-        let board_size = Vec2::new(
-            tile_map.width() as f32 * tile_size,
-            tile_map.height() as f32 * tile_size,
-        );
+        let board_size = topology.board_size(map_size, tile_size);
         log::info!("board size: {}", board_size);
         // We define the board anchor position (bottom left)
         let board_position = match options.position {
@@ -103,9 +207,45 @@ impl<T> BoardPlugin<T> {
             BoardPosition::Custom(p) => p,
         };
 
-        let mut covered_tiles =
-            HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
-        let mut safe_start = None;
+        // Tiles uncovered by the start state of a puzzle
+        let revealed: HashSet<Coordinates> = options
+            .puzzle
+            .iter()
+            .flat_map(|p| p.revealed.iter().copied())
+            .collect();
+        let mut covered_tiles = TileSet::new(tile_map.width(), tile_map.height());
+        covered_tiles.extend(
+            (0..tile_map.height())
+                .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinates { x, y }))
+                .filter(|coords| !tile_map.get_tile(*coords).is_void())
+                .filter(|coords| !revealed.contains(coords)),
+        );
+        let mut marked_tiles = MarkGrid::new(tile_map.width(), tile_map.height());
+        // Puzzles define their own start state
+        if let Some(puzzle) = &options.puzzle {
+            for coords in puzzle.flagged.iter() {
+                if covered_tiles.contains(coords) {
+                    marked_tiles.insert(*coords, 1);
+                }
+            }
+        }
+        let mut pending_uncovers = HashSet::new();
+        // No-guess boards are solved from the safe start
+        if (options.safe_start || options.no_guess) && options.puzzle.is_none() {
+            let safe_start = (0..tile_map.height())
+                .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinates { x, y }))
+                .find(|coords| *tile_map.get_tile(*coords) == Tile::Empty);
+            pending_uncovers.extend(safe_start);
+        }
+        // Drawing chunks needs image assets, which headless apps don't have
+        let render_mode = match (options.render_mode, images.is_some()) {
+            (RenderMode::Chunks, false) => {
+                log::warn!("No image assets to draw chunks, using sprites");
+                RenderMode::Sprites
+            }
+            (mode, _) => mode,
+        };
+        let mut cover_entities = HashMap::new();
         for board in board_query.iter() {
             commands.entity(board).despawn_recursive();
         }
@@ -119,35 +259,65 @@ impl<T> BoardPlugin<T> {
             .insert(Visibility::default())
             .insert(ComputedVisibility::default())
             .with_children(|parent| {
-                // We spawn the board background sprite at the center of the board, since the sprite pivot is centered
-                parent
-                    .spawn_bundle(SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(board_size),
-                            color: board_assets.board_material.color,
-                            ..Default::default()
-                        },
-                        texture: board_assets.board_material.texture.clone(),
-                        transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
-                        ..Default::default()
-                    })
-                    .insert(Name::new("Background"));
-                Self::spawn_tiles(
-                    parent,
-                    &tile_map,
-                    tile_size,
-                    options.tile_padding,
-                    board_assets,
-                    &mut covered_tiles,
-                    &mut safe_start,
-                );
+                if let (RenderMode::Chunks, Some(mut images)) = (render_mode, images) {
+                    // Chunk images draw their own background
+                    systems::chunks::spawn_chunks(
+                        parent,
+                        &tile_map,
+                        tile_size,
+                        options.tile_padding,
+                        &mut images,
+                    );
+                } else {
+                    if tile_map.iter().any(Tile::is_void) {
+                        // Shaped boards have a background square behind each tile instead
+                        Self::spawn_tile_backgrounds(parent, &tile_map, tile_size, &board_assets);
+                    } else {
+                        // We spawn the board background sprite at the center of the board, since the sprite pivot is centered
+                        parent
+                            .spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(board_size),
+                                    color: board_assets.board_material.color,
+                                    ..Default::default()
+                                },
+                                texture: board_assets.board_material.texture.clone(),
+                                transform: Transform::from_xyz(
+                                    board_size.x / 2.,
+                                    board_size.y / 2.,
+                                    0.,
+                                ),
+                                ..Default::default()
+                            })
+                            .insert(Name::new("Background"));
+                    }
+                    Self::spawn_tiles(
+                        parent,
+                        &tile_map,
+                        tile_size,
+                        options.tile_padding,
+                        &board_assets,
+                        &covered_tiles,
+                        &marked_tiles,
+                        &mut cover_entities,
+                    );
+                }
+                if ghosts {
+                    Self::spawn_ghosts(
+                        parent,
+                        &tile_map,
+                        tile_size,
+                        options.tile_padding,
+                        &board_assets,
+                    );
+                }
             })
             .id();
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
-            }
-        }
+        let question_marks = options
+            .question_marks
+            .then(|| TileSet::new(tile_map.width(), tile_map.height()));
+        // Animations of the previous board are over
+        commands.insert_resource(RevealOrigin::default());
         // We add the main resource of the game, the board
         commands.insert_resource(Board {
             tile_map,
@@ -157,23 +327,34 @@ impl<T> BoardPlugin<T> {
             },
             tile_size,
             covered_tiles,
-            marked_tiles: Vec::new(),
+            cover_entities,
+            marked_tiles,
+            flag_owners: HashMap::new(),
+            question_marks,
+            pending_uncovers,
+            players: options.hot_seat.as_ref().map(Players::new),
             entity: board_entity,
         });
     }
 
+    /// Spawns a sprite per tile, with a cover over the `covered_tiles`
+    #[allow(clippy::too_many_arguments)]
     fn spawn_tiles(
         parent: &mut ChildBuilder,
         tile_map: &TileMap,
         size: f32,
         padding: f32,
-        board_assets: Res<BoardAssets>,
-        covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
+        board_assets: &Res<BoardAssets>,
+        covered_tiles: &TileSet,
+        marked_tiles: &MarkGrid,
+        cover_entities: &mut HashMap<Coordinates, Entity>,
     ) {
         // Tiles
-        for (y, line) in tile_map.iter().enumerate() {
+        for (y, line) in tile_map.rows().enumerate() {
             for (x, tile) in line.iter().enumerate() {
+                if tile.is_void() {
+                    continue;
+                }
                 let coordinates = Coordinates {
                     x: x as u16,
                     y: y as u16,
@@ -188,41 +369,48 @@ impl<T> BoardPlugin<T> {
                         ..Default::default()
                     },
                     texture: board_assets.tile_material.texture.clone(),
-                    transform: Transform::from_xyz(
-                        (x as f32 * size) + (size / 2.),
-                        (y as f32 * size) + (size / 2.),
-                        1.,
+                    transform: Transform::from_translation(
+                        tile_map
+                            .topology()
+                            .tile_center(coordinates, size)
+                            .extend(1.),
                     ),
                     ..Default::default()
                 })
                 // Tile name
                 .insert(Name::new(format!("Tile ({}, {})", x, y)))
                 // Tile coordinates
-                .insert(coordinates)
-                // Children
-                .with_children(|parent| {
-                    let mut child_cmd = parent.spawn();
-                    // Tile cover
-                    let entity = child_cmd
-                        .insert_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assets.covered_tile_material.color,
+                .insert(coordinates);
+                // Children, tiles uncovered at start have no cover
+                if covered_tiles.contains(&coordinates) {
+                    cmd.with_children(|parent| {
+                        let mut child_cmd = parent.spawn();
+                        // Tile cover
+                        let entity = child_cmd
+                            .insert_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(size - padding)),
+                                    color: board_assets.covered_tile_material.color,
+                                    ..Default::default()
+                                },
+                                transform: Transform::from_xyz(0., 0., 2.),
+                                texture: board_assets.covered_tile_material.texture.clone(),
                                 ..Default::default()
-                            },
-                            transform: Transform::from_xyz(0., 0., 2.),
-                            texture: board_assets.covered_tile_material.texture.clone(),
-                            ..Default::default()
-                        })
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
+                            })
+                            .id();
+                        if marked_tiles.contains_key(&coordinates) {
+                            child_cmd.with_children(|parent| {
+                                parent
+                                    .spawn_bundle(systems::mark::flag_bundle(board_assets, size))
+                                    .insert(Name::new("Flag"));
+                            });
+                        }
+                        cover_entities.insert(coordinates, entity);
+                    });
+                }
                 match tile {
                     // If the tile is a bomb we add the matching component and a sprite child
-                    Tile::Bomb => {
+                    Tile::Bomb(count) => {
                         cmd.insert(Bomb);
                         cmd.with_children(|child_cmd| {
                             child_cmd.spawn_bundle(SpriteBundle {
@@ -235,33 +423,213 @@ impl<T> BoardPlugin<T> {
                                 texture: board_assets.bomb_material.texture.clone(),
                                 ..Default::default()
                             });
+                            // Multi-mine tiles show their bomb count over the bomb
+                            if *count > 1 {
+                                Self::spawn_bomb_count(
+                                    child_cmd,
+                                    *count,
+                                    board_assets.bomb_counter_color(*count),
+                                    board_assets,
+                                    (size - padding) / 2.,
+                                    1.5,
+                                );
+                            }
                         });
                     }
-                    // If the tile is a bomb neighbour we add the matching component and a text child
+                    // If the tile is a bomb neighbour we add the matching component and a counter child
                     Tile::BombNeighbor(v) => {
                         cmd.insert(BombNeighbor { count: *v });
                         cmd.with_children(|child_cmd| {
-                            child_cmd.spawn_bundle(Self::bomb_count_text_bundle(
+                            Self::spawn_bomb_count(
+                                child_cmd,
                                 *v,
-                                &board_assets,
+                                board_assets.bomb_counter_color(*v),
+                                board_assets,
                                 size - padding,
-                            ));
+                                1.,
+                            );
                         });
                     }
-                    Tile::Empty => (),
+                    Tile::Empty | Tile::Void => (),
                 }
             }
         }
     }
 
+    /// Spawns the background of each tile of a shaped board
+    fn spawn_tile_backgrounds(
+        parent: &mut ChildBuilder,
+        tile_map: &TileMap,
+        size: f32,
+        board_assets: &Res<BoardAssets>,
+    ) {
+        for y in 0..tile_map.height() {
+            for x in 0..tile_map.width() {
+                let coordinates = Coordinates { x, y };
+                if tile_map.get_tile(coordinates).is_void() {
+                    continue;
+                }
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(size)),
+                            color: board_assets.board_material.color,
+                            ..Default::default()
+                        },
+                        texture: board_assets.board_material.texture.clone(),
+                        transform: Transform::from_translation(
+                            tile_map
+                                .topology()
+                                .tile_center(coordinates, size)
+                                .extend(0.),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(Name::new(format!("Background ({}, {})", x, y)));
+            }
+        }
+    }
+
+    /// Spawns translucent copies of the edge tiles past the opposite edges of a wrapping board,
+    /// their cover and flag are updated by `systems::ghost::ghost_tiles`
+    fn spawn_ghosts(
+        parent: &mut ChildBuilder,
+        tile_map: &TileMap,
+        size: f32,
+        padding: f32,
+        board_assets: &Res<BoardAssets>,
+    ) {
+        let (width, height) = (tile_map.width(), tile_map.height());
+        let topology = tile_map.topology();
+        let vertical = if topology.wraps_vertically(height) {
+            -1..=1
+        } else {
+            0..=0
+        };
+        let ghost_color = |mut color: Color| {
+            color.set_a(color.a() * GHOST_ALPHA);
+            color
+        };
+        let sprite = |color: Color, z: f32| SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(size - padding)),
+                color: ghost_color(color),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0., 0., z),
+            ..Default::default()
+        };
+        for dy in vertical {
+            for dx in -1..=1 {
+                // A copy shown on the right is the left column, and so on
+                let xs = match dx {
+                    -1 => width - 1..width,
+                    0 => 0..width,
+                    _ => 0..1,
+                };
+                let ys = match dy {
+                    -1 => height - 1..height,
+                    0 if dx == 0 => continue,
+                    0 => 0..height,
+                    _ => 0..1,
+                };
+                let shift =
+                    Vec2::new(dx as f32 * f32::from(width), dy as f32 * f32::from(height)) * size;
+                for y in ys {
+                    for x in xs.clone() {
+                        let coordinates = Coordinates { x, y };
+                        if tile_map.get_tile(coordinates).is_void() {
+                            continue;
+                        }
+                        let position = topology.tile_center(coordinates, size) + shift;
+                        let mut cmd = parent.spawn_bundle(SpriteBundle {
+                            texture: board_assets.tile_material.texture.clone(),
+                            transform: Transform::from_translation(position.extend(1.)),
+                            ..sprite(board_assets.tile_material.color, 1.)
+                        });
+                        cmd.insert(Name::new(format!("Ghost ({}, {})", x, y)))
+                            .insert(Ghost(coordinates));
+                        cmd.with_children(|parent| {
+                            parent
+                                .spawn_bundle(SpriteBundle {
+                                    texture: board_assets.covered_tile_material.texture.clone(),
+                                    ..sprite(board_assets.covered_tile_material.color, 2.)
+                                })
+                                .insert(GhostCover(coordinates))
+                                .with_children(|parent| {
+                                    parent
+                                        .spawn_bundle(SpriteBundle {
+                                            texture: board_assets.flag_material.texture.clone(),
+                                            visibility: Visibility { is_visible: false },
+                                            ..sprite(board_assets.flag_material.color, 1.)
+                                        })
+                                        .insert(GhostFlag(coordinates));
+                                });
+                            match tile_map.get_tile(coordinates) {
+                                Tile::Bomb(_) => {
+                                    parent.spawn_bundle(SpriteBundle {
+                                        texture: board_assets.bomb_material.texture.clone(),
+                                        ..sprite(board_assets.bomb_material.color, 1.)
+                                    });
+                                }
+                                Tile::BombNeighbor(v) => {
+                                    Self::spawn_bomb_count(
+                                        parent,
+                                        *v,
+                                        ghost_color(board_assets.bomb_counter_color(*v)),
+                                        board_assets,
+                                        size - padding,
+                                        1.,
+                                    );
+                                }
+                                Tile::Empty | Tile::Void => (),
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Spawns the bomb counter of a given value, as an atlas glyph if the assets have one and as a
+    /// text otherwise
+    fn spawn_bomb_count(
+        parent: &mut ChildBuilder,
+        count: u8,
+        color: Color,
+        board_assets: &BoardAssets,
+        size: f32,
+        z: f32,
+    ) {
+        match board_assets.bomb_counter_glyph(count) {
+            Some((texture_atlas, index)) => {
+                parent.spawn_bundle(SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        index,
+                        color,
+                        custom_size: Some(Vec2::splat(size)),
+                        ..Default::default()
+                    },
+                    texture_atlas,
+                    transform: Transform::from_xyz(0., 0., z),
+                    ..Default::default()
+                });
+            }
+            None => {
+                let mut text = Self::bomb_count_text_bundle(count, color, board_assets, size);
+                text.transform.translation.z = z;
+                parent.spawn_bundle(text);
+            }
+        }
+    }
+
     /// Generates the bomb counter text 2D Bundle for a given value
     fn bomb_count_text_bundle(
         count: u8,
-        board_assets: &Res<BoardAssets>,
+        color: Color,
+        board_assets: &BoardAssets,
         size: f32,
     ) -> Text2dBundle {
-        // We retrieve the text and the correct color
-        let color = board_assets.bomb_counter_color(count);
         // We generate a text bundle
         Text2dBundle {
             text: Text {
@@ -283,19 +651,22 @@ impl<T> BoardPlugin<T> {
         }
     }
 
-    /// Computes a tile size that matches the window according to the tile map size
-    fn adaptative_tile_size(
-        window: &Window,
-        (min, max): (f32, f32),
-        (width, height): (u16, u16),
-    ) -> f32 {
-        let max_width = window.width() / width as f32;
-        let max_heigth = window.height() / height as f32;
+    /// Computes a tile size that matches the window according to the board size, in tiles
+    fn adaptative_tile_size(window: &Window, (min, max): (f32, f32), tiles: Vec2) -> f32 {
+        let max_width = window.width() / tiles.x;
+        let max_heigth = window.height() / tiles.y;
         max_width.min(max_heigth).clamp(min, max)
     }
 
-    fn cleanup_board(board: Res<Board>, mut commands: Commands) {
+    fn cleanup_board(
+        board: Res<Board>,
+        mut active_hint: ResMut<ActiveHint>,
+        mut probability_overlay: ResMut<ProbabilityOverlay>,
+        mut commands: Commands,
+    ) {
         commands.entity(board.entity).despawn_recursive();
         commands.remove_resource::<Board>();
+        active_hint.0 = None;
+        probability_overlay.reset();
     }
 }
@@ -0,0 +1,99 @@
+use crate::components::Coordinates;
+use serde::{Deserialize, Serialize};
+
+/// Board animation options, in seconds. Must be used as a resource.
+///
+/// Animations are only drawn over the game state, which never waits for them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationOptions {
+    /// Fade and flip of a cover being revealed
+    pub reveal_duration: f32,
+    /// Reveal delay per tile away from the uncovered tile, rippling the flood fill outward
+    pub ripple_delay: f32,
+    /// Fall of a placed flag
+    pub flag_duration: f32,
+    /// Shockwave of the exploded bomb
+    pub explosion_duration: f32,
+    /// Reveal delay per tile away from the exploded bomb, for the other bombs
+    pub chain_delay: f32,
+    /// Shows every change instantly
+    pub reduced_motion: bool,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            reveal_duration: 0.2,
+            ripple_delay: 0.03,
+            flag_duration: 0.15,
+            explosion_duration: 0.6,
+            chain_delay: 0.08,
+            reduced_motion: false,
+        }
+    }
+}
+
+/// Tile the reveal animations start from, the covers further away being revealed later. Must be
+/// used as a resource
+#[derive(Debug, Clone, Default)]
+pub struct RevealOrigin {
+    /// Origin tile with the time it was uncovered, in seconds since startup
+    origin: Option<(Coordinates, f64)>,
+    /// Reveal delay per tile away from the origin
+    delay_per_tile: f32,
+    /// Exploded bomb tile, whose shockwave is yet to be spawned if `true`
+    explosion: Option<(Coordinates, bool)>,
+    /// Was the board won, its bombs being then revealed without exploding
+    won: bool,
+}
+
+impl RevealOrigin {
+    /// Starts a ripple from `coords` at `time`
+    pub fn start(&mut self, coords: Coordinates, time: f64, delay_per_tile: f32) {
+        self.origin = Some((coords, time));
+        self.delay_per_tile = delay_per_tile;
+    }
+
+    /// Reveal delay of a cover at `distance` tiles from the origin at `time`, the part of the
+    /// ripple already played by the game logic being skipped
+    #[must_use]
+    pub fn delay(&self, distance: f32, time: f64) -> f32 {
+        match self.origin {
+            Some((_, start)) => (distance * self.delay_per_tile - (time - start) as f32).max(0.),
+            None => 0.,
+        }
+    }
+
+    // Getter for the origin tile
+    #[inline]
+    #[must_use]
+    pub fn origin(&self) -> Option<Coordinates> {
+        self.origin.map(|(coords, _)| coords)
+    }
+
+    /// Records that the board was won, the bombs uncovered afterwards don't explode
+    pub fn win(&mut self) {
+        self.won = true;
+    }
+
+    /// Records the first exploded bomb, returning `false` if a bomb already exploded or the board
+    /// was won
+    pub fn explode(&mut self, coords: Coordinates) -> bool {
+        if self.won || self.explosion.is_some() {
+            return false;
+        }
+        self.explosion = Some((coords, true));
+        true
+    }
+
+    /// Retrieves the exploded bomb tile once, to spawn its shockwave
+    pub fn take_shockwave(&mut self) -> Option<Coordinates> {
+        match &mut self.explosion {
+            Some((coords, pending)) if *pending => {
+                *pending = false;
+                Some(*coords)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,22 +1,37 @@
 use crate::components::Coordinates;
 use crate::tile_map::TileMap;
-use crate::Bounds2;
+use crate::{Bounds2, MarkGrid, PlayerId, Players, TileSet};
 use bevy::log;
 use bevy::prelude::*;
-use rand::random;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct Board {
     pub tile_map: TileMap,
     pub bounds: Bounds2,
     pub tile_size: f32,
-    pub covered_tiles: HashMap<Coordinates, Entity>,
-    pub marked_tiles: Vec<Coordinates>,
+    pub covered_tiles: TileSet,
+    /// Cover entities of the covered tiles, empty when the board is drawn in chunks
+    pub cover_entities: HashMap<Coordinates, Entity>,
+    /// Marked tiles with their flag count, which only goes above 1 in the multi-mine variant
+    pub marked_tiles: MarkGrid,
+    /// Players who placed the flags of a shared board
+    pub flag_owners: HashMap<Coordinates, PlayerId>,
+    /// Tiles marked with a question mark, `None` when question marks are disabled
+    pub question_marks: Option<TileSet>,
+    /// Covered tiles uncovered on the next frame
+    pub pending_uncovers: HashSet<Coordinates>,
+    /// Hot-seat players, `None` for a single player game
+    pub players: Option<Players>,
     pub entity: Entity,
 }
 
 impl Board {
+    /// Uncovers the tile at `coords` on the next frame, see `systems::uncover::uncover_tiles`
+    pub fn queue_uncover(&mut self, coords: Coordinates) {
+        self.pending_uncovers.insert(coords);
+    }
+
     /// Translates a mouse position to board coordinates
     pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
         let window_size = Vec2::new(window.width(), window.height());
@@ -25,24 +40,24 @@ impl Board {
         if !self.bounds.in_bounds(position) {
             return None;
         }
-        let coordinates = position - self.bounds.position;
-        Some(Coordinates {
-            x: (coordinates.x / self.tile_size) as u16,
-            y: (coordinates.y / self.tile_size) as u16,
-        })
+        self.tile_map
+            .topology()
+            .tile_at(
+                position - self.bounds.position,
+                self.tile_size,
+                (self.tile_map.width(), self.tile_map.height()),
+            )
+            .filter(|coords| !self.tile_map.get_tile(*coords).is_void())
     }
 
-    /// Retrieves a covered tile entity
-    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Vec<Entity> {
-        if self.marked_tiles.contains(coords) {
+    /// Retrieves the covered tiles to uncover when triggering `coords`
+    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Vec<Coordinates> {
+        if self.marked_tiles.contains_key(coords) {
             bevy::log::info!("tile is marked");
             vec![]
-        } else if self.covered_tiles.contains_key(coords) {
+        } else if self.covered_tiles.contains(coords) {
             bevy::log::info!("Single uncover");
-            self.covered_tiles
-                .get(coords)
-                .map(|entity| vec![*entity])
-                .unwrap()
+            vec![*coords]
         } else if self.marked_tile_is_safe(coords) {
             bevy::log::info!("Marked tile is safe");
             self.surrounding_covered_tiles(coords)
@@ -52,40 +67,34 @@ impl Board {
         }
     }
 
-    pub fn surrounding_covered_tiles(&self, coords: &Coordinates) -> Vec<Entity> {
-        [
-            (-1i8, -1i8),
-            (0, -1),
-            (1, -1),
-            (-1, 0),
-            (1, 0),
-            (-1, 1),
-            (0, 1),
-            (1, 1),
-        ]
-        .iter()
-        .map(|tuple| *coords + *tuple)
-        .filter(|coords| self.covered_tiles.contains_key(coords))
-        .filter(|coords| !self.marked_tiles.contains(coords))
-        .map(|coords| *self.covered_tiles.get(&coords).unwrap())
-        .collect()
+    pub fn surrounding_covered_tiles(&self, coords: &Coordinates) -> Vec<Coordinates> {
+        self.tile_map
+            .neighbors(*coords)
+            .filter(|coords| self.covered_tiles.contains(coords))
+            .filter(|coords| !self.marked_tiles.contains_key(coords))
+            .collect()
     }
 
     pub fn marked_safe_count_at(&self, coords: &Coordinates) -> u8 {
-        [
-            (-1i8, -1i8),
-            (0, -1),
-            (1, -1),
-            (-1, 0),
-            (1, 0),
-            (-1, 1),
-            (0, 1),
-            (1, 1),
-        ]
-        .iter()
-        .map(|tuple| *coords + *tuple)
-        .filter(|coords| self.marked_tiles.contains(coords))
-        .count() as u8
+        self.tile_map
+            .neighbors(*coords)
+            .filter_map(|coords| self.marked_tiles.get(&coords))
+            .sum()
+    }
+
+    /// Total flag count of the marked tiles
+    #[must_use]
+    pub fn marked_count(&self) -> u32 {
+        self.marked_tiles.total()
+    }
+
+    /// Is `coords` marked with a question mark
+    #[inline]
+    #[must_use]
+    pub fn is_questioned(&self, coords: &Coordinates) -> bool {
+        self.question_marks
+            .as_ref()
+            .is_some_and(|question_marks| question_marks.contains(coords))
     }
 
     pub fn marked_tile_is_safe(&self, coords: &Coordinates) -> bool {
@@ -96,63 +105,74 @@ impl Board {
     }
 
     /// Removes the `coords` from `marked_tiles`
-    fn unmark_tile(&mut self, coords: &Coordinates) -> Option<Coordinates> {
-        let pos = match self.marked_tiles.iter().position(|a| a == coords) {
-            None => {
-                log::error!("Failed to unmark tile at {}", coords);
-                return None;
-            }
-            Some(p) => p,
-        };
-        Some(self.marked_tiles.remove(pos))
-    }
-
-    pub fn find_safe_covered_coord(&self) -> Option<Coordinates> {
-        for _ in 0..10000 {
-            let covered_tiles: Vec<Coordinates> = self.covered_tiles.keys().cloned().collect();
-            let covered_tile = covered_tiles[random::<usize>() % covered_tiles.len()];
-            if !self.tile_map.is_bomb_at(covered_tile) {
-                return Some(covered_tile);
-            }
+    fn unmark_tile(&mut self, coords: &Coordinates) -> Option<u8> {
+        self.flag_owners.remove(coords);
+        let count = self.marked_tiles.remove(coords);
+        if count.is_none() {
+            log::error!("Failed to unmark tile at {}", coords);
         }
-        None
+        count
     }
 
-    /// We try to uncover a tile, returning the entity
-    pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> Option<Entity> {
-        if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords)?;
+    /// We try to uncover a tile, returning `false` if it wasn't covered
+    pub fn try_uncover_tile(&mut self, coords: &Coordinates) -> bool {
+        if self.marked_tiles.contains_key(coords) {
+            self.unmark_tile(coords);
+        }
+        if let Some(question_marks) = &mut self.question_marks {
+            question_marks.remove(coords);
         }
         self.covered_tiles.remove(coords)
     }
 
-    /// We try to mark or unmark a tile, returning the entity and if the tile is marked
-    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, bool)> {
-        let entity = *self.covered_tiles.get(coords)?;
-        let mark = if self.marked_tiles.contains(coords) {
-            self.unmark_tile(coords)?;
-            false
-        } else {
-            self.marked_tiles.push(*coords);
-            true
+    /// We try to cycle the flag count of a covered tile, from unmarked up to the maximum bombs of
+    /// a tile then through a question mark if enabled, returning the new flag count
+    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<u8> {
+        if !self.covered_tiles.contains(coords) {
+            return None;
+        }
+        if let Some(question_marks) = &mut self.question_marks {
+            if question_marks.remove(coords) {
+                return Some(0);
+            }
+        }
+        let count = match self.marked_tiles.get(coords) {
+            Some(count) if *count >= self.tile_map.max_mines_per_cell() => {
+                self.unmark_tile(coords)?;
+                if let Some(question_marks) = &mut self.question_marks {
+                    question_marks.insert(*coords);
+                }
+                0
+            }
+            Some(count) => count + 1,
+            None => 1,
         };
-        Some((entity, mark))
+        if count > 0 {
+            self.marked_tiles.insert(*coords, count);
+        }
+        Some(count)
     }
 
-    /// We retrieve the adjacent covered tile entities of `coord`
-    pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Entity> {
+    /// We retrieve the adjacent covered tiles of `coord`
+    pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Coordinates> {
         self.tile_map
-            .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
-            .copied()
+            .neighbors(coord)
+            .filter(|c| self.covered_tiles.contains(c))
             .collect()
     }
 
-    /// Is the board complete
+    /// Is the board complete, with exactly the bomb tiles left covered, apart from the ones
+    /// revealed by hot-seat players. The covered tiles are only checked once there are as many as
+    /// bomb tiles
     #[inline]
     #[must_use]
     pub fn is_completed(&self) -> bool {
-        self.tile_map.bomb_count() as usize == self.covered_tiles.len()
+        let revealed_bombs = self.players.as_ref().map_or(0, Players::mines_revealed);
+        self.tile_map.bomb_tiles() == self.covered_tiles.len() + revealed_bombs
+            && self
+                .covered_tiles
+                .iter()
+                .all(|coords| self.tile_map.is_bomb_at(coords))
     }
 
     pub fn get_all_bomb_coordinates(&self) -> Vec<Coordinates> {
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
 
+/// Bomb counter glyphs of a `BoardAssets::bomb_counter_atlas`
+pub const ATLAS_GLYPHS: u8 = 8;
+
 /// Material of a `Sprite` with a texture and color
 #[derive(Debug, Clone)]
 pub struct SpriteMaterial {
@@ -24,19 +27,22 @@ impl Default for SpriteMaterial {
 pub struct BoardAssets {
     /// Label
     pub label: String,
-    ///
+    /// Background of the board
     pub board_material: SpriteMaterial,
-    ///
+    /// Uncovered tiles
     pub tile_material: SpriteMaterial,
-    ///
+    /// Covered tiles
     pub covered_tile_material: SpriteMaterial,
-    ///
+    /// Font of the bomb counters
     pub bomb_counter_font: Handle<Font>,
-    ///
+    /// Colors of the bomb counters from 1, the last one being used for the higher counters
     pub bomb_counter_colors: Vec<Color>,
-    ///
+    /// Glyphs of the bomb counters 1 to 8, in this order and tinted with the bomb counter colors.
+    /// Bomb counters are written with the `bomb_counter_font` without it, or above 8
+    pub bomb_counter_atlas: Option<Handle<TextureAtlas>>,
+    /// Flags on the marked tiles
     pub flag_material: SpriteMaterial,
-    ///
+    /// Bombs of the revealed tiles
     pub bomb_material: SpriteMaterial,
 }
 
@@ -53,6 +59,14 @@ impl BoardAssets {
         ]
     }
 
+    /// Retrieves the atlas and glyph index of a bomb counter, if it has a glyph
+    pub fn bomb_counter_glyph(&self, counter: u8) -> Option<(Handle<TextureAtlas>, usize)> {
+        match (&self.bomb_counter_atlas, counter) {
+            (Some(atlas), 1..=ATLAS_GLYPHS) => Some((atlas.clone(), usize::from(counter - 1))),
+            _ => None,
+        }
+    }
+
     /// Safely retrieves the color matching a bomb counter
     pub fn bomb_counter_color(&self, counter: u8) -> Color {
         let counter = counter.saturating_sub(1) as usize;
@@ -0,0 +1,129 @@
+use crate::components::Coordinates;
+use crate::PlayerId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Change of the board state requested by a player
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BoardAction {
+    /// Uncovers a covered tile, or chords an uncovered one
+    Reveal(Coordinates),
+    /// Cycles the flag count of a covered tile
+    Flag(Coordinates),
+}
+
+impl BoardAction {
+    #[inline]
+    #[must_use]
+    pub const fn coords(&self) -> Coordinates {
+        match self {
+            Self::Reveal(coords) | Self::Flag(coords) => *coords,
+        }
+    }
+}
+
+/// Board action in the history of a board. Applying the same commands in the same order to boards
+/// generated identically gives the same board
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoardCommand {
+    /// Position of the command in the history of the board, from 0
+    pub sequence: u64,
+    /// Player of a shared board, `None` for a local action
+    pub player: Option<PlayerId>,
+    #[serde(flatten)]
+    pub action: BoardAction,
+}
+
+/// Board commands waiting to be applied, in order. Must be used as a resource
+///
+/// A command is only applied once the previous one is fully resolved (flood fill included), see
+/// `systems::command::apply_commands`, so that conflicts are always resolved the same way:
+/// - a reveal of a tile already uncovered by a previous command does nothing
+/// - a flag on a tile uncovered by a previous command is refused
+/// - a reveal of a flagged tile does nothing, the flag protecting it
+#[derive(Debug, Default)]
+pub struct BoardCommands {
+    queue: VecDeque<BoardCommand>,
+    next_sequence: u64,
+    /// Local actions waiting to be sent to the server of a shared board, `None` when they are
+    /// applied directly
+    outbox: Option<Vec<BoardAction>>,
+}
+
+impl BoardCommands {
+    /// Commands of a shared board: the local actions wait in an outbox to be sent to the server,
+    /// only the commands it ordered being applied
+    #[must_use]
+    pub fn shared() -> Self {
+        Self {
+            outbox: Some(Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_shared(&self) -> bool {
+        self.outbox.is_some()
+    }
+
+    /// Sequence number of the next command
+    #[inline]
+    #[must_use]
+    pub const fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Requests a local action, applied directly or sent to the server of a shared board
+    pub fn request(&mut self, action: BoardAction) {
+        match &mut self.outbox {
+            Some(outbox) => outbox.push(action),
+            None => {
+                self.push(None, action);
+            }
+        }
+    }
+
+    /// Appends an action of `player` to the history, returning its command
+    pub fn push(&mut self, player: Option<PlayerId>, action: BoardAction) -> BoardCommand {
+        let command = BoardCommand {
+            sequence: self.next_sequence,
+            player,
+            action,
+        };
+        self.next_sequence += 1;
+        self.queue.push_back(command);
+        command
+    }
+
+    /// Appends a command ordered by the server, returning `false` without appending it when it
+    /// isn't the next one of the history
+    pub fn receive(&mut self, command: BoardCommand) -> bool {
+        if command.sequence != self.next_sequence {
+            return false;
+        }
+        self.next_sequence += 1;
+        self.queue.push_back(command);
+        true
+    }
+
+    /// Removes the next command to apply
+    pub fn pop(&mut self) -> Option<BoardCommand> {
+        self.queue.pop_front()
+    }
+
+    /// Removes the local actions to send to the server
+    pub fn take_outbox(&mut self) -> Vec<BoardAction> {
+        self.outbox.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Forgets the commands of the previous board, keeping the shared mode
+    pub fn reset(&mut self) {
+        self.queue.clear();
+        self.next_sequence = 0;
+        if let Some(outbox) = &mut self.outbox {
+            outbox.clear();
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::components::Coordinates;
+use bevy::render::texture::TextureError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Supported board layout file formats
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LayoutFormat {
+    /// Plain text grid, `*` for mines and `.` for safe tiles, top line first
+    Text,
+    /// JSON serialized [`BoardLayout`]
+    Json,
+    /// Binary mine board format: width, height, big endian mine count and a `(x, y)` byte pair
+    /// per mine, with the origin at the top left
+    Mbf,
+}
+
+impl LayoutFormat {
+    /// Guesses the format from a file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "txt" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            "mbf" => Some(Self::Mbf),
+            _ => None,
+        }
+    }
+}
+
+/// Layout parsing and writing errors
+#[derive(Debug)]
+pub enum LayoutError {
+    /// The layout has no tile
+    Empty,
+    /// A text line doesn't match the first line width
+    InconsistentWidth { line: usize },
+    /// Unexpected character in a text layout
+    InvalidCharacter { line: usize, column: usize, c: char },
+    /// Binary data doesn't match the announced mine count
+    InvalidLength { expected: usize, found: usize },
+    /// A mine is outside of the board
+    OutOfBounds(Coordinates),
+    /// A mine is listed twice
+    DuplicateMine(Coordinates),
+    /// There are more mines than tiles
+    TooManyMines { mines: usize, tiles: usize },
+    /// A mine is uncovered in a start state
+    RevealedMine(Coordinates),
+    /// A tile is both uncovered and marked in a start state
+    RevealedFlag(Coordinates),
+    /// The layout can't be represented in the target format
+    Unsupported(&'static str),
+    /// JSON (de)serialization failure
+    Json(serde_json::Error),
+    /// Image decoding failure
+    Image(TextureError),
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "layout is empty"),
+            Self::InconsistentWidth { line } => {
+                write!(f, "line {} has an inconsistent width", line)
+            }
+            Self::InvalidCharacter { line, column, c } => {
+                write!(f, "invalid character {:?} at {}:{}", c, line, column)
+            }
+            Self::InvalidLength { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            }
+            Self::OutOfBounds(c) => write!(f, "mine {} is out of bounds", c),
+            Self::DuplicateMine(c) => write!(f, "mine {} is listed twice", c),
+            Self::TooManyMines { mines, tiles } => {
+                write!(f, "{} mines don't fit in {} tiles", mines, tiles)
+            }
+            Self::RevealedMine(c) => write!(f, "mine {} is uncovered", c),
+            Self::RevealedFlag(c) => write!(f, "tile {} is both uncovered and flagged", c),
+            Self::Unsupported(reason) => write!(f, "unsupported layout: {}", reason),
+            Self::Json(e) => write!(f, "{}", e),
+            Self::Image(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+impl From<serde_json::Error> for LayoutError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Fixed mine layout of a board.
+///
+/// Mine coordinates use the board coordinates, with the origin at the bottom left
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoardLayout {
+    pub width: u16,
+    pub height: u16,
+    pub mines: Vec<Coordinates>,
+}
+
+impl BoardLayout {
+    /// Checks that the layout is not empty, that its mines fit in its tiles and that every mine is on
+    /// the board exactly once
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(LayoutError::Empty);
+        }
+        let tiles = self.width as usize * self.height as usize;
+        if self.mines.len() > tiles {
+            return Err(LayoutError::TooManyMines {
+                mines: self.mines.len(),
+                tiles,
+            });
+        }
+        let mut seen = HashSet::with_capacity(self.mines.len());
+        for mine in self.mines.iter() {
+            if mine.x >= self.width || mine.y >= self.height {
+                return Err(LayoutError::OutOfBounds(*mine));
+            }
+            if !seen.insert(*mine) {
+                return Err(LayoutError::DuplicateMine(*mine));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses `data` in the given `format`
+    pub fn read(format: LayoutFormat, data: &[u8]) -> Result<Self, LayoutError> {
+        match format {
+            LayoutFormat::Text => Self::from_text(&String::from_utf8_lossy(data)),
+            LayoutFormat::Json => Self::from_json(&String::from_utf8_lossy(data)),
+            LayoutFormat::Mbf => Self::from_mbf(data),
+        }
+    }
+
+    /// Writes the layout in the given `format`
+    pub fn write(&self, format: LayoutFormat) -> Result<Vec<u8>, LayoutError> {
+        match format {
+            LayoutFormat::Text => Ok(self.to_text().into_bytes()),
+            LayoutFormat::Json => Ok(self.to_json()?.into_bytes()),
+            LayoutFormat::Mbf => self.to_mbf(),
+        }
+    }
+
+    /// Parses a plain text grid. Blank lines and surrounding whitespace are ignored
+    pub fn from_text(text: &str) -> Result<Self, LayoutError> {
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let width = match lines.first() {
+            None => return Err(LayoutError::Empty),
+            Some((_, line)) => line.chars().count(),
+        };
+        let height = lines.len();
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(LayoutError::Unsupported("board is too large"));
+        }
+        let mut mines = Vec::new();
+        for (row, (line_number, line)) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(LayoutError::InconsistentWidth { line: *line_number });
+            }
+            // The first line is the top of the board
+            let y = (height - 1 - row) as u16;
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '*' => mines.push(Coordinates { x: x as u16, y }),
+                    '.' => (),
+                    _ => {
+                        return Err(LayoutError::InvalidCharacter {
+                            line: *line_number,
+                            column: x + 1,
+                            c,
+                        })
+                    }
+                }
+            }
+        }
+        let layout = Self {
+            width: width as u16,
+            height: height as u16,
+            mines,
+        };
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Writes the layout as a plain text grid, top line first
+    pub fn to_text(&self) -> String {
+        let mines: HashSet<Coordinates> = self.mines.iter().copied().collect();
+        let mut buffer = String::with_capacity((self.width as usize + 1) * self.height as usize);
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                buffer.push(if mines.contains(&Coordinates { x, y }) {
+                    '*'
+                } else {
+                    '.'
+                });
+            }
+            buffer.push('\n');
+        }
+        buffer
+    }
+
+    /// Parses a JSON layout
+    pub fn from_json(json: &str) -> Result<Self, LayoutError> {
+        let layout: Self = serde_json::from_str(json)?;
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Writes the layout as pretty printed JSON
+    pub fn to_json(&self) -> Result<String, LayoutError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses a binary mine board
+    pub fn from_mbf(data: &[u8]) -> Result<Self, LayoutError> {
+        if data.len() < 4 {
+            return Err(LayoutError::InvalidLength {
+                expected: 4,
+                found: data.len(),
+            });
+        }
+        let width = data[0] as u16;
+        let height = data[1] as u16;
+        let count = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let expected = 4 + count * 2;
+        if data.len() != expected {
+            return Err(LayoutError::InvalidLength {
+                expected,
+                found: data.len(),
+            });
+        }
+        let mut mines = Vec::with_capacity(count);
+        for pair in data[4..].chunks_exact(2) {
+            let (x, y) = (pair[0] as u16, pair[1] as u16);
+            if x >= width || y >= height {
+                return Err(LayoutError::OutOfBounds(Coordinates { x, y }));
+            }
+            // MBF rows go downwards
+            mines.push(Coordinates {
+                x,
+                y: height - 1 - y,
+            });
+        }
+        let layout = Self {
+            width,
+            height,
+            mines,
+        };
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Writes the layout as a binary mine board
+    pub fn to_mbf(&self) -> Result<Vec<u8>, LayoutError> {
+        self.validate()?;
+        if self.width > u8::MAX as u16 || self.height > u8::MAX as u16 {
+            return Err(LayoutError::Unsupported("MBF boards are at most 255x255"));
+        }
+        let count = self.mines.len() as u16;
+        let mut data = Vec::with_capacity(4 + self.mines.len() * 2);
+        data.push(self.width as u8);
+        data.push(self.height as u8);
+        data.extend_from_slice(&count.to_be_bytes());
+        for mine in self.mines.iter() {
+            data.push(mine.x as u8);
+            data.push((self.height - 1 - mine.y) as u8);
+        }
+        Ok(data)
+    }
+}
@@ -1,5 +1,7 @@
+use crate::{BoardLayout, BoardShape, HotSeatOptions, Neighborhood, Puzzle, Topology};
 use bevy::prelude::Vec3;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Tile size options
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,22 +21,129 @@ pub enum BoardPosition {
     Custom(Vec3),
 }
 
+/// How the tiles are drawn
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// A sprite per tile and cover, and a text per number
+    #[default]
+    Sprites,
+    /// An image per chunk of 32 x 32 tiles, drawn again when the board changes. Scales to very
+    /// large boards, but the textures of the flag and bomb materials are replaced by plain shapes
+    /// tinted with their color
+    Chunks,
+}
+
+/// Moves played automatically for the player, through the regular tile events
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AssistLevel {
+    /// No automatic move
+    #[default]
+    None,
+    /// Flags the covered neighbors of a number when it has as many covered neighbors
+    AutoFlag,
+    /// Uncovers the other neighbors of a number when it has as many flagged neighbors
+    AutoReveal,
+    /// Both, repeated until nothing changes
+    Full,
+}
+
+impl AssistLevel {
+    #[inline]
+    #[must_use]
+    pub fn auto_flag(self) -> bool {
+        matches!(self, Self::AutoFlag | Self::Full)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn auto_reveal(self) -> bool {
+        matches!(self, Self::AutoReveal | Self::Full)
+    }
+}
+
+impl FromStr for AssistLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "flag" => Ok(Self::AutoFlag),
+            "reveal" => Ok(Self::AutoReveal),
+            "full" => Ok(Self::Full),
+            _ => Err(format!(
+                "unknown assist level {:?}, expected none, flag, reveal or full",
+                s
+            )),
+        }
+    }
+}
+
 /// Board generation options. Must be used as a resource
 // We use serde to allow saving option presets and loading them at runtime
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoardOptions {
     /// Tile map size
     pub map_size: (u16, u16),
+    /// Tile grid shape
+    #[serde(default)]
+    pub topology: Topology,
+    /// Board outline, the cells out of it are void
+    #[serde(default)]
+    pub shape: BoardShape,
+    /// Do the left and right edges, and the top and bottom edges, touch each other
+    #[serde(default)]
+    pub wrap: bool,
+    /// Draws copies of the opposite edges around a wrapping board
+    #[serde(default)]
+    pub wrap_ghosts: bool,
+    /// Tiles counted by the numbers
+    #[serde(default)]
+    pub neighborhood: Neighborhood,
     /// bomb count
     pub bomb_count: u16,
+    /// Maximum number of bombs held by a random tile, from 1 to 3. Numbers sum the bombs and
+    /// flags cycle up to this count
+    #[serde(default = "default_max_mines_per_cell")]
+    pub max_mines_per_cell: u8,
     /// Baard world position
     pub position: BoardPosition,
     /// Tile world size
     pub tile_size: TileSize,
     /// Padding between tiles
     pub tile_padding: f32,
+    /// How the tiles are drawn
+    #[serde(default)]
+    pub render_mode: RenderMode,
     /// Does the board generate a safe place to start
     pub safe_start: bool,
+    /// Fixed bomb layout used instead of random generation, overrides `map_size` and `bomb_count`
+    pub layout: Option<BoardLayout>,
+    /// Puzzle to play, overrides `layout` and `safe_start`
+    pub puzzle: Option<Puzzle>,
+    /// Moves played automatically
+    #[serde(default)]
+    pub assist: AssistLevel,
+    /// Marking a tile cycles through a question mark after the flags, before unmarking it
+    #[serde(default)]
+    pub question_marks: bool,
+    /// Difficulty rating band targeted by the random generation, as `(min, max)`. See
+    /// `solver::Difficulty`
+    #[serde(default)]
+    pub rating_band: Option<(f32, f32)>,
+    /// Seed of the random generation, the same seed and options giving the same board
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Only generates boards which can be cleared from the safe start without guessing,
+    /// overrides `rating_band`
+    #[serde(default)]
+    pub no_guess: bool,
+    /// Players taking turns on the board, `None` for a single player game. See `Players`
+    #[serde(default)]
+    pub hot_seat: Option<HotSeatOptions>,
+}
+
+fn default_max_mines_per_cell() -> u8 {
+    1
 }
 
 impl Default for TileSize {
@@ -58,11 +167,26 @@ impl Default for BoardOptions {
     fn default() -> Self {
         Self {
             map_size: (15, 15),
+            topology: Topology::Square,
+            shape: BoardShape::Rectangle,
+            wrap: false,
+            wrap_ghosts: false,
+            neighborhood: Neighborhood::Adjacent,
             bomb_count: 30,
+            max_mines_per_cell: 1,
             position: Default::default(),
             tile_size: Default::default(),
             tile_padding: 0.,
+            render_mode: RenderMode::Sprites,
             safe_start: false,
+            layout: None,
+            puzzle: None,
+            assist: AssistLevel::None,
+            question_marks: false,
+            rating_band: None,
+            seed: None,
+            no_guess: false,
+            hot_seat: None,
         }
     }
 }
@@ -0,0 +1,205 @@
+use crate::components::{ChunkCoordinates, Coordinates, WorldCoordinates};
+use crate::Topology;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+/// Width and height of a chunk, in tiles
+pub const CHUNK_SIZE: u16 = 16;
+/// Bomb densities of an endless board. Sparser boards have openings spreading forever, denser
+/// ones can't be cleared
+const DENSITY_RANGE: RangeInclusive<f32> = 0.12..=0.5;
+
+/// Tile of an endless board, as seen by the player
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cell {
+    Covered,
+    Marked,
+    /// Uncovered safe tile, with its bomb neighbor count
+    Revealed(u8),
+    /// Uncovered bomb, ending the game
+    Exploded,
+}
+
+/// Tiles of a chunk, row by row from the bottom
+#[derive(Debug)]
+struct Chunk {
+    bombs: Vec<bool>,
+    cells: Vec<Cell>,
+}
+
+/// Endless board, generated chunk by chunk when its tiles are first needed. Must be used as a
+/// resource.
+///
+/// The bombs of a chunk only depend on the seed and the chunk coordinates. Generated chunks are
+/// kept with their explored tiles, whether their entities are spawned or not
+#[derive(Debug)]
+pub struct ChunkMap {
+    seed: u64,
+    bomb_density: f32,
+    chunks: HashMap<ChunkCoordinates, Chunk>,
+    /// Chunks with tiles changed since the last `take_dirty` call
+    dirty: HashSet<ChunkCoordinates>,
+    cleared: u32,
+    exploded: bool,
+}
+
+impl ChunkMap {
+    /// Creates a board without any generated chunk, `bomb_density` being the share of bomb tiles
+    #[must_use]
+    pub fn new(seed: u64, bomb_density: f32) -> Self {
+        Self {
+            seed,
+            bomb_density: bomb_density.clamp(*DENSITY_RANGE.start(), *DENSITY_RANGE.end()),
+            chunks: HashMap::new(),
+            dirty: HashSet::new(),
+            cleared: 0,
+            exploded: false,
+        }
+    }
+
+    // Getter for `seed`
+    #[inline]
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of uncovered safe tiles, the score of the game
+    #[inline]
+    #[must_use]
+    pub fn cleared(&self) -> u32 {
+        self.cleared
+    }
+
+    /// Has a bomb been uncovered
+    #[inline]
+    #[must_use]
+    pub fn is_exploded(&self) -> bool {
+        self.exploded
+    }
+
+    /// Tile at `coords`, as seen by the player
+    #[must_use]
+    pub fn cell(&self, coords: WorldCoordinates) -> Cell {
+        let (chunk, local) = coords.chunk();
+        self.chunks
+            .get(&chunk)
+            .map_or(Cell::Covered, |c| c.cells[index(local)])
+    }
+
+    /// Tiles of `chunk` as seen by the player, row by row from the bottom. `None` if the chunk
+    /// wasn't generated yet, all its tiles being covered
+    #[must_use]
+    pub fn chunk_cells(&self, chunk: ChunkCoordinates) -> Option<&[Cell]> {
+        self.chunks.get(&chunk).map(|c| c.cells.as_slice())
+    }
+
+    /// Does the tile at `coords` hold a bomb, generating its chunk if needed
+    pub fn is_bomb_at(&mut self, coords: WorldCoordinates) -> bool {
+        let (chunk, local) = coords.chunk();
+        self.chunk_mut(chunk).bombs[index(local)]
+    }
+
+    /// Number of bombs around `coords`, generating the chunks if needed
+    pub fn bomb_count_at(&mut self, coords: WorldCoordinates) -> u8 {
+        neighbors(coords)
+            .filter(|neighbor| self.is_bomb_at(*neighbor))
+            .count() as u8
+    }
+
+    /// Uncovers the tile at `coords`, flood filling the empty tiles. Returns `true` if the tile
+    /// holds a bomb
+    pub fn uncover(&mut self, coords: WorldCoordinates) -> bool {
+        if self.cell(coords) != Cell::Covered {
+            return false;
+        }
+        if self.is_bomb_at(coords) {
+            self.exploded = true;
+            self.set_cell(coords, Cell::Exploded);
+            return true;
+        }
+        let mut stack = vec![coords];
+        while let Some(coords) = stack.pop() {
+            if self.cell(coords) != Cell::Covered {
+                continue;
+            }
+            let count = self.bomb_count_at(coords);
+            if count == 0 {
+                stack.extend(neighbors(coords));
+            }
+            self.set_cell(coords, Cell::Revealed(count));
+            self.cleared += 1;
+        }
+        false
+    }
+
+    /// Marks or unmarks the covered tile at `coords`
+    pub fn toggle_mark(&mut self, coords: WorldCoordinates) {
+        let cell = match self.cell(coords) {
+            Cell::Covered => Cell::Marked,
+            Cell::Marked => Cell::Covered,
+            _ => return,
+        };
+        self.set_cell(coords, cell);
+    }
+
+    /// Chunks with tiles changed since the last call, which need to be drawn again
+    pub fn take_dirty(&mut self) -> HashSet<ChunkCoordinates> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    fn set_cell(&mut self, coords: WorldCoordinates, cell: Cell) {
+        let (chunk, local) = coords.chunk();
+        self.chunk_mut(chunk).cells[index(local)] = cell;
+        self.dirty.insert(chunk);
+    }
+
+    fn chunk_mut(&mut self, chunk: ChunkCoordinates) -> &mut Chunk {
+        let (seed, bomb_density) = (self.seed, self.bomb_density);
+        self.chunks
+            .entry(chunk)
+            .or_insert_with(|| Chunk::generate(seed, bomb_density, chunk))
+    }
+}
+
+impl Chunk {
+    /// Places the bombs of `chunk`, the tiles around the world origin are kept safe to start from
+    fn generate(seed: u64, bomb_density: f32, chunk: ChunkCoordinates) -> Self {
+        let mut rng = StdRng::seed_from_u64(chunk_seed(seed, chunk));
+        let bombs = (0..CHUNK_SIZE)
+            .flat_map(|y| (0..CHUNK_SIZE).map(move |x| Coordinates { x, y }))
+            .map(|local| {
+                let bomb = rng.gen_bool(f64::from(bomb_density));
+                let coords = chunk.tile(local);
+                bomb && (coords.x.abs() > 1 || coords.y.abs() > 1)
+            })
+            .collect();
+        let tile_count = CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+        Self {
+            bombs,
+            cells: vec![Cell::Covered; tile_count],
+        }
+    }
+}
+
+/// Seed of the bombs of `chunk`, mixing the board seed with the chunk coordinates
+fn chunk_seed(seed: u64, chunk: ChunkCoordinates) -> u64 {
+    // Large odd multipliers spread the neighboring chunks over the whole seed range
+    seed ^ (chunk.x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (chunk.y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+}
+
+/// Index of the tile at `local` in its chunk
+fn index(local: Coordinates) -> usize {
+    local.y as usize * CHUNK_SIZE as usize + local.x as usize
+}
+
+/// The 8 tiles around `coords`
+fn neighbors(coords: WorldCoordinates) -> impl Iterator<Item = WorldCoordinates> {
+    Topology::Square
+        .neighbor_deltas(Coordinates::default())
+        .iter()
+        .map(move |delta| coords + *delta)
+}
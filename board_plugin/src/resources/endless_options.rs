@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Endless board options. Must be used as a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndlessOptions {
+    /// Seed of the bomb generation, a seed always generates the same board. Random if `None`
+    pub seed: Option<u64>,
+    /// Share of bomb tiles, from 0.12 to 0.5
+    pub bomb_density: f32,
+    /// Tile world size
+    pub tile_size: f32,
+    /// Padding between tiles
+    pub tile_padding: f32,
+    /// Camera speed, in tiles per second
+    pub scroll_speed: f32,
+}
+
+impl Default for EndlessOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            bomb_density: 0.16,
+            tile_size: 30.,
+            tile_padding: 1.,
+            scroll_speed: 10.,
+        }
+    }
+}
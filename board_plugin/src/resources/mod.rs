@@ -1,7 +1,23 @@
-pub use {board::*, board_assets::*, board_options::*};
+pub use {
+    animation_options::*, board::*, board_assets::*, board_command::*, board_layout::*,
+    board_options::*, chunk_map::*, endless_options::*, neighborhood::*, players::*, puzzle::*,
+    shape::*, sound_options::*, tile::Tile, tile_grid::*, tile_map::TileMap, topology::*,
+};
 
+mod animation_options;
 mod board;
 mod board_assets;
+mod board_command;
+mod board_layout;
 mod board_options;
+mod chunk_map;
+mod endless_options;
+mod neighborhood;
+mod players;
+mod puzzle;
+mod shape;
+mod sound_options;
 pub(crate) mod tile;
+mod tile_grid;
 pub(crate) mod tile_map;
+mod topology;
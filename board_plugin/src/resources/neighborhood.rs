@@ -0,0 +1,185 @@
+use crate::components::Coordinates;
+use crate::Topology;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Maximum number of custom neighbors, so that the numbers of the multi-mine boards, up to 3
+/// bombs per tile, fit in a byte
+pub const MAX_CUSTOM_NEIGHBORS: usize = 84;
+
+/// Maximum distance of the custom neighbors, on both axes, so that the diagram stays readable
+pub const MAX_CUSTOM_RADIUS: u8 = 5;
+
+/// Delta coordinates of the 4 orthogonal neighbors
+const ORTHOGONAL_NEIGHBORS: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Delta coordinates of the 8 knight's moves
+const KNIGHT_NEIGHBORS: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+/// Delta coordinates of the 24 tiles at most 2 tiles away
+const RADIUS_2_NEIGHBORS: [(i8, i8); 24] = [
+    (-2, -2),
+    (-1, -2),
+    (0, -2),
+    (1, -2),
+    (2, -2),
+    (-2, -1),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (2, -1),
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (-2, 1),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (2, 1),
+    (-2, 2),
+    (-1, 2),
+    (0, 2),
+    (1, 2),
+    (2, 2),
+];
+
+/// Tiles counted by the number of a tile, which are also uncovered around an empty tile
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Neighborhood {
+    /// Tiles touching the tile, as defined by the topology
+    #[default]
+    Adjacent,
+    /// Tiles sharing a side with the tile, ignoring the diagonals
+    Orthogonal,
+    /// Tiles a knight's move away
+    Knight,
+    /// Tiles at most 2 tiles away, diagonals included
+    Radius2,
+    /// Tiles at the given delta coordinates
+    Custom(Vec<(i8, i8)>),
+}
+
+impl Neighborhood {
+    /// Delta coordinates of the neighbors of `coords`
+    #[must_use]
+    pub fn deltas(&self, topology: Topology, coords: Coordinates) -> &[(i8, i8)] {
+        match self {
+            Self::Adjacent => topology.neighbor_deltas(coords),
+            Self::Orthogonal => &ORTHOGONAL_NEIGHBORS,
+            Self::Knight => &KNIGHT_NEIGHBORS,
+            Self::Radius2 => &RADIUS_2_NEIGHBORS,
+            Self::Custom(deltas) => deltas,
+        }
+    }
+
+    /// Short name of the rule
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Adjacent => "adjacent",
+            Self::Orthogonal => "orthogonal",
+            Self::Knight => "knight",
+            Self::Radius2 => "radius 2",
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    /// Checks that the custom neighbors are at most `MAX_CUSTOM_RADIUS` tiles away and at most
+    /// `MAX_CUSTOM_NEIGHBORS`
+    pub fn validate(&self) -> Result<(), String> {
+        let deltas = match self {
+            Self::Custom(deltas) => deltas,
+            _ => return Ok(()),
+        };
+        if let Some(delta) = deltas
+            .iter()
+            .find(|(x, y)| x.unsigned_abs().max(y.unsigned_abs()) > MAX_CUSTOM_RADIUS)
+        {
+            return Err(format!(
+                "neighbor {:?} is more than {} tiles away",
+                delta, MAX_CUSTOM_RADIUS
+            ));
+        }
+        // The tile itself and repeated deltas are ignored by the neighbor lookups
+        let neighbors: HashSet<&(i8, i8)> =
+            deltas.iter().filter(|delta| **delta != (0, 0)).collect();
+        if neighbors.len() > MAX_CUSTOM_NEIGHBORS {
+            return Err(format!(
+                "{} neighbors, at most {} are allowed",
+                neighbors.len(),
+                MAX_CUSTOM_NEIGHBORS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Renders the neighborhood of a square tile, top line first: `o` for the tile itself, `x`
+    /// for its neighbors. Neighbors further than `MAX_CUSTOM_RADIUS` are left out
+    #[must_use]
+    pub fn diagram(&self) -> Vec<String> {
+        let deltas = self.deltas(Topology::Square, Coordinates::default());
+        let radius = deltas
+            .iter()
+            .map(|(x, y)| x.unsigned_abs().max(y.unsigned_abs()))
+            .max()
+            .unwrap_or(0)
+            .min(MAX_CUSTOM_RADIUS) as i8;
+        (-radius..=radius)
+            .rev()
+            .map(|y| {
+                (-radius..=radius)
+                    .map(|x| match (x, y) {
+                        (0, 0) => 'o',
+                        _ if deltas.contains(&(x, y)) => 'x',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Neighborhood {
+    type Err = String;
+
+    /// Parses a rule name, or custom delta coordinates as `x,y;x,y;...`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "adjacent" => Ok(Self::Adjacent),
+            "orthogonal" => Ok(Self::Orthogonal),
+            "knight" => Ok(Self::Knight),
+            "radius2" => Ok(Self::Radius2),
+            _ => {
+                let deltas = s
+                    .split(';')
+                    .map(|delta| {
+                        let (x, y) = delta.split_once(',')?;
+                        Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+                    })
+                    .collect::<Option<Vec<(i8, i8)>>>()
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown neighborhood {:?}, expected adjacent, orthogonal, knight, radius2 or x,y;x,y;...",
+                            s
+                        )
+                    })?;
+                let neighborhood = Self::Custom(deltas);
+                neighborhood
+                    .validate()
+                    .map_err(|e| format!("invalid neighborhood {:?}: {}", s, e))?;
+                Ok(neighborhood)
+            }
+        }
+    }
+}
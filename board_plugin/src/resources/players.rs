@@ -0,0 +1,165 @@
+use crate::components::Coordinates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Hot-seat or co-op player, numbered from 0
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct PlayerId(pub u8);
+
+impl Display for PlayerId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Player {}", self.0 + 1)
+    }
+}
+
+/// What revealing a mine does to a hot-seat player
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MinePenalty {
+    /// Loses points, the player keeping on playing
+    Points(u32),
+    /// Leaves the game, the other players keeping on playing
+    Eliminate,
+}
+
+impl Default for MinePenalty {
+    fn default() -> Self {
+        Self::Points(10)
+    }
+}
+
+/// Hot-seat game options
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct HotSeatOptions {
+    /// Number of players, from 2 to 4
+    pub players: u8,
+    #[serde(default)]
+    pub mine_penalty: MinePenalty,
+}
+
+impl Default for HotSeatOptions {
+    fn default() -> Self {
+        Self {
+            players: 2,
+            mine_penalty: MinePenalty::default(),
+        }
+    }
+}
+
+/// Score of a hot-seat player
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct PlayerScore {
+    /// Revealed safe tiles, minus the mine penalties
+    pub points: i32,
+    /// Safe tiles revealed
+    pub revealed: u32,
+    /// Mines revealed
+    pub mines: u32,
+    pub eliminated: bool,
+}
+
+/// Hot-seat players taking turns on the board. A turn lasts a reveal, flood fill included
+#[derive(Debug, Clone)]
+pub struct Players {
+    pub scores: Vec<PlayerScore>,
+    /// Player whose turn it is
+    pub current: PlayerId,
+    /// Player whose reveal is being uncovered, the turn ending once it is over
+    pub revealing: Option<PlayerId>,
+    /// Player having revealed each uncovered tile, the safe start having none
+    pub revealed_by: HashMap<Coordinates, PlayerId>,
+    pub mine_penalty: MinePenalty,
+}
+
+impl Players {
+    /// Players of a new game, from 2 to 4
+    #[must_use]
+    pub fn new(options: &HotSeatOptions) -> Self {
+        Self {
+            scores: vec![PlayerScore::default(); usize::from(options.players.clamp(2, 4))],
+            current: PlayerId(0),
+            revealing: None,
+            revealed_by: HashMap::new(),
+            mine_penalty: options.mine_penalty,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn score(&self, player: PlayerId) -> &PlayerScore {
+        &self.scores[usize::from(player.0)]
+    }
+
+    /// Starts the reveal of the current player, returning `false` if one is still uncovered
+    pub fn start_reveal(&mut self) -> bool {
+        if self.revealing.is_some() || self.is_over() {
+            return false;
+        }
+        self.revealing = Some(self.current);
+        true
+    }
+
+    /// Credits `player` with the tile uncovered at `coords`
+    pub fn credit(&mut self, player: PlayerId, coords: Coordinates, is_bomb: bool) {
+        self.revealed_by.insert(coords, player);
+        let penalty = self.mine_penalty;
+        let score = &mut self.scores[usize::from(player.0)];
+        if !is_bomb {
+            score.revealed += 1;
+            score.points += 1;
+            return;
+        }
+        score.mines += 1;
+        match penalty {
+            MinePenalty::Points(points) => score.points -= points as i32,
+            MinePenalty::Eliminate => score.eliminated = true,
+        }
+    }
+
+    /// Ends the turn once the reveal is uncovered, giving it to the next player still in game
+    pub fn end_turn(&mut self) {
+        if self.revealing.take().is_none() || self.is_over() {
+            return;
+        }
+        let count = self.scores.len() as u8;
+        for offset in 1..=count {
+            let next = PlayerId((self.current.0 + offset) % count);
+            if !self.score(next).eliminated {
+                self.current = next;
+                return;
+            }
+        }
+    }
+
+    /// Mines revealed by the players, which stay uncovered
+    #[must_use]
+    pub fn mines_revealed(&self) -> usize {
+        self.scores.iter().map(|score| score.mines as usize).sum()
+    }
+
+    /// Are all the players eliminated
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.scores.iter().all(|score| score.eliminated)
+    }
+
+    /// Players ranked by points, the eliminated players last, with their rank from 1. Tied
+    /// players share their rank
+    #[must_use]
+    pub fn standings(&self) -> Vec<(usize, PlayerId, PlayerScore)> {
+        let mut players: Vec<_> = (0..self.scores.len() as u8)
+            .map(|id| (PlayerId(id), *self.score(PlayerId(id))))
+            .collect();
+        let key = |score: &PlayerScore| (score.eliminated, -score.points);
+        players.sort_by_key(|(_, score)| key(score));
+        let mut standings: Vec<(usize, PlayerId, PlayerScore)> = Vec::new();
+        for (index, (player, score)) in players.into_iter().enumerate() {
+            let rank = match standings.last() {
+                Some((rank, _, last)) if key(last) == key(&score) => *rank,
+                _ => index + 1,
+            };
+            standings.push((rank, player, score));
+        }
+        standings
+    }
+}
@@ -0,0 +1,72 @@
+use crate::components::Coordinates;
+use crate::{BoardLayout, LayoutError};
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize, Serialize};
+
+/// Hand authored puzzle: a fixed layout with some tiles already uncovered or marked.
+///
+/// Loaded as an asset from `*.puzzle.json` files
+#[derive(Debug, Clone, Serialize, Deserialize, TypeUuid)]
+#[uuid = "5b0c1a4e-6f0e-4b8e-9a53-2f1f3c1d7e42"]
+pub struct Puzzle {
+    /// Puzzle title
+    pub title: String,
+    /// Expected solving time, in seconds
+    pub par_time: u32,
+    /// Bomb layout
+    pub layout: BoardLayout,
+    /// Tiles uncovered at start
+    #[serde(default)]
+    pub revealed: Vec<Coordinates>,
+    /// Tiles marked at start
+    #[serde(default)]
+    pub flagged: Vec<Coordinates>,
+}
+
+impl Puzzle {
+    /// Checks the layout and that the start state is on the board without any uncovered bomb or
+    /// uncovered flag
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        self.layout.validate()?;
+        let in_bounds = |c: &Coordinates| c.x < self.layout.width && c.y < self.layout.height;
+        if let Some(c) = self
+            .revealed
+            .iter()
+            .chain(self.flagged.iter())
+            .find(|c| !in_bounds(c))
+        {
+            return Err(LayoutError::OutOfBounds(*c));
+        }
+        if let Some(c) = self.revealed.iter().find(|c| self.layout.mines.contains(c)) {
+            return Err(LayoutError::RevealedMine(*c));
+        }
+        if let Some(c) = self.flagged.iter().find(|c| self.revealed.contains(c)) {
+            return Err(LayoutError::RevealedFlag(*c));
+        }
+        Ok(())
+    }
+}
+
+/// Asset loader for `*.puzzle.json` files
+#[derive(Debug, Default)]
+pub struct PuzzleLoader;
+
+impl AssetLoader for PuzzleLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let puzzle: Puzzle = serde_json::from_slice(bytes)?;
+            puzzle.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(puzzle));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["puzzle.json"]
+    }
+}
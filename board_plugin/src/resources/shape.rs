@@ -0,0 +1,217 @@
+use crate::components::Coordinates;
+use crate::LayoutError;
+use bevy::render::render_resource::TextureFormat;
+use bevy::render::texture::{CompressedImageFormats, Image, ImageType};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::path::Path;
+
+/// Minimum alpha of the image pixels kept by a mask
+const ALPHA_THRESHOLD: u8 = 128;
+/// Radius of the donut hole, relative to the board radius
+const DONUT_HOLE: f32 = 0.4;
+
+/// Outline of a board, the cells out of it are void
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardShape {
+    /// Every cell of the board
+    #[default]
+    Rectangle,
+    /// Heart fitting the board
+    Heart,
+    /// Ring fitting the board
+    Donut,
+    /// Hand made mask, overrides the board size for random boards
+    Mask(ShapeMask),
+}
+
+impl BoardShape {
+    /// Parses a shape name, or reads a mask from a `.png` image or a text file at `arg`
+    pub fn load(arg: &str) -> Result<Self, Box<dyn Error>> {
+        match arg {
+            "rectangle" => Ok(Self::Rectangle),
+            "heart" => Ok(Self::Heart),
+            "donut" => Ok(Self::Donut),
+            path => {
+                let data = std::fs::read(path)?;
+                let mask = match Path::new(path).extension() {
+                    Some(extension) if extension.eq_ignore_ascii_case("png") => {
+                        ShapeMask::from_png(&data)?
+                    }
+                    _ => ShapeMask::from_text(&String::from_utf8_lossy(&data))?,
+                };
+                Ok(Self::Mask(mask))
+            }
+        }
+    }
+
+    /// Mask of a `width` x `height` board, `None` when every cell exists
+    #[must_use]
+    pub fn mask(&self, (width, height): (u16, u16)) -> Option<ShapeMask> {
+        match self {
+            Self::Rectangle => None,
+            Self::Heart => Some(ShapeMask::from_fn((width, height), |x, y| {
+                // Heart curve on x in [-1.3, 1.3] and y in [-1.1, 1.4]
+                let (x, y) = (x * 2.6 - 1.3, y * 2.5 - 1.1);
+                (x * x + y * y - 1.).powi(3) - x * x * y.powi(3) <= 0.
+            })),
+            Self::Donut => Some(ShapeMask::from_fn((width, height), |x, y| {
+                let distance = ((x * 2. - 1.).powi(2) + (y * 2. - 1.).powi(2)).sqrt();
+                (DONUT_HOLE..=1.).contains(&distance)
+            })),
+            Self::Mask(mask) => Some(mask.clone()),
+        }
+    }
+}
+
+/// Cells existing on a shaped board.
+///
+/// Cells use the board coordinates, with the origin at the bottom left
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "MaskData")]
+pub struct ShapeMask {
+    pub width: u16,
+    pub height: u16,
+    /// Existing cells, row by row from the bottom
+    cells: Vec<bool>,
+}
+
+/// Deserialized mask, whose cells must match its size
+#[derive(Deserialize)]
+struct MaskData {
+    width: u16,
+    height: u16,
+    cells: Vec<bool>,
+}
+
+impl TryFrom<MaskData> for ShapeMask {
+    type Error = String;
+
+    fn try_from(data: MaskData) -> Result<Self, Self::Error> {
+        let expected = usize::from(data.width) * usize::from(data.height);
+        if data.cells.len() != expected {
+            return Err(format!(
+                "{}x{} mask with {} cells",
+                data.width,
+                data.height,
+                data.cells.len()
+            ));
+        }
+        Ok(Self {
+            width: data.width,
+            height: data.height,
+            cells: data.cells,
+        })
+    }
+}
+
+impl ShapeMask {
+    /// Keeps the cells whose center matches `filter`, called with coordinates relative to the
+    /// board size, from 0 to 1
+    fn from_fn((width, height): (u16, u16), filter: impl Fn(f32, f32) -> bool) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                filter(
+                    (x as f32 + 0.5) / width as f32,
+                    (y as f32 + 0.5) / height as f32,
+                )
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a plain text grid, `#` for the existing cells and `.` for the void ones. Blank
+    /// lines and surrounding whitespace are ignored
+    pub fn from_text(text: &str) -> Result<Self, LayoutError> {
+        let lines: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        let width = match lines.first() {
+            None => return Err(LayoutError::Empty),
+            Some((_, line)) => line.chars().count(),
+        };
+        let height = lines.len();
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(LayoutError::Unsupported("board is too large"));
+        }
+        let mut cells = vec![false; width * height];
+        for (row, (line_number, line)) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(LayoutError::InconsistentWidth { line: *line_number });
+            }
+            // The first line is the top of the board
+            let y = height - 1 - row;
+            for (x, c) in line.chars().enumerate() {
+                cells[y * width + x] = match c {
+                    '#' => true,
+                    '.' => false,
+                    _ => {
+                        return Err(LayoutError::InvalidCharacter {
+                            line: *line_number,
+                            column: x + 1,
+                            c,
+                        })
+                    }
+                };
+            }
+        }
+        Ok(Self {
+            width: width as u16,
+            height: height as u16,
+            cells,
+        })
+    }
+
+    /// Reads a PNG image, keeping the cells of the opaque pixels
+    pub fn from_png(data: &[u8]) -> Result<Self, LayoutError> {
+        let image = Image::from_buffer(
+            data,
+            ImageType::Extension("png"),
+            CompressedImageFormats::NONE,
+            false,
+        )
+        .map_err(LayoutError::Image)?;
+        if !matches!(
+            image.texture_descriptor.format,
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        ) {
+            return Err(LayoutError::Unsupported("mask images must be 8 bit RGBA"));
+        }
+        let size = image.texture_descriptor.size;
+        let (width, height) = (size.width as usize, size.height as usize);
+        if width == 0 || height == 0 {
+            return Err(LayoutError::Empty);
+        }
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(LayoutError::Unsupported("board is too large"));
+        }
+        // Image rows go downwards
+        let cells = (0..height)
+            .rev()
+            .flat_map(|row| (0..width).map(move |x| (row * width + x) * 4 + 3))
+            .map(|alpha| image.data[alpha] >= ALPHA_THRESHOLD)
+            .collect();
+        Ok(Self {
+            width: width as u16,
+            height: height as u16,
+            cells,
+        })
+    }
+
+    /// Does the cell at `coords` exist, cells out of the mask don't
+    #[must_use]
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        coords.x < self.width
+            && coords.y < self.height
+            && self.cells[coords.y as usize * self.width as usize + coords.x as usize]
+    }
+}
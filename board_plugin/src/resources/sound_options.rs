@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Sound options of the `sound::SoundPlugin`. Must be used as a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundOptions {
+    /// Volume of every sound, from 0 to 1
+    pub volume: f32,
+    /// Queues no sound at all
+    pub muted: bool,
+    /// Tiles uncovered by a single click for the opening sound to be played, its volume growing
+    /// with the opening up to 4 times this count
+    pub big_opening: usize,
+    /// Asset path of the sound of an uncovered tile
+    pub reveal: String,
+    /// Asset path of the sound of a big opening
+    pub opening: String,
+    /// Asset path of the sound of a placed flag
+    pub flag: String,
+    /// Asset path of the sound of a removed flag
+    pub unflag: String,
+    /// Asset path of the sound of the explosion
+    pub explosion: String,
+    /// Asset path of the sound of a completed board
+    pub victory: String,
+}
+
+impl Default for SoundOptions {
+    fn default() -> Self {
+        Self {
+            volume: 0.8,
+            muted: false,
+            big_opening: 10,
+            reveal: "sounds/reveal.wav".to_string(),
+            opening: "sounds/opening.wav".to_string(),
+            flag: "sounds/flag.wav".to_string(),
+            unflag: "sounds/unflag.wav".to_string(),
+            explosion: "sounds/explosion.wav".to_string(),
+            victory: "sounds/victory.wav".to_string(),
+        }
+    }
+}
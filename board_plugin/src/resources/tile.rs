@@ -1,15 +1,17 @@
-#[cfg(feature = "debug")]
+#[cfg(feature = "console")]
 use colored::Colorize;
 
 /// Enum describing a Minesweeper tile
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Tile {
-    /// Is a bomb
-    Bomb,
+    /// Holds the given number of bombs, more than one in the multi-mine variant
+    Bomb(u8),
     /// Is a bomb neighbor
     BombNeighbor(u8),
     /// Empty tile
     Empty,
+    /// Cell missing from a shaped board, it is never drawn nor counted
+    Void,
 }
 
 impl Tile {
@@ -17,15 +19,22 @@ impl Tile {
     #[inline]
     #[must_use]
     pub const fn is_bomb(&self) -> bool {
-        matches!(self, Self::Bomb)
+        matches!(self, Self::Bomb(_))
     }
 
-    #[cfg(feature = "debug")]
+    /// Is the tile missing from the board?
+    #[inline]
+    #[must_use]
+    pub const fn is_void(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    #[cfg(feature = "console")]
     pub fn console_output(&self) -> String {
         format!(
             "{}",
             match self {
-                Tile::Bomb => "*".bright_red(),
+                Tile::Bomb(_) => "*".bright_red(),
                 Tile::BombNeighbor(v) => match v {
                     1 => "1".cyan(),
                     2 => "2".green(),
@@ -33,6 +42,7 @@ impl Tile {
                     _ => v.to_string().red(),
                 },
                 Tile::Empty => " ".normal(),
+                Tile::Void => " ".normal(),
             }
         )
     }
@@ -0,0 +1,223 @@
+use crate::components::Coordinates;
+
+/// Bit per word of a `TileSet`
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of the tiles of a board, stored as a bitset indexed by `y * width + x`
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TileSet {
+    width: u16,
+    height: u16,
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl TileSet {
+    /// Generates an empty set for a `width` x `height` board
+    #[must_use]
+    pub fn new(width: u16, height: u16) -> Self {
+        let cells = usize::from(width) * usize::from(height);
+        Self {
+            width,
+            height,
+            words: vec![0; cells.div_ceil(WORD_BITS)],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn index(&self, coords: &Coordinates) -> Option<usize> {
+        (coords.x < self.width && coords.y < self.height)
+            .then(|| usize::from(coords.y) * usize::from(self.width) + usize::from(coords.x))
+    }
+
+    /// Is `coords` in the set, `false` out of the board
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, coords: &Coordinates) -> bool {
+        self.index(coords)
+            .is_some_and(|i| self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0)
+    }
+
+    /// Adds `coords` to the set, returning `false` if it was already in it or is out of the
+    /// board
+    pub fn insert(&mut self, coords: Coordinates) -> bool {
+        let i = match self.index(&coords) {
+            Some(i) => i,
+            None => return false,
+        };
+        let word = &mut self.words[i / WORD_BITS];
+        let bit = 1 << (i % WORD_BITS);
+        if *word & bit != 0 {
+            return false;
+        }
+        *word |= bit;
+        self.len += 1;
+        true
+    }
+
+    /// Removes `coords` from the set, returning `false` if it wasn't in it
+    pub fn remove(&mut self, coords: &Coordinates) -> bool {
+        let i = match self.index(coords) {
+            Some(i) => i,
+            None => return false,
+        };
+        let word = &mut self.words[i / WORD_BITS];
+        let bit = 1 << (i % WORD_BITS);
+        if *word & bit == 0 {
+            return false;
+        }
+        *word &= !bit;
+        self.len -= 1;
+        true
+    }
+
+    // Getter for `len`
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Tiles of the set, row by row from the bottom. Skips 64 absent tiles at a time
+    pub fn iter(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        let width = usize::from(self.width);
+        self.words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| **word != 0)
+            .flat_map(move |(w, word)| {
+                let mut bits = *word;
+                std::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    Some(w * WORD_BITS + bit)
+                })
+            })
+            .map(move |i| Coordinates {
+                x: (i % width) as u16,
+                y: (i / width) as u16,
+            })
+    }
+}
+
+impl Extend<Coordinates> for TileSet {
+    fn extend<I: IntoIterator<Item = Coordinates>>(&mut self, iter: I) {
+        for coords in iter {
+            self.insert(coords);
+        }
+    }
+}
+
+/// Flag counts of the marked tiles of a board, stored in a flat grid indexed by `y * width + x`
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MarkGrid {
+    width: u16,
+    height: u16,
+    counts: Vec<u8>,
+    len: usize,
+    total: u32,
+}
+
+impl MarkGrid {
+    /// Generates a grid without any mark for a `width` x `height` board
+    #[must_use]
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            counts: vec![0; usize::from(width) * usize::from(height)],
+            len: 0,
+            total: 0,
+        }
+    }
+
+    #[inline]
+    fn index(&self, coords: &Coordinates) -> Option<usize> {
+        (coords.x < self.width && coords.y < self.height)
+            .then(|| usize::from(coords.y) * usize::from(self.width) + usize::from(coords.x))
+    }
+
+    /// Flag count of `coords`, `None` if it isn't marked
+    #[inline]
+    #[must_use]
+    pub fn get(&self, coords: &Coordinates) -> Option<&u8> {
+        self.index(coords)
+            .map(|i| &self.counts[i])
+            .filter(|count| **count > 0)
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains_key(&self, coords: &Coordinates) -> bool {
+        self.get(coords).is_some()
+    }
+
+    /// Sets the flag count of `coords`, returning the previous one. A count of 0 unmarks the tile
+    pub fn insert(&mut self, coords: Coordinates, count: u8) -> Option<u8> {
+        let i = self.index(&coords)?;
+        let previous = std::mem::replace(&mut self.counts[i], count);
+        if previous == 0 && count > 0 {
+            self.len += 1;
+        } else if previous > 0 && count == 0 {
+            self.len -= 1;
+        }
+        self.total = self.total - u32::from(previous) + u32::from(count);
+        (previous > 0).then_some(previous)
+    }
+
+    /// Unmarks `coords`, returning its flag count
+    pub fn remove(&mut self, coords: &Coordinates) -> Option<u8> {
+        self.insert(*coords, 0)
+    }
+
+    /// Number of marked tiles
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sum of the flag counts
+    #[inline]
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Marked tiles with their flag count, row by row from the bottom
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, u8)> + '_ {
+        let width = usize::from(self.width);
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(move |(i, count)| {
+                let coords = Coordinates {
+                    x: (i % width) as u16,
+                    y: (i / width) as u16,
+                };
+                (coords, *count)
+            })
+    }
+
+    /// Marked tiles, row by row from the bottom
+    pub fn keys(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.iter().map(|(coords, _)| coords)
+    }
+}
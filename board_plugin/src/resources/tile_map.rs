@@ -1,27 +1,8 @@
 use crate::components::Coordinates;
 use crate::resources::tile::Tile;
+use crate::{BoardLayout, Neighborhood, ShapeMask, Topology};
 use rand::{thread_rng, Rng};
-use std::ops::{Deref, DerefMut};
-
-/// Delta coordinates for all 8 square neighbors
-const SQUARE_COORDINATES: [(i8, i8); 8] = [
-    // Bottom left
-    (-1, -1),
-    // Bottom
-    (0, -1),
-    // Bottom right
-    (1, -1),
-    // Left
-    (-1, 0),
-    // Right
-    (1, 0),
-    // Top Left
-    (-1, 1),
-    // Top
-    (0, 1),
-    // Top right
-    (1, 1),
-];
+use std::ops::Deref;
 
 /// Base tile map
 #[derive(Debug, Clone)]
@@ -29,55 +10,156 @@ pub struct TileMap {
     bomb_count: u16,
     height: u16,
     width: u16,
-    map: Vec<Vec<Tile>>,
+    topology: Topology,
+    wrap: bool,
+    neighborhood: Neighborhood,
+    max_mines_per_cell: u8,
+    /// Number of bomb tiles, lower than `bomb_count` when tiles hold several bombs
+    bomb_tiles: usize,
+    /// Tiles row by row from the bottom, indexed by `y * width + x`
+    map: Vec<Tile>,
 }
 
 impl TileMap {
-    /// Generates an empty map
+    /// Generates an empty map of square tiles
     #[inline]
     #[must_use]
     pub fn empty(width: u16, height: u16) -> Self {
-        let map = (0..height)
-            .into_iter()
-            .map(|_| (0..width).into_iter().map(|_| Tile::Empty).collect())
-            .collect();
+        let map = vec![Tile::Empty; usize::from(width) * usize::from(height)];
         Self {
             bomb_count: 0,
             height,
             width,
+            topology: Topology::Square,
+            wrap: false,
+            neighborhood: Neighborhood::Adjacent,
+            max_mines_per_cell: 1,
+            bomb_tiles: 0,
             map,
         }
     }
 
-    /// Places bombs and bomb neighbor tiles
+    /// Changes the tile grid shape and whether its edges wrap around, updating the bomb neighbor
+    /// tiles
+    pub fn set_topology(&mut self, topology: Topology, wrap: bool) {
+        self.topology = topology;
+        self.wrap = wrap;
+        self.set_bomb_neighbors();
+    }
+
+    /// Generates a map with the bombs of `layout` and the matching bomb neighbor tiles.
+    ///
+    /// Mines outside of the layout bounds are ignored
+    #[must_use]
+    pub fn from_layout(layout: &BoardLayout) -> Self {
+        let mut map = Self::empty(layout.width, layout.height);
+        for mine in layout.mines.iter() {
+            if mine.x < map.width && mine.y < map.height {
+                let index = map.index(*mine);
+                map.map[index] = Tile::Bomb(1);
+            }
+        }
+        map.bomb_count = map.count_bombs();
+        map.set_bomb_neighbors();
+        map
+    }
+
+    /// Retrieves the bomb layout of the map, listing each bomb tile once
+    #[must_use]
+    pub fn layout(&self) -> BoardLayout {
+        let mines = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinates { x, y }))
+            .filter(|coords| self.is_bomb_at(*coords))
+            .collect();
+        BoardLayout {
+            width: self.width,
+            height: self.height,
+            mines,
+        }
+    }
+
+    /// Changes the tiles counted by the numbers, updating the bomb neighbor tiles. Invalid custom
+    /// neighborhoods are refused, leaving the map unchanged
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) -> Result<(), String> {
+        neighborhood.validate()?;
+        self.neighborhood = neighborhood;
+        self.set_bomb_neighbors();
+        Ok(())
+    }
+
+    /// Turns the tiles out of `mask` into void tiles, removing their bombs, and updates the bomb
+    /// neighbor tiles
+    pub fn set_mask(&mut self, mask: &ShapeMask) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coords = Coordinates { x, y };
+                if !mask.contains(coords) {
+                    let index = self.index(coords);
+                    self.map[index] = Tile::Void;
+                }
+            }
+        }
+        self.bomb_count = self.count_bombs();
+        self.set_bomb_neighbors();
+    }
+
+    /// Sets the maximum number of bombs held by a tile, placed by `set_bombs`, from 1 to 3
+    pub fn set_max_mines_per_cell(&mut self, max_mines_per_cell: u8) {
+        self.max_mines_per_cell = max_mines_per_cell.clamp(1, 3);
+    }
+
+    /// Sums the bombs of every tile
+    fn count_bombs(&self) -> u16 {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinates { x, y }))
+            .map(|coords| u16::from(self.mines_at(coords)))
+            .sum()
+    }
+
+    /// Places bombs and bomb neighbor tiles, at most `max_mines_per_cell` per empty tile
     pub fn set_bombs(&mut self, bomb_count: u16) {
+        self.set_bombs_with(bomb_count, &mut thread_rng());
+    }
+
+    /// Places bombs like `set_bombs`, drawing their positions from `rng`
+    pub fn set_bombs_with<R: Rng>(&mut self, bomb_count: u16, rng: &mut R) {
+        let empty_tiles = self.iter().filter(|tile| **tile == Tile::Empty).count();
+        let capacity = empty_tiles * self.max_mines_per_cell as usize;
+        let bomb_count = (bomb_count as usize).min(capacity) as u16;
         self.bomb_count = bomb_count;
         let mut remaining_bombs = bomb_count;
-        let mut rng = thread_rng();
         // Place bombs
         while remaining_bombs > 0 {
-            let (x, y) = (
-                rng.gen_range(0..self.width) as usize,
-                rng.gen_range(0..self.height) as usize,
-            );
-            if self[y][x] == Tile::Empty {
-                self[y][x] = Tile::Bomb;
-                remaining_bombs -= 1;
-            }
+            let index = self.index(Coordinates {
+                x: rng.gen_range(0..self.width),
+                y: rng.gen_range(0..self.height),
+            });
+            let tile = match self.map[index] {
+                Tile::Empty => Tile::Bomb(1),
+                Tile::Bomb(count) if count < self.max_mines_per_cell => Tile::Bomb(count + 1),
+                _ => continue,
+            };
+            self.map[index] = tile;
+            remaining_bombs -= 1;
         }
-        // Place bomb neighbors
+        self.set_bomb_neighbors();
+    }
+
+    /// Places bomb neighbor tiles around the bombs and counts the bomb tiles
+    fn set_bomb_neighbors(&mut self) {
+        self.bomb_tiles = self.iter().filter(|tile| tile.is_bomb()).count();
         for y in 0..self.height {
             for x in 0..self.width {
                 let coords = Coordinates { x, y };
-                if self.is_bomb_at(coords) {
+                if self.is_bomb_at(coords) || self.get_tile(coords).is_void() {
                     continue;
                 }
                 let num = self.bomb_count_at(coords);
-                if num == 0 {
-                    continue;
-                }
-                let tile = &mut self[y as usize][x as usize];
-                *tile = Tile::BombNeighbor(num);
+                let index = self.index(coords);
+                self.map[index] = match num {
+                    0 => Tile::Empty,
+                    _ => Tile::BombNeighbor(num),
+                };
             }
         }
     }
@@ -88,39 +170,57 @@ impl TileMap {
         if coordinates.x >= self.width || coordinates.y >= self.height {
             return false;
         };
-        self.map[coordinates.y as usize][coordinates.x as usize].is_bomb()
+        self.get_tile(coordinates).is_bomb()
+    }
+
+    /// Number of bombs held by the tile at `coordinates`
+    #[inline]
+    #[must_use]
+    pub fn mines_at(&self, coordinates: Coordinates) -> u8 {
+        if coordinates.x >= self.width || coordinates.y >= self.height {
+            return 0;
+        };
+        match *self.get_tile(coordinates) {
+            Tile::Bomb(count) => count,
+            _ => 0,
+        }
     }
 
+    /// Sum of the bombs held by the neighbors of `coordinates`, 0 for a bomb tile
     #[inline]
     #[must_use]
     pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
         if self.is_bomb_at(coordinates) {
             return 0;
         }
-        let res = self
-            .safe_square_at(coordinates)
-            .filter(|coord| self.is_bomb_at(*coord))
-            .count();
-        res as u8
+        self.neighbors(coordinates)
+            .map(|coord| self.mines_at(coord))
+            .sum()
     }
 
+    /// Neighbors of `coordinates` on the map, according to its topology and neighborhood. Void
+    /// tiles are never neighbors
     #[inline]
-    pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
-        SQUARE_COORDINATES
-            .iter()
-            .copied()
-            .map(move |tuple| coordinates + tuple)
+    pub fn neighbors(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.topology
+            .neighbors(
+                coordinates,
+                self.neighborhood.deltas(self.topology, coordinates),
+                (self.width, self.height),
+                self.wrap,
+            )
+            .filter(move |coords| !self.get_tile(*coords).is_void())
     }
 
-    #[cfg(feature = "debug")]
+    #[cfg(feature = "console")]
     pub fn console_output(&self) -> String {
         let mut buffer = format!(
             "Map ({}, {}) with {} bombs:\n",
             self.width, self.height, self.bomb_count
         );
-        let line: String = (0..=(self.width + 1)).into_iter().map(|_| '-').collect();
+        let line: String = (0..=(self.width + 1)).map(|_| '-').collect();
         buffer = format!("{}{}\n", buffer, line);
-        for line in self.iter().rev() {
+        for line in self.rows().rev() {
             buffer = format!("{}|", buffer);
             for tile in line.iter() {
                 buffer = format!("{}{}", buffer, tile.console_output());
@@ -144,6 +244,34 @@ impl TileMap {
         self.height
     }
 
+    // Getter for `topology`
+    #[inline]
+    #[must_use]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    // Getter for `wrap`
+    #[inline]
+    #[must_use]
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    // Getter for `neighborhood`
+    #[inline]
+    #[must_use]
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    // Getter for `max_mines_per_cell`
+    #[inline]
+    #[must_use]
+    pub fn max_mines_per_cell(&self) -> u8 {
+        self.max_mines_per_cell
+    }
+
     // Getter for `bomb_count`
     #[inline]
     #[must_use]
@@ -151,21 +279,35 @@ impl TileMap {
         self.bomb_count
     }
 
+    // Getter for `bomb_tiles`
+    #[inline]
+    #[must_use]
+    pub fn bomb_tiles(&self) -> usize {
+        self.bomb_tiles
+    }
+
+    /// Index of `coordinate` in the flat tile list
+    #[inline]
+    #[must_use]
+    pub fn index(&self, coordinate: Coordinates) -> usize {
+        coordinate.y as usize * self.width as usize + coordinate.x as usize
+    }
+
+    #[inline]
     pub fn get_tile(&self, coordinate: Coordinates) -> &Tile {
-        &self[coordinate.y as usize][coordinate.x as usize]
+        &self.map[self.index(coordinate)]
+    }
+
+    /// Tile rows, from the bottom
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Tile]> + '_ {
+        self.map.chunks(usize::from(self.width).max(1))
     }
 }
 
 impl Deref for TileMap {
-    type Target = Vec<Vec<Tile>>;
+    type Target = [Tile];
 
     fn deref(&self) -> &Self::Target {
         &self.map
     }
 }
-
-impl DerefMut for TileMap {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
-    }
-}
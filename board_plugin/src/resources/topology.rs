@@ -0,0 +1,179 @@
+use crate::components::Coordinates;
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Delta coordinates for all 8 square neighbors
+const SQUARE_NEIGHBORS: [(i8, i8); 8] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Delta coordinates for the 6 hex neighbors of a tile on an even row
+const HEX_EVEN_ROW_NEIGHBORS: [(i8, i8); 6] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom right
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (-1, 1),
+    // Top right
+    (0, 1),
+];
+
+/// Delta coordinates for the 6 hex neighbors of a tile on an odd row
+const HEX_ODD_ROW_NEIGHBORS: [(i8, i8); 6] = [
+    // Bottom left
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Shape of the tile grid, defining the neighbors and the world position of every tile
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Topology {
+    /// Square tiles with 8 neighbors
+    #[default]
+    Square,
+    /// Hexagonal tiles with 6 neighbors, laid out in rows with the odd rows shifted right by half
+    /// a tile
+    Hex,
+}
+
+impl Topology {
+    /// Delta coordinates of the tiles touching `coords`
+    #[must_use]
+    pub fn neighbor_deltas(self, coords: Coordinates) -> &'static [(i8, i8)] {
+        match self {
+            Self::Square => &SQUARE_NEIGHBORS,
+            Self::Hex if coords.y % 2 == 1 => &HEX_ODD_ROW_NEIGHBORS,
+            Self::Hex => &HEX_EVEN_ROW_NEIGHBORS,
+        }
+    }
+
+    /// Tiles at `deltas` from `coords` on a board of `width` x `height` tiles, see `Neighborhood`.
+    ///
+    /// Tiles out of the board are discarded, or wrapped around to the opposite edge when `wrap`
+    /// is set. See `wraps_vertically`
+    pub fn neighbors(
+        self,
+        coords: Coordinates,
+        deltas: &[(i8, i8)],
+        (width, height): (u16, u16),
+        wrap: bool,
+    ) -> impl Iterator<Item = Coordinates> + '_ {
+        let wrap = (wrap, wrap && self.wraps_vertically(height));
+        let offset = move |(dx, dy): (i8, i8)| {
+            Some(Coordinates {
+                x: wrap_axis(coords.x, dx, width, wrap.0)?,
+                y: wrap_axis(coords.y, dy, height, wrap.1)?,
+            })
+        };
+        deltas.iter().enumerate().filter_map(move |(i, delta)| {
+            let neighbor = offset(*delta)?;
+            // Wrapping boards narrower than the neighborhood reach a tile through several deltas
+            let duplicate =
+                neighbor == coords || deltas[..i].iter().any(|d| offset(*d) == Some(neighbor));
+            if duplicate {
+                None
+            } else {
+                Some(neighbor)
+            }
+        })
+    }
+
+    /// Can a board of `height` rows wrap vertically, the shifted hex rows need an even height
+    #[inline]
+    #[must_use]
+    pub fn wraps_vertically(self, height: u16) -> bool {
+        !(self == Self::Hex && height % 2 == 1)
+    }
+
+    /// Horizontal shift of the row `y`, in tiles
+    #[inline]
+    fn row_shift(self, y: u16) -> f32 {
+        match self {
+            Self::Hex if y % 2 == 1 => 0.5,
+            _ => 0.,
+        }
+    }
+
+    /// World size of a board of `width` x `height` tiles
+    #[must_use]
+    pub fn board_size(self, (width, height): (u16, u16), tile_size: f32) -> Vec2 {
+        let shift = if height > 1 { self.row_shift(1) } else { 0. };
+        Vec2::new(
+            (width as f32 + shift) * tile_size,
+            height as f32 * tile_size,
+        )
+    }
+
+    /// Center of the tile at `coords`, relative to the bottom left corner of the board
+    #[must_use]
+    pub fn tile_center(self, coords: Coordinates, tile_size: f32) -> Vec2 {
+        Vec2::new(
+            (coords.x as f32 + self.row_shift(coords.y) + 0.5) * tile_size,
+            (coords.y as f32 + 0.5) * tile_size,
+        )
+    }
+
+    /// Tile at `position`, relative to the bottom left corner of a board of `width` x `height`
+    /// tiles
+    #[must_use]
+    pub fn tile_at(
+        self,
+        position: Vec2,
+        tile_size: f32,
+        (width, height): (u16, u16),
+    ) -> Option<Coordinates> {
+        let y = (position.y / tile_size).floor();
+        if y < 0. || y >= height as f32 {
+            return None;
+        }
+        let x = (position.x / tile_size - self.row_shift(y as u16)).floor();
+        if x < 0. || x >= width as f32 {
+            return None;
+        }
+        Some(Coordinates {
+            x: x as u16,
+            y: y as u16,
+        })
+    }
+}
+
+/// Moves `value` by `delta` on an axis of `size` tiles, wrapping around when `wrap` is set
+fn wrap_axis(value: u16, delta: i8, size: u16, wrap: bool) -> Option<u16> {
+    let value = i32::from(value) + i32::from(delta);
+    if wrap {
+        Some(value.rem_euclid(i32::from(size)) as u16)
+    } else if (0..i32::from(size)).contains(&value) {
+        Some(value as u16)
+    } else {
+        None
+    }
+}
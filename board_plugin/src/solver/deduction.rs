@@ -0,0 +1,165 @@
+use crate::components::Coordinates;
+use crate::solver::probability::{CertainTiles, Frontier};
+use crate::solver::{BoardView, Constraint, TileView};
+
+/// Deduction technique, from the simplest to the most advanced
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Technique {
+    /// A single number is satisfied or needs all its covered neighbors
+    SingleCell,
+    /// The neighbors of a number are included in the neighbors of another one
+    Subset,
+    /// Several overlapping numbers only allow some configurations
+    Pattern,
+    /// The remaining bomb count decides
+    GlobalCount,
+}
+
+/// Tiles proven to be safe or bombs from the visible board
+#[derive(Debug, Clone)]
+pub struct Deduction {
+    pub technique: Technique,
+    /// Covered tiles proven safe
+    pub safe: Vec<Coordinates>,
+    /// Covered tiles proven to be bombs
+    pub mines: Vec<Coordinates>,
+    /// Uncovered tiles justifying the deduction
+    pub reasons: Vec<Coordinates>,
+    /// Short human readable explanation
+    pub explanation: String,
+}
+
+/// Finds a deduction using the simplest possible technique
+#[must_use]
+pub fn find_deduction(view: &BoardView) -> Option<Deduction> {
+    let constraints = view.constraints();
+    single_cell(view, &constraints)
+        .or_else(|| subset(view, &constraints))
+        .or_else(|| {
+            let frontier = Frontier::analyze(view)?;
+            enumeration(&frontier, Technique::Pattern)
+                .or_else(|| enumeration(&frontier, Technique::GlobalCount))
+        })
+}
+
+/// Displayed number of an uncovered tile
+fn number(view: &BoardView, coords: Coordinates) -> u8 {
+    match view.get(coords) {
+        TileView::Revealed(n) => n,
+        _ => 0,
+    }
+}
+
+/// `count` followed by `word`, pluralized
+fn plural(count: usize, word: &str) -> String {
+    match count {
+        1 => format!("a {}", word),
+        _ => format!("{} {}s", count, word),
+    }
+}
+
+fn single_cell(view: &BoardView, constraints: &[Constraint]) -> Option<Deduction> {
+    for constraint in constraints {
+        let n = number(view, constraint.source);
+        let (safe, mines, explanation) = if constraint.mines == 0 {
+            let explanation = match n {
+                0 => "this 0 has no bomb around".to_string(),
+                _ => format!("this {} already touches {}", n, plural(n as usize, "flag")),
+            };
+            (constraint.cells.clone(), Vec::new(), explanation)
+        } else if constraint.mines as usize == constraint.cells.len() {
+            let explanation = format!(
+                "this {} only has {} left, all bombs",
+                n,
+                plural(constraint.cells.len(), "covered neighbor")
+            );
+            (Vec::new(), constraint.cells.clone(), explanation)
+        } else {
+            continue;
+        };
+        return Some(Deduction {
+            technique: Technique::SingleCell,
+            safe,
+            mines,
+            reasons: vec![constraint.source],
+            explanation,
+        });
+    }
+    None
+}
+
+fn subset(view: &BoardView, constraints: &[Constraint]) -> Option<Deduction> {
+    for small in constraints {
+        for large in constraints {
+            if small.cells.len() >= large.cells.len()
+                || small.mines > large.mines
+                || !small.cells.iter().all(|c| large.cells.contains(c))
+            {
+                continue;
+            }
+            let rest: Vec<Coordinates> = large
+                .cells
+                .iter()
+                .filter(|c| !small.cells.contains(c))
+                .copied()
+                .collect();
+            let rest_mines = (large.mines - small.mines) as usize;
+            let (small_n, large_n) = (number(view, small.source), number(view, large.source));
+            let (safe, mines, explanation) = if rest_mines == 0 {
+                let explanation = format!(
+                    "the {} at {} gets all its bombs from the {} at {}, its other neighbors are safe",
+                    large_n, large.source, small_n, small.source
+                );
+                (rest, Vec::new(), explanation)
+            } else if rest_mines == rest.len() {
+                let explanation = format!(
+                    "the {} at {} gets at most {} from the {} at {}, its other neighbors are bombs",
+                    large_n,
+                    large.source,
+                    plural(small.mines as usize, "bomb"),
+                    small_n,
+                    small.source
+                );
+                (Vec::new(), rest, explanation)
+            } else {
+                continue;
+            };
+            return Some(Deduction {
+                technique: Technique::Subset,
+                safe,
+                mines,
+                reasons: vec![small.source, large.source],
+                explanation,
+            });
+        }
+    }
+    None
+}
+
+fn enumeration(frontier: &Frontier, technique: Technique) -> Option<Deduction> {
+    let global = technique == Technique::GlobalCount;
+    let CertainTiles {
+        safe,
+        mines,
+        reasons,
+    } = frontier.certainties(global)?;
+    let explanation = match (global, safe.is_empty()) {
+        (false, false) => "these numbers only fit together if this tile is safe".to_string(),
+        (false, true) => "these numbers only fit together if this tile is a bomb".to_string(),
+        (true, false) => format!(
+            "with {} left, this tile can only be safe",
+            plural(frontier.remaining_mines, "bomb")
+        ),
+        (true, true) => format!(
+            "with {} left, this tile can only be a bomb",
+            plural(frontier.remaining_mines, "bomb")
+        ),
+    };
+    Some(Deduction {
+        technique,
+        safe,
+        mines,
+        reasons,
+        explanation,
+    })
+}
@@ -0,0 +1,270 @@
+use crate::components::Coordinates;
+use crate::resources::tile::Tile;
+use crate::solver::{find_deduction, BoardView, MineProbabilities, Technique, TileView};
+use crate::TileMap;
+use rand::Rng;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
+
+/// Rating points of a guess
+const GUESS_POINTS: f32 = 20.;
+/// Maximum number of boards generated when targeting a rating band
+const MAX_GENERATION_ATTEMPTS: usize = 100;
+/// Maximum number of boards generated when looking for a no-guess board, bounding the time spent
+/// generating a board on the first frame. Once they are all checked, the board leaving the fewest
+/// safe tiles to guess is kept
+const MAX_NO_GUESS_ATTEMPTS: usize = 500;
+
+/// Rating points of a deduction, on top of the clicks counted by the 3BV
+fn technique_points(technique: Technique) -> f32 {
+    match technique {
+        Technique::SingleCell => 0.,
+        Technique::Subset => 1.,
+        Technique::Pattern => 3.,
+        Technique::GlobalCount => 5.,
+    }
+}
+
+/// Difficulty of a board, measured by solving it with the player knowledge only
+#[derive(Debug, Clone)]
+pub struct Difficulty {
+    /// Guesses needed to clear the board, the first click included without safe start
+    pub guesses: u32,
+    /// Chance to survive every guess
+    pub survival: f32,
+    /// Number of deductions made with each technique
+    pub techniques: BTreeMap<Technique, u32>,
+    /// Bechtel's Board Benchmark Value: minimum number of clicks to clear the board without flags
+    pub bbbv: u32,
+    /// Combined rating: the 3BV, plus points for every advanced deduction and guess
+    pub rating: f32,
+}
+
+impl Difficulty {
+    /// Solves `tile_map` from the safe start tile, or from a guess, and rates it
+    #[must_use]
+    pub fn analyze(tile_map: &TileMap, safe_start: bool) -> Self {
+        let mut view = BoardView::covered(tile_map);
+        let mut guesses = 0;
+        let mut survival = 1.;
+        let mut techniques = BTreeMap::new();
+        let start = if safe_start {
+            first_empty(tile_map)
+        } else {
+            None
+        };
+        if let Some(start) = start {
+            reveal(&mut view, tile_map, start);
+        }
+        while view
+            .covered_tiles()
+            .iter()
+            .any(|coords| !tile_map.is_bomb_at(*coords))
+        {
+            if let Some(deduction) = find_deduction(&view) {
+                *techniques.entry(deduction.technique).or_insert(0) += 1;
+                for coords in deduction.mines {
+                    view.set(coords, TileView::Marked);
+                }
+                for coords in deduction.safe {
+                    reveal(&mut view, tile_map, coords);
+                }
+                continue;
+            }
+            let (coords, probability) = match MineProbabilities::compute(&view)
+                .and_then(|probabilities| probabilities.safest())
+            {
+                Some(guess) => guess,
+                None => break,
+            };
+            guesses += 1;
+            survival *= 1. - probability;
+            // A bomb found by a failed guess is known from then on
+            if tile_map.is_bomb_at(coords) {
+                view.set(coords, TileView::Marked);
+            } else {
+                reveal(&mut view, tile_map, coords);
+            }
+        }
+        let bbbv = bbbv(tile_map);
+        let rating = bbbv as f32
+            + techniques
+                .iter()
+                .map(|(technique, count)| technique_points(*technique) * *count as f32)
+                .sum::<f32>()
+            + guesses as f32 * GUESS_POINTS;
+        Self {
+            guesses,
+            survival,
+            techniques,
+            bbbv,
+            rating,
+        }
+    }
+
+    /// Most advanced technique needed, if any
+    #[must_use]
+    pub fn hardest_technique(&self) -> Option<Technique> {
+        self.techniques.keys().next_back().copied()
+    }
+
+    /// Places random bombs on copies of the empty `template` until one is rated in `band`, or
+    /// returns the closest one after too many attempts
+    #[must_use]
+    pub fn generate<R: Rng>(
+        template: &TileMap,
+        bomb_count: u16,
+        safe_start: bool,
+        band: RangeInclusive<f32>,
+        rng: &mut R,
+    ) -> (TileMap, Self) {
+        let distance = |rating: f32| {
+            if rating < *band.start() {
+                band.start() - rating
+            } else {
+                (rating - band.end()).max(0.)
+            }
+        };
+        let mut generate = || {
+            let mut tile_map = template.clone();
+            tile_map.set_bombs_with(bomb_count, rng);
+            let difficulty = Self::analyze(&tile_map, safe_start);
+            (tile_map, difficulty)
+        };
+        let mut best = generate();
+        for _ in 1..MAX_GENERATION_ATTEMPTS {
+            if band.contains(&best.1.rating) {
+                break;
+            }
+            let candidate = generate();
+            if distance(candidate.1.rating) < distance(best.1.rating) {
+                best = candidate;
+            }
+        }
+        best
+    }
+
+    /// Places random bombs on copies of the empty `template` until one can be cleared from the
+    /// safe start without guessing. After too many attempts, returns the one leaving the fewest
+    /// safe tiles to guess, whose `guesses` aren't 0
+    #[must_use]
+    pub fn generate_no_guess<R: Rng>(
+        template: &TileMap,
+        bomb_count: u16,
+        rng: &mut R,
+    ) -> (TileMap, Self) {
+        let mut best: Option<(TileMap, usize)> = None;
+        for _ in 0..MAX_NO_GUESS_ATTEMPTS {
+            let mut tile_map = template.clone();
+            tile_map.set_bombs_with(bomb_count, rng);
+            let unsolved = unsolved_tiles(&tile_map);
+            if best.as_ref().is_none_or(|(_, fewest)| unsolved < *fewest) {
+                best = Some((tile_map, unsolved));
+            }
+            if unsolved == 0 {
+                break;
+            }
+        }
+        // Rating the rejected boards is slow, only the kept one is rated
+        let (tile_map, _) = best.expect("at least one board is generated");
+        let difficulty = Self::analyze(&tile_map, true);
+        (tile_map, difficulty)
+    }
+}
+
+/// Can `tile_map` be cleared from its safe start tile with deductions only
+#[must_use]
+pub fn is_no_guess(tile_map: &TileMap) -> bool {
+    unsolved_tiles(tile_map) == 0
+}
+
+/// Safe tiles still covered once the deductions from the safe start tile run out, every safe
+/// tile without a safe start tile
+fn unsolved_tiles(tile_map: &TileMap) -> usize {
+    let mut view = BoardView::covered(tile_map);
+    let unsolved = |view: &BoardView| {
+        view.covered_tiles()
+            .iter()
+            .filter(|coords| !tile_map.is_bomb_at(**coords))
+            .count()
+    };
+    match first_empty(tile_map) {
+        Some(start) => reveal(&mut view, tile_map, start),
+        None => return unsolved(&view),
+    }
+    loop {
+        let left = unsolved(&view);
+        if left == 0 {
+            return 0;
+        }
+        let deduction = match find_deduction(&view) {
+            Some(deduction) => deduction,
+            None => return left,
+        };
+        for coords in deduction.mines {
+            view.set(coords, TileView::Marked);
+        }
+        for coords in deduction.safe {
+            reveal(&mut view, tile_map, coords);
+        }
+    }
+}
+
+/// Tile uncovered by the safe start
+fn first_empty(tile_map: &TileMap) -> Option<Coordinates> {
+    tile_map.rows().enumerate().find_map(|(y, line)| {
+        line.iter()
+            .position(|tile| *tile == Tile::Empty)
+            .map(|x| Coordinates {
+                x: x as u16,
+                y: y as u16,
+            })
+    })
+}
+
+/// Uncovers `coords` on `view`, flood filling the empty tiles
+fn reveal(view: &mut BoardView, tile_map: &TileMap, coords: Coordinates) {
+    let mut stack = vec![coords];
+    while let Some(coords) = stack.pop() {
+        if view.get(coords) != TileView::Covered {
+            continue;
+        }
+        let count = tile_map.bomb_count_at(coords);
+        view.set(coords, TileView::Revealed(count));
+        if count == 0 {
+            stack.extend(view.neighbors(coords));
+        }
+    }
+}
+
+/// Bechtel's Board Benchmark Value of `tile_map`: its openings, each cleared by a single click,
+/// plus the numbers which aren't on an opening border
+#[must_use]
+pub fn bbbv(tile_map: &TileMap) -> u32 {
+    let view = BoardView::covered(tile_map);
+    let mut cleared = HashSet::new();
+    let mut clicks = 0;
+    for coords in view.coordinates() {
+        if cleared.contains(&coords) || *tile_map.get_tile(coords) != Tile::Empty {
+            continue;
+        }
+        clicks += 1;
+        let mut stack = vec![coords];
+        while let Some(coords) = stack.pop() {
+            if !cleared.insert(coords) {
+                continue;
+            }
+            if *tile_map.get_tile(coords) == Tile::Empty {
+                stack.extend(view.neighbors(coords));
+            }
+        }
+    }
+    clicks
+        + view
+            .coordinates()
+            .filter(|coords| {
+                !cleared.contains(coords)
+                    && matches!(tile_map.get_tile(*coords), Tile::BombNeighbor(_))
+            })
+            .count() as u32
+}
@@ -0,0 +1,93 @@
+use crate::components::Coordinates;
+use crate::solver::{find_deduction, BoardView, MineProbabilities, Technique, TileView};
+use crate::Board;
+
+/// What the player should do with the hinted tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HintKind {
+    /// The tile is proven safe
+    Safe,
+    /// The tile is proven to be a bomb
+    Bomb,
+    /// No deduction is possible, the tile is the best guess
+    Guess,
+    /// The tile is one of the marks exceeding the number of an uncovered neighbor, at least one of
+    /// them isn't a bomb
+    WrongMark,
+}
+
+/// Hint on the next move
+#[derive(Debug, Clone)]
+pub struct Hint {
+    /// Hinted tile
+    pub coords: Coordinates,
+    pub kind: HintKind,
+    /// Technique of the deduction, if any
+    pub technique: Option<Technique>,
+    /// Uncovered tiles justifying the hint
+    pub reasons: Vec<Coordinates>,
+    /// Short human readable explanation
+    pub explanation: String,
+}
+
+/// Hint displayed on the board. Must be used as a resource
+#[derive(Debug, Clone, Default)]
+pub struct ActiveHint(pub Option<Hint>);
+
+/// Finds a tile which can be proven safe or a bomb from the visible board, or the best guess
+/// when no deduction exists. Multi-mine boards have no hint, the solver only knows single bomb
+/// tiles
+#[must_use]
+pub fn find_hint(board: &Board) -> Option<Hint> {
+    if board.tile_map.max_mines_per_cell() > 1 {
+        return None;
+    }
+    let view = BoardView::from_board(board);
+    // Deductions trust the marks, so marks contradicting a number are pointed out first. Exploded
+    // bombs are seen as marks too, but are never wrong
+    if let Some((source, marked)) = view.overmarked() {
+        let coords = marked
+            .iter()
+            .copied()
+            .find(|coords| board.marked_tiles.contains_key(coords))?;
+        let count = match view.get(source) {
+            TileView::Revealed(count) => count,
+            _ => 0,
+        };
+        return Some(Hint {
+            coords,
+            kind: HintKind::WrongMark,
+            technique: None,
+            reasons: vec![source],
+            explanation: format!(
+                "this {} touches {} flags, at least one of them is wrong",
+                count,
+                marked.len()
+            ),
+        });
+    }
+    if let Some(deduction) = find_deduction(&view) {
+        let (coords, kind) = match deduction.safe.first() {
+            Some(coords) => (*coords, HintKind::Safe),
+            None => (*deduction.mines.first()?, HintKind::Bomb),
+        };
+        return Some(Hint {
+            coords,
+            kind,
+            technique: Some(deduction.technique),
+            reasons: deduction.reasons,
+            explanation: deduction.explanation,
+        });
+    }
+    let (coords, probability) = MineProbabilities::compute(&view)?.safest()?;
+    Some(Hint {
+        coords,
+        kind: HintKind::Guess,
+        technique: None,
+        reasons: Vec::new(),
+        explanation: format!(
+            "no safe move, this tile has the lowest bomb chance ({:.0}%)",
+            probability * 100.
+        ),
+    })
+}
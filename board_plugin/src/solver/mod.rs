@@ -0,0 +1,14 @@
+pub use {
+    deduction::*,
+    difficulty::{bbbv, is_no_guess, Difficulty},
+    hint::*,
+    probability::{MineProbabilities, ProbabilityCache, ProbabilityOverlay},
+    view::*,
+};
+
+mod deduction;
+mod difficulty;
+mod hint;
+mod monte_carlo;
+mod probability;
+mod view;
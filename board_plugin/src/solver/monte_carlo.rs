@@ -0,0 +1,261 @@
+use crate::components::Coordinates;
+use crate::solver::BoardView;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Number of sampled bomb configurations
+const SAMPLES: usize = 1_000;
+/// Maximum number of sampling attempts, large frontiers are rarely in a valid configuration
+const MAX_SAMPLING_ROUNDS: usize = 10 * SAMPLES;
+/// Temperature of the walk while sampling. Colder walks find more valid configurations but
+/// explore them slower
+const SAMPLING_TEMPERATURE: f64 = 0.3;
+/// Maximum number of moves when searching for a first configuration matching the view
+const MAX_SEARCH_MOVES: usize = 1_000_000;
+/// Annealing temperatures of the search, from the first to the last move
+const TEMPERATURES: (f64, f64) = (2.0, 0.05);
+/// Probability of a random move during the search, the other moves fix a broken constraint
+const SEARCH_NOISE: f64 = 0.2;
+
+/// Estimates the bomb probability of the covered and unmarked tiles of `view` by sampling the bomb
+/// configurations matching it.
+///
+/// The bombs are moved one at a time to another covered tile, with a Metropolis acceptance on the
+/// constraint errors. Every matching configuration has no error, so they are all sampled with the
+/// same probability. The first matching configuration is searched by annealing, mostly moving
+/// bombs in and out of the broken constraints.
+///
+/// Returns `None` if no matching configuration is found
+pub(crate) fn sample(view: &BoardView, rng: &mut impl Rng) -> Option<HashMap<Coordinates, f32>> {
+    let cells = view.covered_tiles();
+    let remaining_mines = view.remaining_mines();
+    if remaining_mines < 0 || remaining_mines as usize > cells.len() {
+        return None;
+    }
+    let mut walk = Walk::new(view, &cells, remaining_mines as usize, rng);
+    if !walk.search(rng) {
+        return None;
+    }
+    // Single moves rarely keep every constraint satisfied, so the walk goes through invalid
+    // configurations and only the valid ones are counted
+    let mut hits = vec![0usize; cells.len()];
+    let mut samples = 0;
+    for _ in 0..MAX_SAMPLING_ROUNDS {
+        // A move per tile between two samples decorrelates them
+        for _ in 0..cells.len() {
+            let (from, to) = walk.random_move(rng);
+            walk.try_move(rng, from, to, SAMPLING_TEMPERATURE);
+        }
+        if walk.error > 0 {
+            continue;
+        }
+        for cell in walk.bombs.iter() {
+            hits[*cell] += 1;
+        }
+        samples += 1;
+        if samples == SAMPLES {
+            break;
+        }
+    }
+    if samples == 0 {
+        return None;
+    }
+    // Tiles without any constraint all have the same probability, averaging them reduces the noise
+    let interior: Vec<usize> = (0..cells.len())
+        .filter(|i| walk.cell_constraints[*i].is_empty())
+        .collect();
+    let interior_p = match interior.len() {
+        0 => 0.,
+        n => interior.iter().map(|i| hits[*i]).sum::<usize>() as f32 / (n * samples) as f32,
+    };
+    Some(
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let p = if walk.cell_constraints[i].is_empty() {
+                    interior_p
+                } else {
+                    hits[i] as f32 / samples as f32
+                };
+                (*cell, p)
+            })
+            .collect(),
+    )
+}
+
+/// Random walk through the bomb configurations of the covered tiles, with a fixed bomb count
+struct Walk {
+    /// Constraint indexes of each cell
+    cell_constraints: Vec<Vec<usize>>,
+    /// Cells of each constraint
+    constraint_cells: Vec<Vec<usize>>,
+    /// Bomb count required by each constraint
+    required: Vec<i32>,
+    /// Bomb count of each constraint in the current configuration
+    counts: Vec<i32>,
+    /// Sum of the constraint errors, the configuration matches the view at 0
+    error: i32,
+    /// Constraints with an error
+    broken: Vec<usize>,
+    /// Cells holding a bomb
+    bombs: Vec<usize>,
+    /// Cells without bomb
+    empties: Vec<usize>,
+    /// Index of each cell in `bombs` or `empties`
+    slots: Vec<usize>,
+    is_bomb: Vec<bool>,
+}
+
+impl Walk {
+    /// Starts from a random configuration
+    fn new(view: &BoardView, cells: &[Coordinates], mines: usize, rng: &mut impl Rng) -> Self {
+        let cell_index: HashMap<Coordinates, usize> =
+            cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+        let constraints = view.constraints();
+        let mut cell_constraints = vec![Vec::new(); cells.len()];
+        let mut constraint_cells = Vec::with_capacity(constraints.len());
+        for (i, constraint) in constraints.iter().enumerate() {
+            let indexes: Vec<usize> = constraint.cells.iter().map(|c| cell_index[c]).collect();
+            for cell in indexes.iter() {
+                cell_constraints[*cell].push(i);
+            }
+            constraint_cells.push(indexes);
+        }
+        let mut order: Vec<usize> = (0..cells.len()).collect();
+        order.shuffle(rng);
+        let empties = order.split_off(mines);
+        let bombs = order;
+        let mut slots = vec![0; cells.len()];
+        let mut is_bomb = vec![false; cells.len()];
+        for (i, cell) in bombs.iter().enumerate() {
+            slots[*cell] = i;
+            is_bomb[*cell] = true;
+        }
+        for (i, cell) in empties.iter().enumerate() {
+            slots[*cell] = i;
+        }
+        let mut counts = vec![0; constraints.len()];
+        for cell in bombs.iter() {
+            for c in cell_constraints[*cell].iter() {
+                counts[*c] += 1;
+            }
+        }
+        let required: Vec<i32> = constraints.iter().map(|c| c.mines as i32).collect();
+        let broken: Vec<usize> = (0..counts.len())
+            .filter(|c| counts[*c] != required[*c])
+            .collect();
+        let error = broken
+            .iter()
+            .map(|c| (counts[*c] - required[*c]).abs())
+            .sum();
+        Self {
+            cell_constraints,
+            constraint_cells,
+            required,
+            counts,
+            error,
+            broken,
+            bombs,
+            empties,
+            slots,
+            is_bomb,
+        }
+    }
+
+    /// Anneals toward a configuration matching the view, returns `false` if none is found
+    fn search(&mut self, rng: &mut impl Rng) -> bool {
+        let (hot, cold) = TEMPERATURES;
+        let cooling = (cold / hot).powf(1. / MAX_SEARCH_MOVES as f64);
+        let mut temperature = hot;
+        for _ in 0..MAX_SEARCH_MOVES {
+            if self.error == 0 {
+                return true;
+            }
+            let (from, to) = if rng.gen_bool(SEARCH_NOISE) {
+                self.random_move(rng)
+            } else {
+                self.fixing_move(rng)
+            };
+            self.try_move(rng, from, to, temperature);
+            temperature *= cooling;
+        }
+        self.error == 0
+    }
+
+    /// A random bomb and a random empty cell
+    fn random_move(&self, rng: &mut impl Rng) -> (usize, usize) {
+        match (self.bombs.choose(rng), self.empties.choose(rng)) {
+            (Some(from), Some(to)) => (*from, *to),
+            // Every cell or none holds a bomb, there is nothing to move
+            _ => (0, 0),
+        }
+    }
+
+    /// Moves a bomb out of or into a random broken constraint
+    fn fixing_move(&self, rng: &mut impl Rng) -> (usize, usize) {
+        let constraint = match self.broken.choose(rng) {
+            Some(c) => *c,
+            None => return self.random_move(rng),
+        };
+        let too_many = self.counts[constraint] > self.required[constraint];
+        let candidates: Vec<usize> = self.constraint_cells[constraint]
+            .iter()
+            .filter(|cell| self.is_bomb[**cell] == too_many)
+            .copied()
+            .collect();
+        let (random_from, random_to) = self.random_move(rng);
+        match (candidates.choose(rng), too_many) {
+            (Some(from), true) => (*from, random_to),
+            (Some(to), false) => (random_from, *to),
+            (None, _) => (random_from, random_to),
+        }
+    }
+
+    /// Moves the bomb of `from` to the empty cell `to`. Worse moves are accepted with a
+    /// probability decreasing with `temperature`
+    fn try_move(&mut self, rng: &mut impl Rng, from: usize, to: usize, temperature: f64) {
+        if !self.is_bomb[from] || self.is_bomb[to] {
+            return;
+        }
+        let delta = self.move_bomb(from, to);
+        if delta > 0 && rng.gen::<f64>() >= (-delta as f64 / temperature).exp() {
+            self.move_bomb(to, from);
+        }
+    }
+
+    /// Moves the bomb of `from` to the empty cell `to`, returns the error change
+    fn move_bomb(&mut self, from: usize, to: usize) -> i32 {
+        let delta = self.shift(from, to);
+        let (bomb_slot, empty_slot) = (self.slots[from], self.slots[to]);
+        self.bombs[bomb_slot] = to;
+        self.empties[empty_slot] = from;
+        self.slots[to] = bomb_slot;
+        self.slots[from] = empty_slot;
+        self.is_bomb[from] = false;
+        self.is_bomb[to] = true;
+        delta
+    }
+
+    /// Moves a bomb from `from` to `to` in the constraint counts, returns the error change
+    fn shift(&mut self, from: usize, to: usize) -> i32 {
+        let before = self.error;
+        for (cell, change) in [(from, -1), (to, 1)] {
+            for c in self.cell_constraints[cell].iter() {
+                let required = self.required[*c];
+                let old = (self.counts[*c] - required).abs();
+                self.counts[*c] += change;
+                let new = (self.counts[*c] - required).abs();
+                self.error += new - old;
+                if old == 0 {
+                    self.broken.push(*c);
+                } else if new == 0 {
+                    let index = self.broken.iter().position(|b| b == c).unwrap();
+                    self.broken.swap_remove(index);
+                }
+            }
+        }
+        self.error - before
+    }
+}
@@ -0,0 +1,518 @@
+use crate::components::Coordinates;
+use crate::solver::{monte_carlo, BoardView, Constraint};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maximum number of search steps when enumerating the bomb configurations of a component
+const MAX_ENUMERATION_STEPS: usize = 1_000_000;
+
+/// Covered tiles linked together by shared constraints, with all their valid bomb configurations
+#[derive(Debug, Clone)]
+pub(crate) struct Component {
+    pub cells: Vec<Coordinates>,
+    /// Indexes of the constraints on `cells`
+    pub constraints: Vec<usize>,
+    /// `solutions[k]` is the number of valid configurations with `k` bombs
+    pub solutions: Vec<f64>,
+    /// `cell_mines[k][i]` is the number of valid configurations with `k` bombs where `cells[i]` is a bomb
+    pub cell_mines: Vec<Vec<f64>>,
+}
+
+impl Component {
+    /// Enumerates every bomb configuration of `cells`, returns `None` if there are too many
+    fn enumerate(
+        cells: Vec<Coordinates>,
+        constraint_indexes: Vec<usize>,
+        constraints: &[Constraint],
+    ) -> Option<Self> {
+        let cell_index: HashMap<Coordinates, usize> =
+            cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+        let mut cell_constraints = vec![Vec::new(); cells.len()];
+        let mut mines_left = Vec::with_capacity(constraint_indexes.len());
+        let mut unassigned = Vec::with_capacity(constraint_indexes.len());
+        for (local, index) in constraint_indexes.iter().enumerate() {
+            let constraint = &constraints[*index];
+            for cell in constraint.cells.iter() {
+                cell_constraints[cell_index[cell]].push(local);
+            }
+            mines_left.push(constraint.mines as i32);
+            unassigned.push(constraint.cells.len() as i32);
+        }
+        let mut search = Search {
+            cell_constraints,
+            mines_left,
+            unassigned,
+            assignment: vec![false; cells.len()],
+            steps: 0,
+            solutions: vec![0.; cells.len() + 1],
+            cell_mines: vec![vec![0.; cells.len()]; cells.len() + 1],
+        };
+        if !search.run(0) {
+            return None;
+        }
+        Some(Self {
+            cells,
+            constraints: constraint_indexes,
+            solutions: search.solutions,
+            cell_mines: search.cell_mines,
+        })
+    }
+}
+
+/// Backtracking state of a component enumeration
+struct Search {
+    cell_constraints: Vec<Vec<usize>>,
+    mines_left: Vec<i32>,
+    unassigned: Vec<i32>,
+    assignment: Vec<bool>,
+    steps: usize,
+    solutions: Vec<f64>,
+    cell_mines: Vec<Vec<f64>>,
+}
+
+impl Search {
+    /// Assigns the cells from `cell`, returns `false` if the step budget is exhausted
+    fn run(&mut self, cell: usize) -> bool {
+        self.steps += 1;
+        if self.steps > MAX_ENUMERATION_STEPS {
+            return false;
+        }
+        if cell == self.assignment.len() {
+            let k = self.assignment.iter().filter(|a| **a).count();
+            self.solutions[k] += 1.;
+            for (i, _) in self.assignment.iter().enumerate().filter(|(_, a)| **a) {
+                self.cell_mines[k][i] += 1.;
+            }
+            return true;
+        }
+        for bomb in [false, true] {
+            let valid = self.cell_constraints[cell].iter().all(|c| {
+                let mines_left = self.mines_left[*c] - bomb as i32;
+                mines_left >= 0 && mines_left < self.unassigned[*c]
+            });
+            if !valid {
+                continue;
+            }
+            self.assign(cell, bomb, 1);
+            let completed = self.run(cell + 1);
+            self.assign(cell, bomb, -1);
+            if !completed {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Applies (`sign` = 1) or reverts (`sign` = -1) a cell assignment on its constraints
+    fn assign(&mut self, cell: usize, bomb: bool, sign: i32) {
+        self.assignment[cell] = bomb && sign > 0;
+        for c in self.cell_constraints[cell].iter() {
+            self.mines_left[*c] -= sign * bomb as i32;
+            self.unassigned[*c] -= sign;
+        }
+    }
+}
+
+/// Enumerated components of previous analyses, keyed by their constraints.
+///
+/// Uncovering or marking a tile only changes the components around it, the others are reused
+#[derive(Debug, Clone, Default)]
+pub struct ProbabilityCache {
+    /// `None` for the components too large to be enumerated
+    components: HashMap<Vec<Constraint>, Option<Component>>,
+}
+
+/// Split of the covered tiles of a view: constrained components and unconstrained interior
+#[derive(Debug, Clone)]
+pub(crate) struct Frontier {
+    pub constraints: Vec<Constraint>,
+    pub components: Vec<Component>,
+    /// Covered tiles without any uncovered neighbor
+    pub interior: Vec<Coordinates>,
+    /// Bombs left once the marked tiles are deduced
+    pub remaining_mines: usize,
+}
+
+impl Frontier {
+    /// Analyzes `view`, returns `None` if the marks exceed the bomb count or if a component is too
+    /// large to be enumerated
+    pub fn analyze(view: &BoardView) -> Option<Self> {
+        Self::analyze_cached(view, &mut ProbabilityCache::default())
+    }
+
+    /// Analyzes `view`, only enumerating the components missing from `cache`. The components
+    /// which are not part of `view` anymore are removed from `cache`
+    pub fn analyze_cached(view: &BoardView, cache: &mut ProbabilityCache) -> Option<Self> {
+        let remaining_mines = view.remaining_mines();
+        if remaining_mines < 0 {
+            return None;
+        }
+        let constraints = view.constraints();
+        let mut cell_constraints: HashMap<Coordinates, Vec<usize>> = HashMap::new();
+        for (i, constraint) in constraints.iter().enumerate() {
+            for cell in constraint.cells.iter() {
+                cell_constraints.entry(*cell).or_default().push(i);
+            }
+        }
+        let mut visited = HashSet::with_capacity(cell_constraints.len());
+        let mut components = Vec::new();
+        let mut keys = HashSet::new();
+        let mut too_large = false;
+        for start in view.covered_tiles() {
+            if !cell_constraints.contains_key(&start) || !visited.insert(start) {
+                continue;
+            }
+            // Breadth first search through shared constraints keeps linked cells close, which
+            // prunes the enumeration early
+            let mut cells = Vec::new();
+            let mut indexes = HashSet::new();
+            let mut queue = VecDeque::from([start]);
+            while let Some(cell) = queue.pop_front() {
+                cells.push(cell);
+                for index in cell_constraints[&cell].iter() {
+                    if !indexes.insert(*index) {
+                        continue;
+                    }
+                    for neighbor in constraints[*index].cells.iter() {
+                        if visited.insert(*neighbor) {
+                            queue.push_back(*neighbor);
+                        }
+                    }
+                }
+            }
+            let mut indexes: Vec<usize> = indexes.into_iter().collect();
+            indexes.sort_unstable();
+            let key: Vec<Constraint> = indexes.iter().map(|i| constraints[*i].clone()).collect();
+            let component = match cache.components.get(&key) {
+                // Same constraints means same cells and configurations
+                Some(component) => component.clone().map(|component| Component {
+                    constraints: indexes,
+                    ..component
+                }),
+                None => {
+                    let component = Component::enumerate(cells, indexes, &constraints);
+                    cache.components.insert(key.clone(), component.clone());
+                    component
+                }
+            };
+            keys.insert(key);
+            match component {
+                Some(component) => components.push(component),
+                None => too_large = true,
+            }
+        }
+        cache.components.retain(|key, _| keys.contains(key));
+        if too_large {
+            return None;
+        }
+        let interior = view
+            .covered_tiles()
+            .into_iter()
+            .filter(|c| !cell_constraints.contains_key(c))
+            .collect();
+        Some(Self {
+            constraints,
+            components,
+            interior,
+            remaining_mines: remaining_mines as usize,
+        })
+    }
+
+    /// Can the frontier hold `frontier_mines` bombs, the others going in the interior
+    fn feasible(&self, frontier_mines: usize) -> bool {
+        frontier_mines <= self.remaining_mines
+            && self.remaining_mines - frontier_mines <= self.interior.len()
+    }
+
+    /// Distribution of the frontier bomb count of every component but `skip`, each component
+    /// being scaled down to avoid overflows
+    fn distribution(&self, skip: Option<usize>) -> Vec<f64> {
+        let mut distribution = vec![1.];
+        for (i, component) in self.components.iter().enumerate() {
+            if Some(i) == skip {
+                continue;
+            }
+            let max = component.solutions.iter().cloned().fold(0., f64::max);
+            if max == 0. {
+                return vec![0.];
+            }
+            let mut next = vec![0.; distribution.len() + component.solutions.len() - 1];
+            for (a, da) in distribution.iter().enumerate() {
+                for (b, db) in component.solutions.iter().enumerate() {
+                    next[a + b] += da * db / max;
+                }
+            }
+            distribution = next;
+        }
+        distribution
+    }
+
+    /// Relative weight of each interior bomb count, as `C(interior, bombs)` scaled down
+    fn interior_weights(&self) -> Vec<f64> {
+        let n = self.interior.len();
+        let mut ln_combinations = Vec::with_capacity(n + 1);
+        let mut ln = 0f64;
+        ln_combinations.push(ln);
+        for k in 1..=n {
+            ln += ((n - k + 1) as f64 / k as f64).ln();
+            ln_combinations.push(ln);
+        }
+        let max = ln_combinations.iter().cloned().fold(f64::MIN, f64::max);
+        ln_combinations.iter().map(|l| (l - max).exp()).collect()
+    }
+
+    /// Weight of the configurations with `frontier_mines` bombs in the frontier
+    fn weight(&self, interior_weights: &[f64], frontier_mines: usize) -> f64 {
+        if self.feasible(frontier_mines) {
+            interior_weights[self.remaining_mines - frontier_mines]
+        } else {
+            0.
+        }
+    }
+
+    /// Bomb probability of every covered tile, `None` if no configuration matches the view
+    pub fn probabilities(&self) -> Option<HashMap<Coordinates, f32>> {
+        let interior_weights = self.interior_weights();
+        let total = self.distribution(None);
+        let mut norm = 0.;
+        let mut interior_mines = 0.;
+        for (t, d) in total.iter().enumerate() {
+            let w = d * self.weight(&interior_weights, t);
+            norm += w;
+            if w > 0. {
+                interior_mines += w * (self.remaining_mines - t) as f64;
+            }
+        }
+        if norm <= 0. {
+            return None;
+        }
+        let mut probabilities = HashMap::new();
+        if !self.interior.is_empty() {
+            let p = (interior_mines / norm / self.interior.len() as f64) as f32;
+            probabilities.extend(self.interior.iter().map(|c| (*c, p)));
+        }
+        for (j, component) in self.components.iter().enumerate() {
+            let others = self.distribution(Some(j));
+            let max = component.solutions.iter().cloned().fold(0., f64::max);
+            for (i, cell) in component.cells.iter().enumerate() {
+                let mut p = 0.;
+                for (k, mines) in component.cell_mines.iter().enumerate() {
+                    if mines[i] == 0. {
+                        continue;
+                    }
+                    let others_weight: f64 = others
+                        .iter()
+                        .enumerate()
+                        .map(|(t, d)| d * self.weight(&interior_weights, k + t))
+                        .sum();
+                    p += mines[i] / max * others_weight;
+                }
+                probabilities.insert(*cell, (p / norm) as f32);
+            }
+        }
+        Some(probabilities)
+    }
+
+    /// Bomb counts of component `j` compatible with the other components and the bomb count
+    fn feasible_counts(&self, j: usize) -> Vec<usize> {
+        let others = self.distribution(Some(j));
+        self.components[j]
+            .solutions
+            .iter()
+            .enumerate()
+            .filter(|(k, s)| {
+                **s > 0.
+                    && others
+                        .iter()
+                        .enumerate()
+                        .any(|(t, d)| *d > 0. && self.feasible(k + t))
+            })
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    /// Finds a component where some tiles are safe or bombs in every configuration. With
+    /// `global`, only the configurations matching the remaining bomb count are considered.
+    ///
+    /// Returns the safe tiles, the bomb tiles and the constraint sources of the component
+    pub fn certainties(&self, global: bool) -> Option<CertainTiles> {
+        for (j, component) in self.components.iter().enumerate() {
+            let counts: Vec<usize> = if global {
+                self.feasible_counts(j)
+            } else {
+                (0..component.solutions.len())
+                    .filter(|k| component.solutions[*k] > 0.)
+                    .collect()
+            };
+            if counts.is_empty() {
+                continue;
+            }
+            let mut certain = CertainTiles::default();
+            for (i, cell) in component.cells.iter().enumerate() {
+                if counts.iter().all(|k| component.cell_mines[*k][i] == 0.) {
+                    certain.safe.push(*cell);
+                } else if counts
+                    .iter()
+                    .all(|k| component.cell_mines[*k][i] == component.solutions[*k])
+                {
+                    certain.mines.push(*cell);
+                }
+            }
+            if !certain.is_empty() {
+                certain.reasons = component
+                    .constraints
+                    .iter()
+                    .map(|i| self.constraints[*i].source)
+                    .collect();
+                return Some(certain);
+            }
+        }
+        if !global || self.interior.is_empty() {
+            return None;
+        }
+        // Interior tiles are certain when every feasible frontier count leaves the same answer
+        let total = self.distribution(None);
+        let counts: Vec<usize> = (0..total.len())
+            .filter(|t| total[*t] > 0. && self.feasible(*t))
+            .collect();
+        let mut certain = CertainTiles::default();
+        if !counts.is_empty() && counts.iter().all(|t| self.remaining_mines == *t) {
+            certain.safe = self.interior.clone();
+        } else if !counts.is_empty()
+            && counts
+                .iter()
+                .all(|t| self.remaining_mines - t == self.interior.len())
+        {
+            certain.mines = self.interior.clone();
+        }
+        if certain.is_empty() {
+            None
+        } else {
+            Some(certain)
+        }
+    }
+}
+
+/// Tiles proven safe or bombs, with the uncovered tiles proving it
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CertainTiles {
+    pub safe: Vec<Coordinates>,
+    pub mines: Vec<Coordinates>,
+    pub reasons: Vec<Coordinates>,
+}
+
+impl CertainTiles {
+    fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// Bomb probability of the covered and unmarked tiles of a view
+#[derive(Debug, Clone, Default)]
+pub struct MineProbabilities {
+    probabilities: HashMap<Coordinates, f32>,
+    exact: bool,
+}
+
+impl MineProbabilities {
+    /// Computes the exact probabilities by enumerating the frontier configurations. If the
+    /// frontier is too large the probabilities are estimated by sampling bomb configurations, or
+    /// locally as a last resort. The sampling is seeded from the view, the same view always gets
+    /// the same estimation.
+    ///
+    /// Returns `None` if no bomb configuration matches the view
+    pub fn compute(view: &BoardView) -> Option<Self> {
+        Self::compute_cached(view, &mut ProbabilityCache::default())
+    }
+
+    /// Same as `compute`, reusing and updating the component enumerations of `cache`
+    pub fn compute_cached(view: &BoardView, cache: &mut ProbabilityCache) -> Option<Self> {
+        if let Some(frontier) = Frontier::analyze_cached(view, cache) {
+            return Some(Self {
+                probabilities: frontier.probabilities()?,
+                exact: true,
+            });
+        }
+        Some(Self::sampled(view).unwrap_or_else(|| Self {
+            probabilities: Self::estimate(view),
+            exact: false,
+        }))
+    }
+
+    /// Estimates the probabilities by sampling bomb configurations, even when they could be
+    /// enumerated. The sampling is seeded from the view.
+    ///
+    /// Returns `None` if no bomb configuration matching the view is found
+    pub fn sampled(view: &BoardView) -> Option<Self> {
+        let mut rng = StdRng::seed_from_u64(view.seed());
+        Some(Self {
+            probabilities: monte_carlo::sample(view, &mut rng)?,
+            exact: false,
+        })
+    }
+
+    /// Local estimation: the highest bomb density of the surrounding constraints, or the global
+    /// density for unconstrained tiles
+    fn estimate(view: &BoardView) -> HashMap<Coordinates, f32> {
+        let covered = view.covered_tiles();
+        let density = view.remaining_mines().max(0) as f32 / covered.len().max(1) as f32;
+        let mut probabilities: HashMap<Coordinates, f32> = HashMap::new();
+        for constraint in view.constraints() {
+            let p = constraint.mines as f32 / constraint.cells.len() as f32;
+            for cell in constraint.cells {
+                let entry = probabilities.entry(cell).or_insert(p);
+                *entry = entry.max(p);
+            }
+        }
+        for cell in covered {
+            probabilities.entry(cell).or_insert(density);
+        }
+        probabilities
+    }
+
+    /// Bomb probability of `coords`, `None` for uncovered or marked tiles
+    #[must_use]
+    pub fn get(&self, coords: Coordinates) -> Option<f32> {
+        self.probabilities.get(&coords).copied()
+    }
+
+    /// Are the probabilities exact or estimated
+    #[inline]
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, f32)> + '_ {
+        self.probabilities.iter().map(|(c, p)| (*c, *p))
+    }
+
+    /// Tile with the lowest bomb probability, the lowest coordinates first on ties
+    #[must_use]
+    pub fn safest(&self) -> Option<(Coordinates, f32)> {
+        self.iter().min_by(|(ca, pa), (cb, pb)| {
+            pa.partial_cmp(pb)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(ca.cmp(cb))
+        })
+    }
+}
+
+/// Mine probability overlay of the covered tiles. Must be used as a resource
+#[derive(Debug, Clone, Default)]
+pub struct ProbabilityOverlay {
+    /// Is the overlay displayed
+    pub enabled: bool,
+    /// Probabilities of the current board, `None` until computed
+    pub probabilities: Option<MineProbabilities>,
+    pub(crate) cache: ProbabilityCache,
+}
+
+impl ProbabilityOverlay {
+    /// Forgets the probabilities of the previous board
+    pub fn reset(&mut self) {
+        self.probabilities = None;
+        self.cache = ProbabilityCache::default();
+    }
+}
@@ -0,0 +1,251 @@
+use crate::components::Coordinates;
+use crate::{Board, Neighborhood, TileMap, Topology};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Tile as seen by the player
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TileView {
+    /// Covered and unmarked tile
+    Covered,
+    /// Covered and marked tile, or an exploded bomb
+    Marked,
+    /// Uncovered tile with its bomb neighbor count
+    Revealed(u8),
+    /// Cell missing from a shaped board
+    Void,
+}
+
+/// Bomb count constraint given by an uncovered tile
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Constraint {
+    /// Uncovered tile giving the constraint
+    pub source: Coordinates,
+    /// Covered and unmarked neighbors, sorted
+    pub cells: Vec<Coordinates>,
+    /// Bombs among `cells`, marked neighbors are already deduced
+    pub mines: u8,
+}
+
+/// Board state as visible by the player, covered bombs are never exposed
+#[derive(Debug, Clone)]
+pub struct BoardView {
+    width: u16,
+    height: u16,
+    bomb_count: u16,
+    topology: Topology,
+    wrap: bool,
+    neighborhood: Neighborhood,
+    tiles: Vec<TileView>,
+}
+
+impl BoardView {
+    /// Generates a completely covered view of `tile_map`
+    #[must_use]
+    pub fn covered(tile_map: &TileMap) -> Self {
+        let (width, height) = (tile_map.width(), tile_map.height());
+        Self {
+            width,
+            height,
+            bomb_count: tile_map.bomb_count(),
+            topology: tile_map.topology(),
+            wrap: tile_map.wrap(),
+            neighborhood: tile_map.neighborhood().clone(),
+            tiles: tile_map
+                .iter()
+                .map(|tile| {
+                    if tile.is_void() {
+                        TileView::Void
+                    } else {
+                        TileView::Covered
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Retrieves what the player sees of `board`
+    #[must_use]
+    pub fn from_board(board: &Board) -> Self {
+        let tile_map = &board.tile_map;
+        let mut view = Self::covered(tile_map);
+        for coords in view.coordinates().collect::<Vec<_>>() {
+            let tile = if tile_map.get_tile(coords).is_void() {
+                TileView::Void
+            } else if board.marked_tiles.contains_key(&coords) {
+                TileView::Marked
+            } else if board.covered_tiles.contains(&coords) {
+                TileView::Covered
+            } else if tile_map.is_bomb_at(coords) {
+                TileView::Marked
+            } else {
+                TileView::Revealed(tile_map.bomb_count_at(coords))
+            };
+            view.set(coords, tile);
+        }
+        view
+    }
+
+    #[inline]
+    fn index(&self, coords: Coordinates) -> usize {
+        coords.y as usize * self.width as usize + coords.x as usize
+    }
+
+    // Getter for `width`
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    // Getter for `height`
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    // Getter for `topology`
+    #[inline]
+    #[must_use]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    // Getter for `wrap`
+    #[inline]
+    #[must_use]
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    // Getter for `neighborhood`
+    #[inline]
+    #[must_use]
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    // Getter for `bomb_count`
+    #[inline]
+    #[must_use]
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn in_bounds(&self, coords: Coordinates) -> bool {
+        coords.x < self.width && coords.y < self.height
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, coords: Coordinates) -> TileView {
+        self.tiles[self.index(coords)]
+    }
+
+    #[inline]
+    pub fn set(&mut self, coords: Coordinates, tile: TileView) {
+        let index = self.index(coords);
+        self.tiles[index] = tile;
+    }
+
+    /// Iterates through every coordinates of the board
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+    }
+
+    /// Iterates through the neighbors of `coords` which are on the board, void tiles excluded
+    pub fn neighbors(&self, coords: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.topology
+            .neighbors(
+                coords,
+                self.neighborhood.deltas(self.topology, coords),
+                (self.width, self.height),
+                self.wrap,
+            )
+            .filter(move |c| self.get(*c) != TileView::Void)
+    }
+
+    /// Bomb count minus the marked tile count
+    #[must_use]
+    pub fn remaining_mines(&self) -> i32 {
+        let marked = self
+            .tiles
+            .iter()
+            .filter(|t| **t == TileView::Marked)
+            .count();
+        self.bomb_count as i32 - marked as i32
+    }
+
+    /// Covered and unmarked tiles
+    #[must_use]
+    pub fn covered_tiles(&self) -> Vec<Coordinates> {
+        self.coordinates()
+            .filter(|c| self.get(*c) == TileView::Covered)
+            .collect()
+    }
+
+    /// Uncovered tile touching more marked tiles than its number, with those marked tiles. Some of
+    /// them are wrong, but the view can't tell which
+    #[must_use]
+    pub fn overmarked(&self) -> Option<(Coordinates, Vec<Coordinates>)> {
+        self.coordinates().find_map(|source| {
+            let count = match self.get(source) {
+                TileView::Revealed(count) => count,
+                _ => return None,
+            };
+            let marked: Vec<Coordinates> = self
+                .neighbors(source)
+                .filter(|c| self.get(*c) == TileView::Marked)
+                .collect();
+            if marked.len() > usize::from(count) {
+                Some((source, marked))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Seed derived from the visible tiles, so that random estimations of a view are reproducible
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.width, self.height, self.bomb_count).hash(&mut hasher);
+        self.tiles.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Constraints of every uncovered tile with covered and unmarked neighbors
+    #[must_use]
+    pub fn constraints(&self) -> Vec<Constraint> {
+        self.coordinates()
+            .filter_map(|source| {
+                let count = match self.get(source) {
+                    TileView::Revealed(count) => count,
+                    _ => return None,
+                };
+                let mut cells = Vec::new();
+                let mut marked = 0;
+                for neighbor in self.neighbors(source) {
+                    match self.get(neighbor) {
+                        TileView::Covered => cells.push(neighbor),
+                        TileView::Marked => marked += 1,
+                        TileView::Revealed(_) | TileView::Void => (),
+                    }
+                }
+                if cells.is_empty() {
+                    return None;
+                }
+                cells.sort();
+                Some(Constraint {
+                    source,
+                    cells,
+                    mines: count.saturating_sub(marked),
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,171 @@
+use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileMarkedEvent, TileUncoveredEvent};
+use crate::{Board, SoundOptions};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Sound played for a board event
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SoundEffect {
+    /// Tiles uncovered by a click
+    Reveal,
+    /// Flood fill uncovering at least `SoundOptions::big_opening` tiles, played once it is over
+    Opening {
+        tiles: usize,
+    },
+    Flag,
+    Unflag,
+    Explosion,
+    Victory,
+}
+
+impl SoundEffect {
+    /// Asset path of the sound
+    #[must_use]
+    pub fn path(self, options: &SoundOptions) -> &str {
+        match self {
+            Self::Reveal => &options.reveal,
+            Self::Opening { .. } => &options.opening,
+            Self::Flag => &options.flag,
+            Self::Unflag => &options.unflag,
+            Self::Explosion => &options.explosion,
+            Self::Victory => &options.victory,
+        }
+    }
+
+    /// Volume of the sound, from 0 to 1
+    #[must_use]
+    pub fn volume(self, options: &SoundOptions) -> f32 {
+        let scale = match self {
+            // From half volume for a just big enough opening, to full volume at 4 times its size
+            Self::Opening { tiles } => {
+                let big_opening = options.big_opening.max(1) as f32;
+                (0.5 + (tiles as f32 / big_opening - 1.) / 6.).clamp(0.5, 1.)
+            }
+            _ => 1.,
+        };
+        options.volume.clamp(0., 1.) * scale
+    }
+}
+
+/// Sound waiting to be played
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct QueuedSound {
+    pub effect: SoundEffect,
+    pub volume: f32,
+}
+
+/// Sounds selected from the board events. Must be used as a resource.
+///
+/// Drained by the plugin when the app has an audio output, and left for the app to read otherwise
+#[derive(Debug, Clone, Default)]
+pub struct SoundQueue(pub Vec<QueuedSound>);
+
+/// Sound selection state, kept between frames
+#[derive(Debug, Default)]
+pub struct SoundState {
+    /// Tiles uncovered since the last click, while a flood fill is running
+    opening: usize,
+    /// A bomb exploded, the other bombs being uncovered silently
+    exploded: bool,
+    /// The board was completed, its bombs being uncovered silently
+    won: bool,
+}
+
+/// Plays sounds on the board events, with the `SoundOptions` resource.
+///
+/// The sounds are only played with the `audio` feature, on apps having an audio output. The
+/// `SoundQueue` resource lists the selected sounds otherwise
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundOptions>()
+            .init_resource::<SoundQueue>()
+            .add_system(queue_sounds);
+        // The audio output is added by the bevy `AudioPlugin`
+        #[cfg(feature = "audio")]
+        {
+            if app.world.contains_resource::<Audio>() {
+                app.add_system(play_sounds);
+            }
+        }
+        log::info!("Loaded Sound Plugin");
+    }
+}
+
+/// Selects the sounds of the board events
+#[allow(clippy::too_many_arguments)]
+pub fn queue_sounds(
+    options: Res<SoundOptions>,
+    board: Option<Res<Board>>,
+    mut state: Local<SoundState>,
+    mut queue: ResMut<SoundQueue>,
+    mut tile_uncovered_evr: EventReader<TileUncoveredEvent>,
+    mut tile_marked_evr: EventReader<TileMarkedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+) {
+    if board.is_some_and(|board| board.is_added()) {
+        *state = SoundState::default();
+    }
+    let mut effects = Vec::new();
+    // The bombs uncovered once the board is completed may explode on the same frame
+    let completed = board_completed_evr.iter().count() > 0;
+    // Uncovering the other bombs sends more explosions
+    if bomb_explosion_evr.iter().count() > 0 && !state.exploded && !state.won && !completed {
+        state.exploded = true;
+        effects.push(SoundEffect::Explosion);
+    }
+    let uncovered = tile_uncovered_evr.iter().count();
+    if state.exploded || state.won {
+        state.opening = 0;
+    } else if uncovered > 0 {
+        if state.opening == 0 {
+            effects.push(SoundEffect::Reveal);
+        }
+        state.opening += uncovered;
+    } else if state.opening > 0 {
+        // The flood fill is over
+        if state.opening >= options.big_opening.max(2) {
+            effects.push(SoundEffect::Opening {
+                tiles: state.opening,
+            });
+        }
+        state.opening = 0;
+    }
+    for TileMarkedEvent(_, count) in tile_marked_evr.iter() {
+        effects.push(match count {
+            0 => SoundEffect::Unflag,
+            _ => SoundEffect::Flag,
+        });
+    }
+    if completed {
+        state.won = true;
+        effects.push(SoundEffect::Victory);
+    }
+    if options.muted {
+        return;
+    }
+    queue
+        .0
+        .extend(effects.into_iter().map(|effect| QueuedSound {
+            effect,
+            volume: effect.volume(&options),
+        }));
+}
+
+/// Plays the queued sounds
+#[cfg(feature = "audio")]
+fn play_sounds(
+    options: Res<SoundOptions>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    mut queue: ResMut<SoundQueue>,
+) {
+    for sound in queue.0.drain(..) {
+        audio.play_with_settings(
+            asset_server.load(sound.effect.path(&options)),
+            PlaybackSettings::ONCE.with_volume(sound.volume),
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::components::{FlagDrop, RevealAnimation, Shockwave};
+use crate::{AnimationOptions, Board, BoardAssets, RevealOrigin};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// Shockwave radius at the end of its animation, in tiles
+const SHOCKWAVE_TILES: f32 = 6.;
+/// Shockwave image size, in pixels
+const SHOCKWAVE_PIXELS: u32 = 64;
+/// Color of the shockwave
+const SHOCKWAVE_COLOR: Color = Color::ORANGE;
+
+/// Progress of an animation, from 0 to 1
+fn progress(elapsed: f32, duration: f32) -> f32 {
+    if duration <= 0. {
+        1.
+    } else {
+        (elapsed / duration).clamp(0., 1.)
+    }
+}
+
+/// Fades and flips the covers of the uncovered tiles, then despawns them
+pub fn animate_reveals(
+    mut commands: Commands,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+    board_assets: Res<BoardAssets>,
+    mut covers: Query<(Entity, &mut RevealAnimation, &mut Sprite, &mut Transform)>,
+) {
+    let alpha = board_assets.covered_tile_material.color.a();
+    for (entity, mut animation, mut sprite, mut transform) in covers.iter_mut() {
+        animation.elapsed += time.delta_seconds();
+        let t = progress(animation.elapsed - animation.delay, options.reveal_duration);
+        if t >= 1. || options.reduced_motion {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        sprite.color.set_a(alpha * (1. - t));
+        transform.scale.x = 1. - t;
+    }
+}
+
+/// Drops the placed flags on their tile cover
+pub fn animate_flags(
+    mut commands: Commands,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+    mut flags: Query<(Entity, &mut FlagDrop, &mut Transform)>,
+) {
+    for (entity, mut drop, mut transform) in flags.iter_mut() {
+        drop.elapsed += time.delta_seconds();
+        let t = if options.reduced_motion {
+            1.
+        } else {
+            progress(drop.elapsed, options.flag_duration)
+        };
+        // Falls faster and faster
+        let remaining = 1. - t * t;
+        transform.translation.y = drop.height * remaining;
+        transform.scale = Vec3::splat(1. + remaining / 2.);
+        if t >= 1. {
+            commands.entity(entity).remove::<FlagDrop>();
+        }
+    }
+}
+
+/// Spawns the shockwave of the exploded bomb
+pub fn spawn_shockwave(
+    mut commands: Commands,
+    options: Res<AnimationOptions>,
+    board: Res<Board>,
+    mut origin: ResMut<RevealOrigin>,
+    images: Option<ResMut<Assets<Image>>>,
+    mut ring: Local<Option<Handle<Image>>>,
+) {
+    let coords = match origin.take_shockwave() {
+        Some(coords) if !options.reduced_motion => coords,
+        _ => return,
+    };
+    if ring.is_none() {
+        if let Some(mut images) = images {
+            *ring = Some(images.add(ring_image()));
+        }
+    }
+    // Apps without image assets draw a square
+    let texture = ring.clone().unwrap_or_default();
+    let position = board
+        .tile_map
+        .topology()
+        .tile_center(coords, board.tile_size);
+    commands.entity(board.entity).with_children(|parent| {
+        parent
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(board.tile_size)),
+                    color: SHOCKWAVE_COLOR,
+                    ..Default::default()
+                },
+                texture,
+                transform: Transform::from_translation(position.extend(6.)),
+                ..Default::default()
+            })
+            .insert(Name::new("Shockwave"))
+            .insert(Shockwave { elapsed: 0. });
+    });
+}
+
+/// Spreads and fades the shockwaves, then despawns them
+pub fn animate_shockwaves(
+    mut commands: Commands,
+    time: Res<Time>,
+    options: Res<AnimationOptions>,
+    mut shockwaves: Query<(Entity, &mut Shockwave, &mut Sprite, &mut Transform)>,
+) {
+    for (entity, mut shockwave, mut sprite, mut transform) in shockwaves.iter_mut() {
+        shockwave.elapsed += time.delta_seconds();
+        let t = progress(shockwave.elapsed, options.explosion_duration);
+        if t >= 1. || options.reduced_motion {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        // Slows down as it spreads
+        let spread = 1. - (1. - t) * (1. - t);
+        transform.scale = Vec3::splat(1. + spread * (2. * SHOCKWAVE_TILES - 1.));
+        sprite.color.set_a(1. - t);
+    }
+}
+
+/// White ring fading towards its center
+fn ring_image() -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: SHOCKWAVE_PIXELS,
+            height: SHOCKWAVE_PIXELS,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let center = SHOCKWAVE_PIXELS as f32 / 2.;
+    for (i, pixel) in image.data.chunks_exact_mut(4).enumerate() {
+        let x = (i as u32 % SHOCKWAVE_PIXELS) as f32 + 0.5 - center;
+        let y = (i as u32 / SHOCKWAVE_PIXELS) as f32 + 0.5 - center;
+        let distance = (x * x + y * y).sqrt() / center;
+        let alpha = if distance > 1. { 0. } else { distance.powi(3) };
+        pixel[3] = (alpha * 255.) as u8;
+    }
+    image
+}
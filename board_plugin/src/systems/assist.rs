@@ -0,0 +1,105 @@
+use crate::components::Coordinates;
+use crate::events::{TileMarkEvent, TileMarkedEvent, TileTriggerEvent};
+use crate::solver::{BoardView, TileView};
+use crate::{AssistLevel, Board, BoardOptions};
+use bevy::log;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Move played by the assist
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AssistAction {
+    /// Marks a covered tile
+    Mark(Coordinates),
+    /// Chords around a satisfied number
+    Reveal(Coordinates),
+}
+
+/// Moves of `level` on `view`
+fn assist_actions(view: &BoardView, level: AssistLevel) -> HashSet<AssistAction> {
+    let mut actions = HashSet::new();
+    for coords in view.coordinates() {
+        let count = match view.get(coords) {
+            TileView::Revealed(count) if count > 0 => count as usize,
+            _ => continue,
+        };
+        let (mut covered, mut marked) = (Vec::new(), 0);
+        for neighbor in view.neighbors(coords) {
+            match view.get(neighbor) {
+                TileView::Covered => covered.push(neighbor),
+                TileView::Marked => marked += 1,
+                TileView::Revealed(_) | TileView::Void => (),
+            }
+        }
+        if covered.is_empty() {
+            continue;
+        }
+        if level.auto_flag() && covered.len() + marked == count {
+            actions.extend(covered.into_iter().map(AssistAction::Mark));
+        } else if level.auto_reveal() && marked == count {
+            actions.insert(AssistAction::Reveal(coords));
+        }
+    }
+    actions
+}
+
+/// Plays the assist moves of the board options once the uncovering is done. Every move is sent
+/// once as a regular tile event, the moves it unlocks are played on the next board changes.
+///
+/// Tiles unmarked by the player are never marked again by the assist
+pub fn assist(
+    board: Res<Board>,
+    board_options: Option<Res<BoardOptions>>,
+    mut requested: Local<HashSet<AssistAction>>,
+    mut unmarked: Local<HashSet<Coordinates>>,
+    mut tile_marked_evr: EventReader<TileMarkedEvent>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+) {
+    if board.is_added() {
+        requested.clear();
+        unmarked.clear();
+    }
+    // The assist never unmarks a tile, so these tiles were unmarked by the player
+    for TileMarkedEvent(coords, count) in tile_marked_evr.iter() {
+        if *count == 0 {
+            unmarked.insert(*coords);
+        }
+    }
+    let options_changed = board_options.as_ref().is_some_and(|o| o.is_changed());
+    if !board.is_changed() && !options_changed {
+        return;
+    }
+    let level = board_options.map(|o| o.assist).unwrap_or_default();
+    // The solver only knows single bomb tiles
+    if level == AssistLevel::None
+        || !board.pending_uncovers.is_empty()
+        || board.tile_map.max_mines_per_cell() > 1
+    {
+        return;
+    }
+    // No assist once a bomb exploded
+    if board
+        .get_all_bomb_coordinates()
+        .iter()
+        .any(|coords| !board.covered_tiles.contains(coords))
+    {
+        return;
+    }
+    let mut actions = assist_actions(&BoardView::from_board(&board), level);
+    actions.retain(
+        |action| !matches!(action, AssistAction::Mark(coords) if unmarked.contains(coords)),
+    );
+    // Played moves stop being valid, the others are still waiting for their event
+    requested.retain(|action| actions.contains(action));
+    for action in actions {
+        if !requested.insert(action) {
+            continue;
+        }
+        log::debug!("Assist: {:?}", action);
+        match action {
+            AssistAction::Mark(coords) => tile_mark_ewr.send(TileMarkEvent(coords)),
+            AssistAction::Reveal(coords) => tile_trigger_ewr.send(TileTriggerEvent(coords)),
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use crate::components::Coordinates;
+use crate::resources::tile::Tile;
+use crate::tile_map::TileMap;
+use crate::{Board, BoardAssets};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// Width and height of a chunk, in tiles
+const RENDER_CHUNK_SIZE: u16 = 32;
+/// Smallest and largest tile size in the chunk images, in pixels
+const TILE_PIXELS: (u32, u32) = (8, 32);
+/// Color of the flags, tinted by the flag material color
+const FLAG_COLOR: Color = Color::RED;
+/// Color of the bombs, tinted by the bomb material color
+const BOMB_COLOR: Color = Color::BLACK;
+/// Flag shape on an 8 x 8 grid, top row first
+const FLAG_ICON: [u8; 8] = [
+    0b00000000, 0b00011000, 0b00111000, 0b01111000, 0b00111000, 0b00001000, 0b00001000, 0b00111100,
+];
+/// Question mark shape on an 8 x 8 grid, top row first
+const QUESTION_ICON: [u8; 8] = [
+    0b00000000, 0b00111100, 0b01100110, 0b00001100, 0b00011000, 0b00000000, 0b00011000, 0b00000000,
+];
+/// Bomb shape on an 8 x 8 grid, top row first
+const BOMB_ICON: [u8; 8] = [
+    0b00000000, 0b00011000, 0b00111100, 0b01111110, 0b01111110, 0b00111100, 0b00011000, 0b00000000,
+];
+/// Digit shapes on a 3 x 5 grid, top row first
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// What the player sees of a tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum TileLook {
+    Void,
+    /// Covered tile, with its flag count
+    Covered(u8),
+    /// Covered tile marked with a question mark
+    Questioned,
+    Revealed(Tile),
+}
+
+impl TileLook {
+    fn of(board: &Board, coords: Coordinates) -> Self {
+        let tile = *board.tile_map.get_tile(coords);
+        if tile.is_void() {
+            Self::Void
+        } else if board.is_questioned(&coords) {
+            Self::Questioned
+        } else if board.covered_tiles.contains(&coords) {
+            Self::Covered(board.marked_tiles.get(&coords).copied().unwrap_or(0))
+        } else {
+            Self::Revealed(tile)
+        }
+    }
+}
+
+/// Image of a chunk of tiles, drawn again by `redraw_chunks` when the board changes
+#[derive(Component)]
+pub struct TileChunk {
+    /// Bottom left tile
+    origin: Coordinates,
+    /// Size in tiles
+    size: (u16, u16),
+    /// Tile size in the image, in pixels
+    pixels: u32,
+    /// Padding around each tile in the image, in pixels
+    inset: u32,
+    /// Drawn tiles, row by row from the bottom
+    looks: Vec<TileLook>,
+}
+
+impl TileChunk {
+    /// Tiles of the chunk, row by row from the bottom
+    fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let (origin, (width, height)) = (self.origin, self.size);
+        (origin.y..origin.y + height)
+            .flat_map(move |y| (origin.x..origin.x + width).map(move |x| Coordinates { x, y }))
+    }
+}
+
+/// Spawns the images of the chunks of `tile_map`, blank until the first `redraw_chunks` run
+pub fn spawn_chunks(
+    parent: &mut ChildBuilder,
+    tile_map: &TileMap,
+    size: f32,
+    padding: f32,
+    images: &mut Assets<Image>,
+) {
+    let topology = tile_map.topology();
+    // An even pixel count, hex rows being shifted by half a tile
+    let pixels = ((size.ceil() as u32).clamp(TILE_PIXELS.0, TILE_PIXELS.1) + 1) & !1;
+    let inset = (padding / size * pixels as f32 / 2.).round() as u32;
+    for y in (0..tile_map.height()).step_by(RENDER_CHUNK_SIZE.into()) {
+        for x in (0..tile_map.width()).step_by(RENDER_CHUNK_SIZE.into()) {
+            let origin = Coordinates { x, y };
+            let chunk_size = (
+                (tile_map.width() - x).min(RENDER_CHUNK_SIZE),
+                (tile_map.height() - y).min(RENDER_CHUNK_SIZE),
+            );
+            let tiles = topology.board_size(chunk_size, 1.);
+            let image = Image::new_fill(
+                Extent3d {
+                    width: (tiles.x * pixels as f32) as u32,
+                    height: (tiles.y * pixels as f32) as u32,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[0, 0, 0, 0],
+                TextureFormat::Rgba8UnormSrgb,
+            );
+            // Chunks start on an even row, which isn't shifted
+            let corner = topology.tile_center(origin, size) - Vec2::splat(size / 2.);
+            let world_size = tiles * size;
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(world_size),
+                        ..Default::default()
+                    },
+                    texture: images.add(image),
+                    transform: Transform::from_translation((corner + world_size / 2.).extend(1.)),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Chunk ({}, {})", x, y)))
+                .insert(TileChunk {
+                    origin,
+                    size: chunk_size,
+                    pixels,
+                    inset,
+                    looks: Vec::new(),
+                });
+        }
+    }
+}
+
+/// Draws the changed tiles of every chunk image when the board changes
+pub fn redraw_chunks(
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    images: Option<ResMut<Assets<Image>>>,
+    mut chunks: Query<(&mut TileChunk, &Handle<Image>)>,
+) {
+    let mut images = match images {
+        Some(images) if board.is_changed() => images,
+        _ => return,
+    };
+    for (mut chunk, handle) in chunks.iter_mut() {
+        let looks: Vec<TileLook> = chunk
+            .coordinates()
+            .map(|coords| TileLook::of(&board, coords))
+            .collect();
+        if looks == chunk.looks {
+            continue;
+        }
+        let image = match images.get_mut(handle) {
+            Some(image) => image,
+            None => continue,
+        };
+        for (i, (coords, look)) in chunk.coordinates().zip(looks.iter()).enumerate() {
+            if chunk.looks.get(i) != Some(look) {
+                draw_tile(image, &chunk, &board, &board_assets, coords, *look);
+            }
+        }
+        chunk.looks = looks;
+    }
+}
+
+/// Draws the tile at `coords` on the image of `chunk`
+fn draw_tile(
+    image: &mut Image,
+    chunk: &TileChunk,
+    board: &Board,
+    board_assets: &BoardAssets,
+    coords: Coordinates,
+    look: TileLook,
+) {
+    let topology = board.tile_map.topology();
+    let pixels = chunk.pixels;
+    let corner = topology.tile_center(coords, pixels as f32)
+        - topology.tile_center(chunk.origin, pixels as f32);
+    let mut canvas = Canvas {
+        image,
+        x: corner.x as u32,
+        y: corner.y as u32,
+        size: pixels,
+    };
+    if look == TileLook::Void {
+        canvas.fill(0, 0, pixels, pixels, [0; 4]);
+        return;
+    }
+    let tile_color = match look {
+        TileLook::Covered(_) | TileLook::Questioned => board_assets.covered_tile_material.color,
+        _ => board_assets.tile_material.color,
+    };
+    canvas.fill(
+        0,
+        0,
+        pixels,
+        pixels,
+        rgba(board_assets.board_material.color),
+    );
+    let inner = pixels.saturating_sub(2 * chunk.inset);
+    canvas.fill(chunk.inset, chunk.inset, inner, inner, rgba(tile_color));
+    match look {
+        TileLook::Covered(0) | TileLook::Revealed(Tile::Empty) | TileLook::Void => (),
+        TileLook::Covered(count) => {
+            canvas.icon(
+                &FLAG_ICON,
+                tinted(FLAG_COLOR, board_assets.flag_material.color),
+            );
+            if count > 1 {
+                canvas.number(count, rgba(Color::WHITE));
+            }
+        }
+        TileLook::Questioned => canvas.icon(&QUESTION_ICON, rgba(Color::WHITE)),
+        TileLook::Revealed(Tile::Bomb(count)) => {
+            canvas.icon(
+                &BOMB_ICON,
+                tinted(BOMB_COLOR, board_assets.bomb_material.color),
+            );
+            if count > 1 {
+                canvas.number(count, rgba(Color::WHITE));
+            }
+        }
+        TileLook::Revealed(Tile::BombNeighbor(count)) => {
+            canvas.number(count, rgba(board_assets.bomb_counter_color(count)));
+        }
+        TileLook::Revealed(Tile::Void) => (),
+    }
+}
+
+/// Square area of an image, with the origin at its bottom left
+struct Canvas<'a> {
+    image: &'a mut Image,
+    x: u32,
+    y: u32,
+    size: u32,
+}
+
+impl Canvas<'_> {
+    /// Fills a rectangle, clipped to the canvas
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 4]) {
+        let image_size = self.image.texture_descriptor.size;
+        let x_end = (x + width).min(self.size);
+        let y_end = (y + height).min(self.size);
+        for y in y..y_end {
+            // Image rows go downwards
+            let row = match image_size.height.checked_sub(self.y + y + 1) {
+                Some(row) => row,
+                None => continue,
+            };
+            for x in x..x_end {
+                let column = self.x + x;
+                if column >= image_size.width {
+                    continue;
+                }
+                let i = ((row * image_size.width + column) * 4) as usize;
+                self.image.data[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draws an 8 x 8 icon, scaled to the canvas
+    fn icon(&mut self, icon: &[u8; 8], color: [u8; 4]) {
+        let scale = (self.size / 8).max(1);
+        let offset = self.size.saturating_sub(8 * scale) / 2;
+        for (row, bits) in icon.iter().enumerate() {
+            for column in 0..8 {
+                if bits & (0b10000000 >> column) != 0 {
+                    let y = offset + (7 - row as u32) * scale;
+                    self.fill(offset + column * scale, y, scale, scale, color);
+                }
+            }
+        }
+    }
+
+    /// Draws `value` centered on the canvas
+    fn number(&mut self, value: u8, color: [u8; 4]) {
+        let digits: Vec<usize> = value
+            .to_string()
+            .bytes()
+            .map(|digit| (digit - b'0') as usize)
+            .collect();
+        let scale = (self.size / 8).max(1);
+        let width = (digits.len() as u32 * 4 - 1) * scale;
+        let x = self.size.saturating_sub(width) / 2;
+        let y = self.size.saturating_sub(5 * scale) / 2;
+        for (i, digit) in digits.into_iter().enumerate() {
+            let left = x + i as u32 * 4 * scale;
+            for (row, bits) in DIGITS[digit].iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) != 0 {
+                        let top = y + (4 - row as u32) * scale;
+                        self.fill(left + column * scale, top, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pixel of `color`
+fn rgba(color: Color) -> [u8; 4] {
+    color
+        .as_rgba_f32()
+        .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8)
+}
+
+/// Pixel of `color` multiplied by `tint`
+fn tinted(color: Color, tint: Color) -> [u8; 4] {
+    let (color, tint) = (color.as_rgba_f32(), tint.as_rgba_f32());
+    rgba(Color::rgba(
+        color[0] * tint[0],
+        color[1] * tint[1],
+        color[2] * tint[2],
+        color[3] * tint[3],
+    ))
+}
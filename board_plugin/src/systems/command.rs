@@ -0,0 +1,73 @@
+use crate::events::{TileMarkEvent, TileMarkedEvent, TileTriggerEvent};
+use crate::{AnimationOptions, Board, BoardAction, BoardCommands, RevealOrigin};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Turns the triggered and marked tiles into local board actions
+pub fn request_commands(
+    mut board_commands: ResMut<BoardCommands>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    for TileTriggerEvent(coords) in tile_trigger_evr.iter() {
+        board_commands.request(BoardAction::Reveal(*coords));
+    }
+    for TileMarkEvent(coords) in tile_mark_evr.iter() {
+        board_commands.request(BoardAction::Flag(*coords));
+    }
+}
+
+/// Applies the board commands in order, each one waiting for the tiles of the previous ones to
+/// be uncovered
+pub fn apply_commands(
+    mut board: ResMut<Board>,
+    mut board_commands: ResMut<BoardCommands>,
+    time: Res<Time>,
+    animation_options: Res<AnimationOptions>,
+    mut reveal_origin: ResMut<RevealOrigin>,
+    mut tile_marked_event_wr: EventWriter<TileMarkedEvent>,
+) {
+    while board.pending_uncovers.is_empty() {
+        let command = match board_commands.pop() {
+            Some(command) => command,
+            None => return,
+        };
+        log::debug!("Applying board command {:?}", command);
+        match command.action {
+            BoardAction::Reveal(coords) => {
+                let tiles = board.tile_to_uncover(&coords);
+                if tiles.is_empty() {
+                    continue;
+                }
+                // A hot-seat turn is a single reveal
+                if let Some(players) = &mut board.players {
+                    if !players.start_reveal() {
+                        continue;
+                    }
+                }
+                // Flood fills ripple from the triggered tile
+                reveal_origin.start(
+                    coords,
+                    time.seconds_since_startup(),
+                    animation_options.ripple_delay,
+                );
+                for coords in tiles {
+                    board.queue_uncover(coords);
+                }
+            }
+            BoardAction::Flag(coords) => {
+                let count = match board.try_toggle_mark(&coords) {
+                    Some(count) => count,
+                    None => {
+                        log::debug!("Refused to flag the uncovered tile {}", coords);
+                        continue;
+                    }
+                };
+                if let (1, Some(player)) = (count, command.player) {
+                    board.flag_owners.insert(coords, player);
+                }
+                tile_marked_event_wr.send(TileMarkedEvent(coords, count));
+            }
+        }
+    }
+}
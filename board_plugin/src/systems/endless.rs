@@ -0,0 +1,77 @@
+use crate::components::WorldCoordinates;
+use crate::events::BombExplosionEvent;
+use crate::{ChunkMap, EndlessOptions};
+use bevy::input::{mouse::MouseButtonInput, ButtonState};
+use bevy::log;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+
+/// Pans the camera over the endless board with the arrow keys
+pub fn scroll_camera(
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    options: Res<EndlessOptions>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    let direction = [
+        (KeyCode::Left, -Vec2::X),
+        (KeyCode::Right, Vec2::X),
+        (KeyCode::Down, -Vec2::Y),
+        (KeyCode::Up, Vec2::Y),
+    ]
+    .iter()
+    .filter(|(key, _)| keys.pressed(*key))
+    .fold(Vec2::ZERO, |sum, (_, direction)| sum + *direction);
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let shift =
+        direction.normalize() * options.scroll_speed * options.tile_size * time.delta_seconds();
+    for mut transform in cameras.iter_mut() {
+        transform.translation += shift.extend(0.);
+    }
+}
+
+pub fn endless_input_handling(
+    windows: Res<Windows>,
+    options: Res<EndlessOptions>,
+    mut chunk_map: ResMut<ChunkMap>,
+    cameras: Query<&Transform, With<Camera2d>>,
+    mut button_evr: EventReader<MouseButtonInput>,
+    mut bomb_explosion_ewr: EventWriter<BombExplosionEvent>,
+) {
+    let (window, camera) = match (windows.get_primary(), cameras.get_single()) {
+        (Some(w), Ok(c)) => (w, c),
+        _ => return,
+    };
+    for event in button_evr.iter() {
+        if event.state != ButtonState::Pressed || chunk_map.is_exploded() {
+            continue;
+        }
+        let position = match window.cursor_position() {
+            Some(p) => p,
+            None => continue,
+        };
+        // The camera is centered on the window
+        let window_size = Vec2::new(window.width(), window.height());
+        let position = (position - window_size / 2. + camera.translation.xy()) / options.tile_size;
+        let coordinates = WorldCoordinates {
+            x: position.x.floor() as i32,
+            y: position.y.floor() as i32,
+        };
+        match event.button {
+            MouseButton::Left => {
+                log::info!("Trying to uncover tile on {}", coordinates);
+                if chunk_map.uncover(coordinates) {
+                    log::info!("Boom ! Score: {}", chunk_map.cleared());
+                    bomb_explosion_ewr.send(BombExplosionEvent);
+                }
+            }
+            MouseButton::Right => {
+                log::info!("Trying to mark tile on {}", coordinates);
+                chunk_map.toggle_mark(coordinates);
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::components::{GhostCover, GhostFlag};
+use crate::Board;
+use bevy::prelude::*;
+
+/// Mirrors the covers and flags of the board tiles on their ghost copies
+pub fn ghost_tiles(
+    board: Res<Board>,
+    mut covers: Query<(&GhostCover, &mut Visibility)>,
+    mut flags: Query<(&GhostFlag, &mut Visibility), Without<GhostCover>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+    for (cover, mut visibility) in covers.iter_mut() {
+        visibility.is_visible = board.covered_tiles.contains(&cover.0);
+    }
+    for (flag, mut visibility) in flags.iter_mut() {
+        visibility.is_visible = board.marked_tiles.contains_key(&flag.0);
+    }
+}
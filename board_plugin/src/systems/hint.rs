@@ -0,0 +1,82 @@
+use crate::components::{Coordinates, HintHighlight};
+use crate::events::{HintEvent, TileMarkEvent, TileTriggerEvent};
+use crate::solver::{ActiveHint, HintKind};
+use crate::Board;
+use bevy::prelude::*;
+
+/// Highlight color of the uncovered tiles justifying a hint
+const REASON_COLOR: Color = Color::rgba(0.2, 0.5, 1.0, 0.4);
+
+/// Highlight color of the hinted tile
+fn hint_color(kind: HintKind) -> Color {
+    match kind {
+        HintKind::Safe => Color::rgba(0.2, 1.0, 0.2, 0.5),
+        HintKind::Bomb => Color::rgba(1.0, 0.2, 0.2, 0.5),
+        HintKind::Guess => Color::rgba(1.0, 1.0, 0.2, 0.5),
+        HintKind::WrongMark => Color::rgba(1.0, 0.2, 1.0, 0.5),
+    }
+}
+
+fn spawn_highlight(parent: &mut ChildBuilder, board: &Board, coords: Coordinates, color: Color) {
+    let size = board.tile_size;
+    parent
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(size)),
+                color,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(
+                board
+                    .tile_map
+                    .topology()
+                    .tile_center(coords, size)
+                    .extend(5.),
+            ),
+            ..Default::default()
+        })
+        .insert(Name::new("Hint"))
+        .insert(HintHighlight);
+}
+
+pub fn show_hint(
+    mut commands: Commands,
+    board: Res<Board>,
+    mut active_hint: ResMut<ActiveHint>,
+    mut hint_evr: EventReader<HintEvent>,
+    highlights: Query<Entity, With<HintHighlight>>,
+) {
+    let hint = match hint_evr.iter().last() {
+        Some(HintEvent(hint)) => hint,
+        None => return,
+    };
+    for entity in highlights.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.entity(board.entity).with_children(|parent| {
+        spawn_highlight(parent, &board, hint.coords, hint_color(hint.kind));
+        for coords in hint.reasons.iter() {
+            spawn_highlight(parent, &board, *coords, REASON_COLOR);
+        }
+    });
+    active_hint.0 = Some(hint.clone());
+}
+
+/// Any player action clears the hint
+pub fn clear_hint(
+    mut commands: Commands,
+    mut active_hint: ResMut<ActiveHint>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    highlights: Query<Entity, With<HintHighlight>>,
+) {
+    let triggered = tile_trigger_evr.iter().count() > 0;
+    let marked = tile_mark_evr.iter().count() > 0;
+    if !triggered && !marked {
+        return;
+    }
+    for entity in highlights.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    active_hint.0 = None;
+}
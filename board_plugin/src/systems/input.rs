@@ -11,7 +11,11 @@ pub fn input_handling(
     mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
     mut tile_mark_ewr: EventWriter<TileMarkEvent>,
 ) {
-    let window = windows.get_primary().unwrap();
+    // Headless apps have no primary window to pick tiles from
+    let window = match windows.get_primary() {
+        Some(w) => w,
+        None => return,
+    };
 
     for event in button_evr.iter() {
         if let ButtonState::Pressed = event.state {
@@ -1,44 +1,111 @@
-use crate::events::TileMarkEvent;
-use crate::{Board, BoardAssets};
+use crate::components::{FlagDrop, PlayerFlag};
+use crate::events::TileMarkedEvent;
+use crate::{AnimationOptions, Board, BoardAssets};
 use bevy::log;
 use bevy::prelude::*;
 
 pub fn mark_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
+    board: Res<Board>,
     board_assets: Res<BoardAssets>,
-    mut tile_mark_event_rdr: EventReader<TileMarkEvent>,
+    animation_options: Res<AnimationOptions>,
+    mut tile_marked_event_rdr: EventReader<TileMarkedEvent>,
     query: Query<&Children>,
 ) {
-    for event in tile_mark_event_rdr.iter() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
-            if mark {
-                commands.entity(entity).with_children(|parent| {
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            texture: board_assets.flag_material.texture.clone(),
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(board.tile_size)),
-                                color: board_assets.flag_material.color,
-                                ..Default::default()
-                            },
-                            transform: Transform::from_xyz(0., 0., 1.),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Flag"));
-                });
-            } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrieve flag components: {}", e);
-                        continue;
-                    }
-                };
-                for child in children.iter() {
-                    commands.entity(*child).despawn_recursive();
+    // Flags are toggled by `systems::command::apply_commands`
+    for &TileMarkedEvent(coords, count) in tile_marked_event_rdr.iter() {
+        // Boards drawn in chunks have no cover entity
+        let entity = match board.cover_entities.get(&coords) {
+            Some(entity) => *entity,
+            None => continue,
+        };
+        // The previous flag is replaced, if any
+        if count != 1 {
+            let children = match query.get(entity) {
+                Ok(c) => c,
+                Err(e) => {
+                    log::error!("Failed to retrieve flag components: {}", e);
+                    continue;
                 }
+            };
+            for child in children.iter() {
+                commands.entity(*child).despawn_recursive();
             }
         }
+        if count > 0 {
+            commands.entity(entity).with_children(|parent| {
+                let mut flag = parent.spawn_bundle(flag_bundle(&board_assets, board.tile_size));
+                flag.insert(Name::new("Flag"));
+                if let Some(player) = board.flag_owners.get(&coords) {
+                    flag.insert(PlayerFlag(*player));
+                }
+                // New flags drop in from above, see `systems::animation::animate_flags`
+                if count == 1 && !animation_options.reduced_motion {
+                    let height = board.tile_size;
+                    flag.insert(FlagDrop {
+                        height,
+                        elapsed: 0.,
+                    })
+                    .insert(Transform::from_xyz(0., height, 1.).with_scale(Vec3::splat(1.5)));
+                }
+                if count > 1 {
+                    parent
+                        .spawn_bundle(flag_count_bundle(&board_assets, board.tile_size, count))
+                        .insert(Name::new("Flag count"));
+                }
+            });
+        } else if board.is_questioned(&coords) {
+            commands.entity(entity).with_children(|parent| {
+                parent
+                    .spawn_bundle(question_mark_bundle(&board_assets, board.tile_size))
+                    .insert(Name::new("Question mark"));
+            });
+        }
+    }
+}
+
+/// Flag sprite bundle, spawned as a child of a tile cover
+pub fn flag_bundle(board_assets: &BoardAssets, size: f32) -> SpriteBundle {
+    SpriteBundle {
+        texture: board_assets.flag_material.texture.clone(),
+        sprite: Sprite {
+            custom_size: Some(Vec2::splat(size)),
+            color: board_assets.flag_material.color,
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(0., 0., 1.),
+        ..Default::default()
+    }
+}
+
+/// Flag count text bundle, spawned as a child of a tile cover holding several flags
+pub fn flag_count_bundle(board_assets: &BoardAssets, size: f32, count: u8) -> Text2dBundle {
+    mark_text_bundle(board_assets, size, count.to_string())
+}
+
+/// Question mark text bundle, spawned as a child of a tile cover
+pub fn question_mark_bundle(board_assets: &BoardAssets, size: f32) -> Text2dBundle {
+    mark_text_bundle(board_assets, size, "?".to_string())
+}
+
+/// Text bundle written over a tile cover
+fn mark_text_bundle(board_assets: &BoardAssets, size: f32, value: String) -> Text2dBundle {
+    Text2dBundle {
+        text: Text {
+            sections: vec![TextSection {
+                value,
+                style: TextStyle {
+                    color: Color::WHITE,
+                    font: board_assets.bomb_counter_font.clone(),
+                    font_size: size / 2.,
+                },
+            }],
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0., 0., 2.),
+        ..Default::default()
     }
 }
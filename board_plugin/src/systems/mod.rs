@@ -1,3 +1,11 @@
+pub mod animation;
+pub mod assist;
+pub mod chunks;
+pub mod command;
+pub mod endless;
+pub mod ghost;
+pub mod hint;
 pub mod input;
 pub mod mark;
+pub mod probability;
 pub mod uncover;
@@ -0,0 +1,134 @@
+use crate::components::{Coordinates, ProbabilityLabel};
+use crate::solver::{BoardView, MineProbabilities, ProbabilityOverlay};
+use crate::{Board, BoardAssets};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Cover tint of a safe tile
+const SAFE_TINT: [f32; 3] = [0.2, 0.8, 0.2];
+/// Cover tint of a bomb
+const BOMB_TINT: [f32; 3] = [0.9, 0.2, 0.2];
+/// Share of the tint in the cover color
+const TINT_STRENGTH: f32 = 0.6;
+
+/// Cover color of a tile with a `probability` to be a bomb
+fn tinted(base: Color, probability: f32) -> Color {
+    let [r, g, b, a] = base.as_rgba_f32();
+    let mix = |channel: f32, i: usize| {
+        let tint = SAFE_TINT[i] + (BOMB_TINT[i] - SAFE_TINT[i]) * probability;
+        channel + (tint - channel) * TINT_STRENGTH
+    };
+    Color::rgba(mix(r, 0), mix(g, 1), mix(b, 2), a)
+}
+
+fn label_text(probabilities: &MineProbabilities, probability: f32) -> String {
+    let percent = (probability * 100.).round();
+    if probabilities.is_exact() {
+        format!("{}%", percent)
+    } else {
+        format!("~{}%", percent)
+    }
+}
+
+fn label_bundle(
+    board: &Board,
+    board_assets: &BoardAssets,
+    coords: Coordinates,
+    value: String,
+) -> Text2dBundle {
+    let size = board.tile_size;
+    Text2dBundle {
+        text: Text {
+            sections: vec![TextSection {
+                value,
+                style: TextStyle {
+                    color: Color::WHITE,
+                    font: board_assets.bomb_counter_font.clone(),
+                    font_size: size * 0.35,
+                },
+            }],
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_translation(
+            board
+                .tile_map
+                .topology()
+                .tile_center(coords, size)
+                .extend(4.),
+        ),
+        ..Default::default()
+    }
+}
+
+/// Tints the covers and labels them with their mine probability, which is only computed again
+/// when the board changes
+pub fn probability_overlay(
+    mut commands: Commands,
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    mut overlay: ResMut<ProbabilityOverlay>,
+    mut covers: Query<&mut Sprite>,
+    mut labels: Query<(Entity, &ProbabilityLabel, &mut Text)>,
+) {
+    let base = board_assets.covered_tile_material.color;
+    if !overlay.enabled {
+        if overlay.probabilities.is_some() {
+            overlay.probabilities = None;
+            for entity in board.cover_entities.values() {
+                if let Ok(mut sprite) = covers.get_mut(*entity) {
+                    sprite.color = base;
+                }
+            }
+            for (entity, _, _) in labels.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        return;
+    }
+    if overlay.probabilities.is_some() && !board.is_changed() {
+        return;
+    }
+    let overlay = &mut *overlay;
+    // The solver only knows single bomb tiles
+    let probabilities = if board.tile_map.max_mines_per_cell() > 1 {
+        MineProbabilities::default()
+    } else {
+        let view = BoardView::from_board(&board);
+        MineProbabilities::compute_cached(&view, &mut overlay.cache).unwrap_or_default()
+    };
+    // Existing labels are updated, the ones of uncovered or marked tiles are removed
+    let mut labeled = HashSet::new();
+    for (entity, label, mut text) in labels.iter_mut() {
+        match probabilities.get(label.0) {
+            Some(p) => {
+                text.sections[0].value = label_text(&probabilities, p);
+                labeled.insert(label.0);
+            }
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+    commands.entity(board.entity).with_children(|parent| {
+        for coords in board.covered_tiles.iter() {
+            let probability = probabilities.get(coords);
+            // Boards drawn in chunks have no cover entity to tint
+            let cover = board.cover_entities.get(&coords);
+            if let Some(Ok(mut sprite)) = cover.map(|entity| covers.get_mut(*entity)) {
+                sprite.color = match probability {
+                    Some(p) => tinted(base, p),
+                    None => base,
+                };
+            }
+            if let Some(p) = probability.filter(|_| !labeled.contains(&coords)) {
+                let value = label_text(&probabilities, p);
+                parent
+                    .spawn_bundle(label_bundle(&board, &board_assets, coords, value))
+                    .insert(Name::new("Probability"))
+                    .insert(ProbabilityLabel(coords));
+            }
+        }
+    });
+    overlay.probabilities = Some(probabilities);
+}
@@ -1,60 +1,103 @@
-use crate::components::{Bomb, BombNeighbor, Coordinates, Uncover};
-use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileTriggerEvent};
-use crate::Board;
+use crate::components::RevealAnimation;
+use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileUncoveredEvent};
+use crate::resources::tile::Tile;
+use crate::{AnimationOptions, Board, RevealOrigin};
+use bevy::ecs::system::SystemParam;
 use bevy::log;
 use bevy::prelude::*;
+use std::marker::PhantomData;
+
+/// Resources timing the reveal animations of the uncovered tiles
+#[derive(SystemParam)]
+pub struct RevealTiming<'w, 's> {
+    time: Res<'w, Time>,
+    options: Res<'w, AnimationOptions>,
+    origin: ResMut<'w, RevealOrigin>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
 
 pub fn uncover_tiles(
     mut commands: Commands,
     mut board: ResMut<Board>,
-    children: Query<(Entity, &Parent), With<Uncover>>,
-    parents: Query<(&Coordinates, Option<&Bomb>, Option<&BombNeighbor>)>,
+    mut timing: RevealTiming,
+    mut tile_uncovered_event_wr: EventWriter<TileUncoveredEvent>,
     mut board_completed_event_wr: EventWriter<BoardCompletedEvent>,
     mut bomb_explosion_event_wr: EventWriter<BombExplosionEvent>,
 ) {
-    // We iterate through tile covers to uncover
-    for (entity, parent) in children.iter() {
-        // we destroy the entity
-        commands.entity(entity).despawn_recursive();
-        let (coords, bomb, bomb_counter) = match parents.get(parent.get()) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("{}", e);
-                continue;
+    // The board is only changed when there is something to uncover
+    if board.pending_uncovers.is_empty() {
+        return;
+    }
+    // Hot-seat player whose reveal is uncovered, flood fill included
+    let player = board.players.as_ref().and_then(|players| players.revealing);
+    // We iterate through the tiles to uncover
+    for coords in std::mem::take(&mut board.pending_uncovers) {
+        // We remove the tile from the board map
+        if !board.try_uncover_tile(&coords) {
+            log::debug!("Tried to uncover an already uncovered tile");
+            continue;
+        }
+        log::debug!("Uncovered tile {}", coords);
+        tile_uncovered_event_wr.send(TileUncoveredEvent(coords, player));
+        let is_bomb = board.tile_map.is_bomb_at(coords);
+        if let (Some(players), Some(player)) = (&mut board.players, player) {
+            players.credit(player, coords, is_bomb);
+        }
+        let now = timing.time.seconds_since_startup();
+        // The other bombs are revealed from the first exploded one
+        if is_bomb && timing.origin.explode(coords) {
+            let chain_delay = timing.options.chain_delay;
+            timing.origin.start(coords, now, chain_delay);
+        }
+        // Boards drawn in chunks have no cover entity
+        if let Some(entity) = board.cover_entities.remove(&coords) {
+            if timing.options.reduced_motion {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                // The cover is removed from the board right away, and despawned once animated
+                let topology = board.tile_map.topology();
+                let distance = timing.origin.origin().map_or(0., |origin| {
+                    topology
+                        .tile_center(origin, 1.)
+                        .distance(topology.tile_center(coords, 1.))
+                });
+                commands.entity(entity).insert(RevealAnimation {
+                    delay: timing.origin.delay(distance, now),
+                    elapsed: 0.,
+                });
             }
-        };
-        // We remove the entity from the board map
-        match board.try_uncover_tile(coords) {
-            None => log::debug!("Tried to uncover an already uncovered tile"),
-            Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coords, e),
         }
         if board.is_completed() {
             log::info!("Board completed");
+            timing.origin.win();
             board_completed_event_wr.send(BoardCompletedEvent);
         }
-        if bomb.is_some() {
-            log::info!("Boom !");
-            bomb_explosion_event_wr.send(BombExplosionEvent);
-        }
-        // If the tile is empty..
-        else if bomb_counter.is_none() {
-            // .. We propagate the uncovering by adding the `Uncover` component to adjacent tiles
-            // which will then be removed next frame
-            for entity in board.adjacent_covered_tiles(*coords) {
-                commands.entity(entity).insert(Uncover);
+        match *board.tile_map.get_tile(coords) {
+            // Hot-seat games go on until every player is eliminated
+            Tile::Bomb(_) => match (&board.players, player) {
+                (Some(players), Some(player)) if !players.is_over() => {
+                    log::info!("{} revealed a mine", player);
+                }
+                _ => {
+                    log::info!("Boom !");
+                    bomb_explosion_event_wr.send(BombExplosionEvent);
+                }
+            },
+            // If the tile is empty we propagate the uncovering to the adjacent tiles, which will
+            // be uncovered next frame
+            Tile::Empty => {
+                for neighbor in board.adjacent_covered_tiles(coords) {
+                    board.queue_uncover(neighbor);
+                }
             }
+            Tile::BombNeighbor(_) | Tile::Void => (),
         }
     }
-}
-
-pub fn trigger_event_handler(
-    mut commands: Commands,
-    board: Res<Board>,
-    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
-) {
-    for trigger_event in tile_trigger_evr.iter() {
-        for entity in board.tile_to_uncover(&trigger_event.0) {
-            commands.entity(entity).insert(Uncover {});
+    // The turn ends once the flood fill is over
+    if board.pending_uncovers.is_empty() {
+        if let Some(players) = &mut board.players {
+            players.end_turn();
         }
     }
 }
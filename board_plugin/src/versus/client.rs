@@ -0,0 +1,191 @@
+use crate::components::Coordinates;
+use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::versus::{
+    BoardSpec, ClientMessage, Connection, EndReason, ServerMessage, PROTOCOL_VERSION,
+};
+use crate::TileSet;
+use bevy::log;
+use bevy::prelude::*;
+use std::io;
+use std::net::ToSocketAddrs;
+
+/// Progress of the opponent, drawn as a mini-map
+#[derive(Debug, Clone)]
+pub struct OpponentProgress {
+    pub name: String,
+    /// Tiles uncovered by the opponent
+    pub uncovered: TileSet,
+    pub flags: u32,
+    /// Safe tiles left to uncover
+    pub remaining: u32,
+}
+
+/// State of the match of a client
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersusStatus {
+    /// Connected, waiting for the server to find an opponent
+    Waiting,
+    Playing(BoardSpec),
+    Finished {
+        winner: Option<u8>,
+        reason: EndReason,
+    },
+    /// The connection was refused or closed
+    Disconnected(String),
+}
+
+/// Versus match of a client. Must be used as a resource
+#[derive(Debug)]
+pub struct VersusClient {
+    connection: Connection<ClientMessage, ServerMessage>,
+    /// Number given by the server
+    pub player: Option<u8>,
+    pub status: VersusStatus,
+    pub opponent: Option<OpponentProgress>,
+}
+
+impl VersusClient {
+    /// Connects to the server at `address` as `name`
+    pub fn connect(address: impl ToSocketAddrs, name: &str) -> io::Result<Self> {
+        let mut connection = Connection::connect(address)?;
+        connection.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            name: name.to_string(),
+        })?;
+        Ok(Self {
+            connection,
+            player: None,
+            status: VersusStatus::Waiting,
+            opponent: None,
+        })
+    }
+
+    /// Sends an action, while the match is played
+    pub fn send(&mut self, message: &ClientMessage) {
+        if !matches!(self.status, VersusStatus::Playing(_)) {
+            return;
+        }
+        if let Err(e) = self.connection.send(message) {
+            self.status = VersusStatus::Disconnected(e.to_string());
+        }
+    }
+
+    /// Applies the received messages to the match, and returns them
+    pub fn poll(&mut self) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        loop {
+            match self.connection.try_recv() {
+                Ok(Some(message)) => {
+                    self.apply(&message);
+                    messages.push(message);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    if !matches!(
+                        self.status,
+                        VersusStatus::Finished { .. } | VersusStatus::Disconnected(_)
+                    ) {
+                        self.status = VersusStatus::Disconnected(e.to_string());
+                    }
+                    break;
+                }
+            }
+        }
+        messages
+    }
+
+    fn apply(&mut self, message: &ServerMessage) {
+        match message {
+            ServerMessage::Welcome { player, .. } => self.player = Some(*player),
+            ServerMessage::Error { message } => log::error!("Versus server error: {}", message),
+            ServerMessage::Start { board, opponent } => {
+                self.status = VersusStatus::Playing(*board);
+                self.opponent = Some(OpponentProgress {
+                    name: opponent.clone(),
+                    uncovered: TileSet::new(board.width, board.height),
+                    flags: 0,
+                    remaining: u32::from(board.width) * u32::from(board.height)
+                        - u32::from(board.bomb_count),
+                });
+            }
+            ServerMessage::Progress {
+                player,
+                uncovered,
+                flags,
+                remaining,
+            } if Some(*player) != self.player => {
+                if let Some(opponent) = &mut self.opponent {
+                    opponent.uncovered.extend(uncovered.iter().copied());
+                    opponent.flags = *flags;
+                    opponent.remaining = *remaining;
+                }
+            }
+            ServerMessage::Progress { .. } => (),
+            ServerMessage::Result { winner, reason } => {
+                self.status = VersusStatus::Finished {
+                    winner: *winner,
+                    reason: *reason,
+                };
+            }
+        }
+    }
+
+    /// Did this client win the finished match
+    #[must_use]
+    pub fn won(&self) -> Option<bool> {
+        match self.status {
+            VersusStatus::Finished { winner, .. } => {
+                Some(winner.is_some() && winner == self.player)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Message received from the versus server
+#[derive(Debug, Clone)]
+pub struct VersusEvent(pub ServerMessage);
+
+/// Sends the reveals and flags of the board to the versus server, and receives the match
+/// updates as `VersusEvent`s.
+///
+/// Needs the `VersusClient` resource, the app starting the board of the `Start` message. The
+/// server is authoritative: the result is only known from its `Result` message
+pub struct VersusClientPlugin;
+
+impl Plugin for VersusClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<VersusEvent>()
+            .add_system(send_actions)
+            .add_system(receive_messages);
+        log::info!("Loaded Versus Client Plugin");
+    }
+}
+
+fn send_actions(
+    client: Option<ResMut<VersusClient>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    let mut client = match client {
+        Some(client) => client,
+        None => return,
+    };
+    for TileTriggerEvent(Coordinates { x, y }) in tile_trigger_evr.iter() {
+        client.send(&ClientMessage::Reveal { x: *x, y: *y });
+    }
+    for TileMarkEvent(Coordinates { x, y }) in tile_mark_evr.iter() {
+        client.send(&ClientMessage::Flag { x: *x, y: *y });
+    }
+}
+
+fn receive_messages(
+    client: Option<ResMut<VersusClient>>,
+    mut versus_ewr: EventWriter<VersusEvent>,
+) {
+    if let Some(mut client) = client {
+        for message in client.poll() {
+            versus_ewr.send(VersusEvent(message));
+        }
+    }
+}
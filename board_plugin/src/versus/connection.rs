@@ -0,0 +1,73 @@
+use crate::versus::{read_message, write_message};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, BufReader};
+use std::marker::PhantomData;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// TCP connection sending `Out` messages and receiving `In` messages.
+///
+/// Messages are read by a background thread, so that the game never waits for the network. The
+/// receiver is behind a mutex, the connection being a resource shared with the systems
+#[derive(Debug)]
+pub struct Connection<Out, In> {
+    stream: TcpStream,
+    incoming: Mutex<Receiver<In>>,
+    outgoing: PhantomData<Out>,
+}
+
+impl<Out, In> Connection<Out, In>
+where
+    Out: Serialize,
+    In: DeserializeOwned + Send + 'static,
+{
+    /// Connects to the server at `address`
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    /// Reads the messages of `stream` until it is closed or sends an invalid message
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (sender, incoming) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(Some(message)) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            stream,
+            incoming: Mutex::new(incoming),
+            outgoing: PhantomData,
+        })
+    }
+
+    pub fn send(&mut self, message: &Out) -> io::Result<()> {
+        write_message(&mut self.stream, message)
+    }
+
+    /// Next received message if any, or an error once the connection is closed
+    pub fn try_recv(&self) -> io::Result<Option<In>> {
+        match self.incoming.lock().unwrap().try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(io::ErrorKind::ConnectionAborted.into()),
+        }
+    }
+
+    /// Waits up to `timeout` for the next message, or returns an error once the connection is
+    /// closed
+    pub fn recv_timeout(&self, timeout: Duration) -> io::Result<Option<In>> {
+        match self.incoming.lock().unwrap().recv_timeout(timeout) {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(io::ErrorKind::ConnectionAborted.into()),
+        }
+    }
+}
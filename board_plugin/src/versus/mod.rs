@@ -0,0 +1,5 @@
+pub use {client::*, connection::*, protocol::*};
+
+mod client;
+mod connection;
+mod protocol;
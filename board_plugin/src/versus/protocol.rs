@@ -0,0 +1,113 @@
+use crate::components::Coordinates;
+use crate::BoardOptions;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// Version of the protocol, bumped on every incompatible change. See `docs/versus_protocol.md`
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Port of the versus server when none is given
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Board raced by both players, generated identically by the server and the clients
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BoardSpec {
+    pub seed: u64,
+    pub width: u16,
+    pub height: u16,
+    pub bomb_count: u16,
+}
+
+impl BoardSpec {
+    /// Options generating the board: a no-guess board without any variant, from a safe start
+    #[must_use]
+    pub fn options(&self) -> BoardOptions {
+        BoardOptions {
+            map_size: (self.width, self.height),
+            bomb_count: self.bomb_count,
+            seed: Some(self.seed),
+            no_guess: true,
+            safe_start: true,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the board in `options`, keeping how it is drawn
+    pub fn apply(&self, options: &mut BoardOptions) {
+        *options = BoardOptions {
+            position: options.position.clone(),
+            tile_size: options.tile_size.clone(),
+            tile_padding: options.tile_padding,
+            render_mode: options.render_mode,
+            question_marks: options.question_marks,
+            ..self.options()
+        };
+    }
+}
+
+/// Message sent by a client
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// First message of the connection
+    Hello { version: u32, name: String },
+    /// Uncovers a covered tile, or chords around an uncovered number
+    Reveal { x: u16, y: u16 },
+    /// Cycles the mark of a covered tile
+    Flag { x: u16, y: u16 },
+}
+
+/// Reason of the end of a match
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndReason {
+    /// The winner cleared the board
+    Cleared,
+    /// The loser revealed a mine
+    Exploded,
+    /// The loser left the match
+    Disconnected,
+}
+
+/// Message sent by the server
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Accepts the connection of `player`, waiting for an opponent
+    Welcome { version: u32, player: u8 },
+    /// Refuses a message, closing the connection if it was the `Hello`
+    Error { message: String },
+    /// Starts the match on `board`
+    Start { board: BoardSpec, opponent: String },
+    /// Tiles uncovered by an action of `player`, sent to both players
+    Progress {
+        player: u8,
+        uncovered: Vec<Coordinates>,
+        flags: u32,
+        /// Safe tiles left to uncover
+        remaining: u32,
+    },
+    /// Ends the match, `winner` being `None` if nobody won
+    Result {
+        winner: Option<u8>,
+        reason: EndReason,
+    },
+}
+
+/// Writes `message` as a line of JSON
+pub fn write_message<W: Write, M: Serialize>(writer: &mut W, message: &M) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, message)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+/// Reads a line of JSON, `None` once the connection is closed
+pub fn read_message<R: BufRead, M: DeserializeOwned>(reader: &mut R) -> io::Result<Option<M>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,79 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::{AssistLevel, BoardOptions};
+use common::layout_board;
+
+mod common;
+
+fn board(layout: &str, assist: AssistLevel) -> HeadlessBoard {
+    let options = BoardOptions {
+        assist,
+        ..Default::default()
+    };
+    layout_board(layout, options)
+}
+
+fn is_marked(board: &HeadlessBoard, x: u16) -> bool {
+    board
+        .board()
+        .marked_tiles
+        .contains_key(&Coordinates { x, y: 0 })
+}
+
+fn is_covered(board: &HeadlessBoard, x: u16) -> bool {
+    board
+        .board()
+        .covered_tiles
+        .contains(&Coordinates { x, y: 0 })
+}
+
+#[test]
+fn auto_flag_marks_the_last_covered_neighbors() {
+    let mut board = board("*.*..", AssistLevel::AutoFlag);
+    // The opening stops at the 1 next to the second bomb
+    board.uncover(Coordinates { x: 4, y: 0 });
+    assert!(is_marked(&board, 2));
+    assert!(!is_marked(&board, 0));
+}
+
+#[test]
+fn unmarked_tiles_are_not_flagged_again() {
+    let mut board = board("*.*..", AssistLevel::AutoFlag);
+    board.uncover(Coordinates { x: 4, y: 0 });
+    board.toggle_mark(Coordinates { x: 2, y: 0 });
+    assert!(!is_marked(&board, 2));
+    // Other changes don't bring the flag back
+    board.toggle_mark(Coordinates { x: 1, y: 0 });
+    board.toggle_mark(Coordinates { x: 1, y: 0 });
+    assert!(!is_marked(&board, 2));
+}
+
+#[test]
+fn auto_reveal_chords_satisfied_numbers() {
+    let mut board = board("*..", AssistLevel::AutoReveal);
+    board.uncover(Coordinates { x: 1, y: 0 });
+    assert!(is_covered(&board, 2));
+    // Flagging the bomb satisfies the 1, its other neighbor is uncovered
+    board.toggle_mark(Coordinates { x: 0, y: 0 });
+    assert!(!is_covered(&board, 2));
+    // Numbers aren't flagged around
+    let mut board = self::board("..*..", AssistLevel::AutoReveal);
+    board.uncover(Coordinates { x: 0, y: 0 });
+    assert!(!is_marked(&board, 2));
+}
+
+#[test]
+fn full_assist_reveals_around_its_own_flags() {
+    let mut board = board("..*..", AssistLevel::Full);
+    // The opening stops at the 1 next to the bomb, which is flagged
+    board.uncover(Coordinates { x: 0, y: 0 });
+    assert!(is_marked(&board, 2));
+    // The other 1 is satisfied by the assist flag
+    board.uncover(Coordinates { x: 3, y: 0 });
+    assert!(!is_covered(&board, 4));
+    // Flags alone leave it covered
+    let mut board = self::board("..*..", AssistLevel::AutoFlag);
+    board.uncover(Coordinates { x: 0, y: 0 });
+    board.uncover(Coordinates { x: 3, y: 0 });
+    assert!(is_covered(&board, 4));
+}
@@ -0,0 +1,123 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::{BoardAction, BoardCommands, BoardOptions, PlayerId, RevealOrigin};
+use common::{bomb_tiles, layout_board, tiles};
+
+mod common;
+
+/// An opening in the right part of the board, the mines in the left corner
+const LAYOUT: &str = "*.......\n**......\n........\n........";
+
+fn board() -> HeadlessBoard {
+    layout_board(LAYOUT, BoardOptions::default())
+}
+
+fn covered(board: &HeadlessBoard) -> Vec<Coordinates> {
+    tiles(board, |coords| {
+        board.board().covered_tiles.contains(&coords)
+    })
+}
+
+/// A safe tile without any neighbor mine, opening the board
+fn opening(board: &HeadlessBoard) -> Coordinates {
+    let tile_map = &board.board().tile_map;
+    tiles(board, |coords| {
+        !tile_map.is_bomb_at(coords) && tile_map.bomb_count_at(coords) == 0
+    })[0]
+}
+
+#[test]
+fn flags_on_uncovered_tiles_are_refused() {
+    let mut board = board();
+    let opening = opening(&board);
+    board.command(Some(PlayerId(0)), BoardAction::Reveal(opening));
+    // The second player flags the tile the first one revealed
+    board.command(Some(PlayerId(1)), BoardAction::Flag(opening));
+    assert!(!board.board().covered_tiles.contains(&opening));
+    assert_eq!(board.board().marked_count(), 0);
+    assert!(board.board().flag_owners.is_empty());
+}
+
+#[test]
+fn flags_are_attributed_and_protect_their_tile() {
+    let mut board = board();
+    let mine = bomb_tiles(&board)[0];
+    board.command(Some(PlayerId(1)), BoardAction::Flag(mine));
+    assert_eq!(board.board().flag_owners.get(&mine), Some(&PlayerId(1)));
+    // The flagged tile isn't revealed by the other player
+    board.command(Some(PlayerId(0)), BoardAction::Reveal(mine));
+    assert!(board.board().covered_tiles.contains(&mine));
+    // Removing the flag forgets its owner
+    board.command(Some(PlayerId(0)), BoardAction::Flag(mine));
+    assert!(board.board().flag_owners.is_empty());
+}
+
+#[test]
+fn same_history_same_board() {
+    let options = BoardOptions {
+        map_size: (16, 16),
+        bomb_count: 40,
+        seed: Some(7),
+        safe_start: true,
+        ..Default::default()
+    };
+    let mut first = HeadlessBoard::new(options.clone());
+    let mut second = HeadlessBoard::new(options);
+    // Both players reveal and flag the same tiles at once, in both orders
+    let mut commands = Vec::new();
+    for (i, coords) in covered(&first).iter().take(20).enumerate() {
+        let flag = (PlayerId(0), BoardAction::Flag(*coords));
+        let reveal = (PlayerId(1), BoardAction::Reveal(*coords));
+        if i % 2 == 0 {
+            commands.extend_from_slice(&[flag, reveal, reveal]);
+        } else {
+            commands.extend_from_slice(&[reveal, reveal, flag]);
+        }
+    }
+    for (player, action) in commands {
+        let command = first.command(Some(player), action);
+        assert_eq!(second.command(Some(player), action), command);
+    }
+    assert_eq!(first.status(), second.status());
+    assert_eq!(covered(&first), covered(&second));
+    assert_eq!(first.board().flag_owners, second.board().flag_owners);
+}
+
+#[test]
+fn shared_boards_apply_the_server_order() {
+    let mut board_commands = BoardCommands::shared();
+    let coords = Coordinates { x: 1, y: 2 };
+    // Local actions wait to be sent to the server
+    board_commands.request(BoardAction::Reveal(coords));
+    assert_eq!(board_commands.pop(), None);
+    assert_eq!(
+        board_commands.take_outbox(),
+        vec![BoardAction::Reveal(coords)]
+    );
+    // The server commands are only applied in sequence
+    let mut server = BoardCommands::default();
+    let first = server.push(Some(PlayerId(0)), BoardAction::Reveal(coords));
+    let second = server.push(Some(PlayerId(1)), BoardAction::Flag(coords));
+    assert!(!board_commands.receive(second));
+    assert!(board_commands.receive(first));
+    assert!(board_commands.receive(second));
+    assert_eq!(board_commands.pop(), Some(first));
+    assert_eq!(board_commands.pop(), Some(second));
+}
+
+#[test]
+fn bombs_revealed_after_a_win_do_not_explode() {
+    let mut board = board();
+    board.uncover(opening(&board));
+    board.uncover(Coordinates { x: 1, y: 3 });
+    assert_eq!(board.status(), GameStatus::Won);
+    // The bombs are uncovered once the game is over
+    board.command(None, BoardAction::Reveal(Coordinates { x: 0, y: 3 }));
+    assert!(!board
+        .board()
+        .covered_tiles
+        .contains(&Coordinates { x: 0, y: 3 }));
+    let mut reveal_origin = board.world_mut().resource_mut::<RevealOrigin>();
+    assert_eq!(reveal_origin.take_shockwave(), None);
+    assert!(!reveal_origin.explode(Coordinates { x: 0, y: 2 }));
+}
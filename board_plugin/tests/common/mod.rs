@@ -0,0 +1,33 @@
+//! Fixtures shared by the integration tests, each test using some of them
+#![allow(dead_code)]
+
+use board_plugin::components::Coordinates;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::{BoardLayout, BoardOptions};
+
+/// Board of the text `layout`, the other options being taken from `options`
+pub fn layout_board(layout: &str, options: BoardOptions) -> HeadlessBoard {
+    HeadlessBoard::new(BoardOptions {
+        layout: Some(BoardLayout::from_text(layout).unwrap()),
+        ..options
+    })
+}
+
+/// Tiles of the board matching `filter`
+pub fn tiles(board: &HeadlessBoard, filter: impl Fn(Coordinates) -> bool) -> Vec<Coordinates> {
+    let tile_map = &board.board().tile_map;
+    (0..tile_map.height())
+        .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinates { x, y }))
+        .filter(|coords| filter(*coords))
+        .collect()
+}
+
+/// Tiles of the board with a mine
+pub fn bomb_tiles(board: &HeadlessBoard) -> Vec<Coordinates> {
+    tiles(board, |coords| board.board().tile_map.is_bomb_at(coords))
+}
+
+/// Tiles of the board without any mine
+pub fn safe_tiles(board: &HeadlessBoard) -> Vec<Coordinates> {
+    tiles(board, |coords| !board.board().tile_map.is_bomb_at(coords))
+}
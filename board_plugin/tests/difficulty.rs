@@ -0,0 +1,96 @@
+use board_plugin::solver::{bbbv, is_no_guess, Difficulty, Technique};
+use board_plugin::{BoardLayout, TileMap};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn tile_map(layout: &str) -> TileMap {
+    TileMap::from_layout(&BoardLayout::from_text(layout).unwrap())
+}
+
+#[test]
+fn bbbv_counts_openings_and_lone_numbers() {
+    // A single opening with its border
+    assert_eq!(bbbv(&tile_map("*....*")), 1);
+    // Two openings
+    assert_eq!(bbbv(&tile_map("..*..")), 2);
+    // Numbers only
+    assert_eq!(bbbv(&tile_map("*..*..*")), 4);
+    assert_eq!(bbbv(&tile_map("...\n.*.\n...")), 8);
+    // An opening and the two numbers out of its border
+    assert_eq!(bbbv(&tile_map("....\n....\n.*..\n*...")), 3);
+}
+
+#[test]
+fn rating_adds_technique_and_guess_points() {
+    let difficulty = Difficulty::analyze(&tile_map(".....\n.....\n..*..\n.*.*.\n....."), true);
+    let points: f32 = difficulty
+        .techniques
+        .iter()
+        .map(|(technique, count)| {
+            let points = match technique {
+                Technique::SingleCell => 0.,
+                Technique::Subset => 1.,
+                Technique::Pattern => 3.,
+                Technique::GlobalCount => 5.,
+            };
+            points * *count as f32
+        })
+        .sum();
+    assert_eq!(
+        difficulty.rating,
+        difficulty.bbbv as f32 + points + difficulty.guesses as f32 * 20.
+    );
+    assert_eq!(difficulty.guesses, 0);
+    assert_eq!(difficulty.survival, 1.);
+}
+
+#[test]
+fn guesses_lower_the_survival() {
+    // The two tiles below the 1s can't be told apart
+    let difficulty = Difficulty::analyze(&tile_map("*.\n..\n.."), true);
+    assert_eq!(difficulty.guesses, 1);
+    assert_eq!(difficulty.survival, 0.5);
+}
+
+#[test]
+fn generation_targets_the_rating_band() {
+    let template = TileMap::empty(9, 9);
+    let band = 15.0..=25.;
+    let (tile_map, difficulty) = Difficulty::generate(
+        &template,
+        10,
+        true,
+        band.clone(),
+        &mut StdRng::seed_from_u64(7),
+    );
+    assert!(band.contains(&difficulty.rating));
+    assert_eq!(tile_map.bomb_count(), 10);
+    // The same seed gives the same board and rating
+    let (same_map, same) =
+        Difficulty::generate(&template, 10, true, band, &mut StdRng::seed_from_u64(7));
+    assert_eq!(same_map.layout(), tile_map.layout());
+    assert_eq!(same.rating, difficulty.rating);
+}
+
+#[test]
+fn no_guess_boards_are_found_for_the_presets() {
+    for (width, height, bombs) in [(9, 9, 10), (16, 16, 40), (30, 16, 99)] {
+        let template = TileMap::empty(width, height);
+        for seed in 0..10 {
+            let (tile_map, difficulty) =
+                Difficulty::generate_no_guess(&template, bombs, &mut StdRng::seed_from_u64(seed));
+            assert!(is_no_guess(&tile_map));
+            assert_eq!(difficulty.guesses, 0);
+        }
+    }
+}
+
+#[test]
+fn impossible_no_guess_boards_need_guesses() {
+    // Without any empty tile, there is no safe start
+    let (tile_map, difficulty) =
+        Difficulty::generate_no_guess(&TileMap::empty(2, 1), 1, &mut StdRng::seed_from_u64(7));
+    assert_eq!(tile_map.bomb_count(), 1);
+    assert!(!is_no_guess(&tile_map));
+    assert!(difficulty.guesses > 0);
+}
@@ -0,0 +1,54 @@
+use board_plugin::components::{ChunkCoordinates, WorldCoordinates};
+use board_plugin::{Cell, ChunkMap, CHUNK_SIZE};
+
+/// Bombs of the square of `radius` chunks around the world origin
+fn bombs(chunk_map: &mut ChunkMap, radius: i32) -> Vec<bool> {
+    let tiles = radius * i32::from(CHUNK_SIZE);
+    (-tiles..tiles)
+        .flat_map(|y| (-tiles..tiles).map(move |x| WorldCoordinates { x, y }))
+        .map(|coords| chunk_map.is_bomb_at(coords))
+        .collect()
+}
+
+#[test]
+fn same_seed_same_chunks() {
+    let first = bombs(&mut ChunkMap::new(2026, 0.2), 2);
+    // Chunks don't depend on the order they are generated in
+    let mut second = ChunkMap::new(2026, 0.2);
+    second.is_bomb_at(WorldCoordinates { x: 100, y: -100 });
+    assert_eq!(bombs(&mut second, 2), first);
+    assert_ne!(bombs(&mut ChunkMap::new(2027, 0.2), 2), first);
+}
+
+#[test]
+fn same_seed_same_uncovered_tiles() {
+    let mut first = ChunkMap::new(7, 0.15);
+    let mut second = ChunkMap::new(7, 0.15);
+    assert!(!first.uncover(WorldCoordinates::default()));
+    assert!(!second.uncover(WorldCoordinates::default()));
+    assert_eq!(first.cleared(), second.cleared());
+    let chunk = ChunkCoordinates::default();
+    assert_eq!(first.chunk_cells(chunk), second.chunk_cells(chunk));
+}
+
+#[test]
+fn flood_fill_crosses_chunk_borders() {
+    let mut chunk_map = ChunkMap::new(0, 0.5);
+    // The tiles around the origin are safe, the origin being the corner of 4 chunks
+    assert!(!chunk_map.uncover(WorldCoordinates::default()));
+    assert_eq!(
+        chunk_map.cell(WorldCoordinates::default()),
+        Cell::Revealed(0)
+    );
+    for (x, y) in [(-1, -1), (-1, 0), (0, -1)] {
+        assert!(matches!(
+            chunk_map.cell(WorldCoordinates { x, y }),
+            Cell::Revealed(_)
+        ));
+    }
+    let dirty = chunk_map.take_dirty();
+    for (x, y) in [(-1, -1), (-1, 0), (0, -1), (0, 0)] {
+        assert!(dirty.contains(&ChunkCoordinates { x, y }));
+    }
+    assert!(chunk_map.take_dirty().is_empty());
+}
@@ -0,0 +1,31 @@
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::solver::is_no_guess;
+use board_plugin::BoardOptions;
+
+/// Seeded no-guess intermediate board
+fn board(seed: u64) -> HeadlessBoard {
+    HeadlessBoard::new(BoardOptions {
+        map_size: (16, 16),
+        bomb_count: 40,
+        seed: Some(seed),
+        no_guess: true,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn same_seed_same_board() {
+    let first = board(20_261_018);
+    let second = board(20_261_018);
+    assert_eq!(
+        first.board().tile_map.layout(),
+        second.board().tile_map.layout()
+    );
+}
+
+#[test]
+fn no_guess_boards_are_solvable() {
+    for seed in 0..5 {
+        assert!(is_no_guess(&board(seed).board().tile_map));
+    }
+}
@@ -0,0 +1,85 @@
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::{BoardOptions, HotSeatOptions, MinePenalty, PlayerId, Players};
+use common::{bomb_tiles, layout_board, safe_tiles};
+
+mod common;
+
+/// Every safe tile is next to a mine, a reveal uncovering a single tile
+const LAYOUT: &str = "*.*\n.*.";
+
+/// Hot-seat board of `players` on `LAYOUT`
+fn board(players: u8, mine_penalty: MinePenalty) -> HeadlessBoard {
+    let options = BoardOptions {
+        hot_seat: Some(HotSeatOptions {
+            players,
+            mine_penalty,
+        }),
+        ..Default::default()
+    };
+    layout_board(LAYOUT, options)
+}
+
+fn players(board: &HeadlessBoard) -> &Players {
+    board.board().players.as_ref().unwrap()
+}
+
+#[test]
+fn players_take_turns() {
+    let mut board = board(3, MinePenalty::default());
+    let safe = safe_tiles(&board);
+    board.uncover(safe[0]);
+    board.uncover(safe[1]);
+    let players = players(&board);
+    assert_eq!(players.current, PlayerId(2));
+    assert_eq!(players.score(PlayerId(0)).points, 1);
+    assert_eq!(players.score(PlayerId(1)).points, 1);
+    assert_eq!(players.revealed_by.get(&safe[1]), Some(&PlayerId(1)));
+}
+
+#[test]
+fn covered_or_marked_tiles_keep_the_turn() {
+    let mut board = board(2, MinePenalty::default());
+    let safe = safe_tiles(&board);
+    board.toggle_mark(safe[0]);
+    board.uncover(safe[0]);
+    assert_eq!(players(&board).current, PlayerId(0));
+}
+
+#[test]
+fn mines_cost_points() {
+    let mut board = board(2, MinePenalty::Points(5));
+    let bombs = bomb_tiles(&board);
+    board.uncover(bombs[0]);
+    assert_eq!(board.status(), GameStatus::Playing);
+    let players = players(&board);
+    assert_eq!(players.score(PlayerId(0)).points, -5);
+    assert_eq!(players.current, PlayerId(1));
+}
+
+#[test]
+fn eliminated_players_are_skipped() {
+    let mut board = board(2, MinePenalty::Eliminate);
+    let bombs = bomb_tiles(&board);
+    let safe = safe_tiles(&board);
+    board.uncover(bombs[0]);
+    board.uncover(safe[0]);
+    assert_eq!(players(&board).current, PlayerId(1));
+    board.uncover(bombs[1]);
+    assert_eq!(board.status(), GameStatus::Lost);
+}
+
+#[test]
+fn board_completes_with_revealed_mines() {
+    let mut board = board(2, MinePenalty::Points(1));
+    let bombs = bomb_tiles(&board);
+    let safe = safe_tiles(&board);
+    board.uncover(bombs[0]);
+    for coords in &safe {
+        board.uncover(*coords);
+    }
+    assert_eq!(board.status(), GameStatus::Won);
+    let standings = players(&board).standings();
+    assert_eq!(standings[0].1, PlayerId(1));
+    assert_eq!(standings[0].2.points, 2);
+    assert_eq!((standings[1].0, standings[1].2.points), (2, 0));
+}
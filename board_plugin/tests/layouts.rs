@@ -0,0 +1,132 @@
+use board_plugin::components::Coordinates;
+use board_plugin::{BoardLayout, LayoutError, LayoutFormat};
+
+/// Asymmetric layout, so that flipped rows or columns don't round trip
+fn layout() -> BoardLayout {
+    BoardLayout::from_text("*...\n..*.\n....\n**..").unwrap()
+}
+
+#[test]
+fn text_is_read_top_line_first() {
+    let layout = layout();
+    assert_eq!((layout.width, layout.height), (4, 4));
+    assert_eq!(
+        layout.mines,
+        vec![
+            Coordinates { x: 0, y: 3 },
+            Coordinates { x: 2, y: 2 },
+            Coordinates { x: 0, y: 0 },
+            Coordinates { x: 1, y: 0 },
+        ]
+    );
+}
+
+#[test]
+fn text_round_trips() {
+    let layout = layout();
+    assert_eq!(layout.to_text(), "*...\n..*.\n....\n**..\n");
+    assert_eq!(BoardLayout::from_text(&layout.to_text()).unwrap(), layout);
+}
+
+#[test]
+fn json_round_trips() {
+    let layout = layout();
+    assert_eq!(
+        BoardLayout::from_json(&layout.to_json().unwrap()).unwrap(),
+        layout
+    );
+}
+
+#[test]
+fn mbf_round_trips() {
+    let layout = layout();
+    let data = layout.to_mbf().unwrap();
+    // MBF rows go downwards
+    assert_eq!(data, vec![4, 4, 0, 4, 0, 0, 2, 1, 0, 3, 1, 3]);
+    assert_eq!(BoardLayout::from_mbf(&data).unwrap(), layout);
+}
+
+#[test]
+fn every_format_round_trips() {
+    let layout = layout();
+    for format in [LayoutFormat::Text, LayoutFormat::Json, LayoutFormat::Mbf] {
+        let data = layout.write(format).unwrap();
+        assert_eq!(BoardLayout::read(format, &data).unwrap(), layout);
+    }
+}
+
+#[test]
+fn ragged_rows_are_refused() {
+    assert!(matches!(
+        BoardLayout::from_text("*..\n\n..\n..."),
+        Err(LayoutError::InconsistentWidth { line: 3 })
+    ));
+}
+
+#[test]
+fn unknown_characters_are_refused() {
+    assert!(matches!(
+        BoardLayout::from_text("*..\n.x."),
+        Err(LayoutError::InvalidCharacter {
+            line: 2,
+            column: 2,
+            c: 'x'
+        })
+    ));
+}
+
+#[test]
+fn empty_layouts_are_refused() {
+    assert!(matches!(
+        BoardLayout::from_text("\n  \n"),
+        Err(LayoutError::Empty)
+    ));
+    assert!(matches!(
+        BoardLayout::from_mbf(&[0, 3, 0, 0]),
+        Err(LayoutError::Empty)
+    ));
+}
+
+#[test]
+fn bad_mbf_header_or_length_is_refused() {
+    assert!(matches!(
+        BoardLayout::from_mbf(&[4, 4, 0]),
+        Err(LayoutError::InvalidLength {
+            expected: 4,
+            found: 3
+        })
+    ));
+    assert!(matches!(
+        BoardLayout::from_mbf(&[4, 4, 0, 2, 0, 0]),
+        Err(LayoutError::InvalidLength {
+            expected: 8,
+            found: 6
+        })
+    ));
+    assert!(matches!(
+        BoardLayout::from_mbf(&[4, 4, 0, 1, 4, 0]),
+        Err(LayoutError::OutOfBounds(Coordinates { x: 4, y: 0 }))
+    ));
+}
+
+#[test]
+fn too_many_mines_are_refused() {
+    assert!(matches!(
+        BoardLayout::from_mbf(&[1, 2, 0, 3, 0, 0, 0, 1, 0, 0]),
+        Err(LayoutError::TooManyMines { mines: 3, tiles: 2 })
+    ));
+    assert!(matches!(
+        BoardLayout::from_mbf(&[2, 2, 0, 2, 1, 1, 1, 1]),
+        Err(LayoutError::DuplicateMine(Coordinates { x: 1, y: 0 }))
+    ));
+}
+
+#[test]
+fn large_layouts_are_not_mbf() {
+    let layout = BoardLayout {
+        width: 256,
+        height: 1,
+        mines: vec![],
+    };
+    assert!(matches!(layout.to_mbf(), Err(LayoutError::Unsupported(_))));
+}
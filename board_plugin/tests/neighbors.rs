@@ -0,0 +1,216 @@
+use board_plugin::components::Coordinates;
+use board_plugin::{
+    BoardLayout, Neighborhood, ShapeMask, Tile, TileMap, Topology, MAX_CUSTOM_NEIGHBORS,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::BTreeSet;
+
+fn tile_map(layout: &str) -> TileMap {
+    TileMap::from_layout(&BoardLayout::from_text(layout).unwrap())
+}
+
+fn neighbors(tile_map: &TileMap, x: u16, y: u16) -> BTreeSet<(u16, u16)> {
+    tile_map
+        .neighbors(Coordinates { x, y })
+        .map(|c| (c.x, c.y))
+        .collect()
+}
+
+fn set(tiles: &[(u16, u16)]) -> BTreeSet<(u16, u16)> {
+    tiles.iter().copied().collect()
+}
+
+#[test]
+fn square_tiles_have_8_neighbors() {
+    let tile_map = tile_map("...\n...\n...");
+    assert_eq!(neighbors(&tile_map, 1, 1).len(), 8);
+    assert_eq!(neighbors(&tile_map, 0, 0), set(&[(1, 0), (0, 1), (1, 1)]));
+}
+
+#[test]
+fn hex_odd_rows_are_shifted_right() {
+    let mut tile_map = TileMap::empty(4, 4);
+    tile_map.set_topology(Topology::Hex, false);
+    // Even rows reach the column on their left above and below
+    assert_eq!(
+        neighbors(&tile_map, 1, 2),
+        set(&[(0, 1), (1, 1), (0, 2), (2, 2), (0, 3), (1, 3)])
+    );
+    // Odd rows reach the column on their right
+    assert_eq!(
+        neighbors(&tile_map, 1, 1),
+        set(&[(1, 0), (2, 0), (0, 1), (2, 1), (1, 2), (2, 2)])
+    );
+    assert_eq!(neighbors(&tile_map, 3, 1), set(&[(3, 0), (2, 1), (3, 2)]));
+}
+
+#[test]
+fn hex_counts_follow_the_shift() {
+    let mut tile_map = tile_map("....\n....\n.*..\n....");
+    tile_map.set_topology(Topology::Hex, false);
+    // The bomb on the odd row 1 touches (1, 0) and (2, 0) below it, not (0, 0)
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 0, y: 0 }), 0);
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 1, y: 0 }), 1);
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 2, y: 0 }), 1);
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 2, y: 2 }), 1);
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 0, y: 2 }), 0);
+}
+
+#[test]
+fn wrapped_edges_touch_the_opposite_edges() {
+    let mut tile_map = tile_map("*...\n....\n...*");
+    tile_map.set_topology(Topology::Square, true);
+    assert_eq!(
+        neighbors(&tile_map, 0, 0),
+        set(&[
+            (3, 2),
+            (0, 2),
+            (1, 2),
+            (3, 0),
+            (1, 0),
+            (3, 1),
+            (0, 1),
+            (1, 1)
+        ])
+    );
+    // Both bombs are across an edge
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 0, y: 0 }), 2);
+}
+
+#[test]
+fn narrow_wrapped_boards_count_each_neighbor_once() {
+    // Every delta of a single column reaches the column itself
+    let mut column = tile_map("*\n.\n.");
+    column.set_topology(Topology::Square, true);
+    assert_eq!(neighbors(&column, 0, 1), set(&[(0, 0), (0, 2)]));
+    assert_eq!(column.bomb_count_at(Coordinates { x: 0, y: 1 }), 1);
+    // Both rows of a 2 rows board are above and below each other
+    let mut square = tile_map("*.\n..");
+    square.set_topology(Topology::Square, true);
+    assert_eq!(neighbors(&square, 1, 0), set(&[(0, 0), (0, 1), (1, 1)]));
+    assert_eq!(square.bomb_count_at(Coordinates { x: 1, y: 0 }), 1);
+}
+
+#[test]
+fn odd_hex_boards_only_wrap_horizontally() {
+    let mut tile_map = TileMap::empty(4, 3);
+    tile_map.set_topology(Topology::Hex, true);
+    assert_eq!(
+        neighbors(&tile_map, 0, 0),
+        set(&[(3, 0), (1, 0), (3, 1), (0, 1)])
+    );
+}
+
+/// Bomb count of the center of a 5x5 board, bombs on every other tile
+fn center_count(neighborhood: Neighborhood) -> u8 {
+    let mut tile_map = tile_map("*.*.*\n.*.*.\n*...*\n.*.*.\n*.*.*");
+    tile_map.set_neighborhood(neighborhood).unwrap();
+    tile_map.bomb_count_at(Coordinates { x: 2, y: 2 })
+}
+
+#[test]
+fn neighborhoods_change_the_counts() {
+    assert_eq!(center_count(Neighborhood::Adjacent), 4);
+    assert_eq!(center_count(Neighborhood::Orthogonal), 0);
+    assert_eq!(center_count(Neighborhood::Knight), 0);
+    assert_eq!(center_count(Neighborhood::Radius2), 12);
+    assert_eq!(center_count("-2,-2;2,2;0,1".parse().unwrap()), 2);
+}
+
+/// Deltas of the `count` first tiles of the 11x11 square around the tile
+fn custom_deltas(count: usize) -> Vec<(i8, i8)> {
+    (-5..=5)
+        .flat_map(|y| (-5..=5).map(move |x| (x, y)))
+        .filter(|delta| *delta != (0, 0))
+        .take(count)
+        .collect()
+}
+
+/// Custom neighborhood of the `count` first tiles of the 11x11 square around the tile
+fn custom(count: usize) -> String {
+    custom_deltas(count)
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[test]
+fn custom_neighborhoods_fit_the_counts() {
+    assert!(custom(MAX_CUSTOM_NEIGHBORS).parse::<Neighborhood>().is_ok());
+    assert!(custom(MAX_CUSTOM_NEIGHBORS + 1)
+        .parse::<Neighborhood>()
+        .is_err());
+    // The tile itself and repeated deltas aren't neighbors
+    let repeated = format!("{};0,0;-5,-5", custom(MAX_CUSTOM_NEIGHBORS));
+    assert!(repeated.parse::<Neighborhood>().is_ok());
+    // Directly built neighborhoods are checked by the tile map
+    let mut tile_map = TileMap::empty(5, 5);
+    let too_many = Neighborhood::Custom(custom_deltas(MAX_CUSTOM_NEIGHBORS + 1));
+    assert!(tile_map.set_neighborhood(too_many).is_err());
+    assert_eq!(*tile_map.neighborhood(), Neighborhood::Adjacent);
+}
+
+#[test]
+fn custom_neighborhoods_stay_close() {
+    assert!("5,-5;-5,5".parse::<Neighborhood>().is_ok());
+    assert!("6,0".parse::<Neighborhood>().is_err());
+    assert!("0,-128".parse::<Neighborhood>().is_err());
+    let far = Neighborhood::Custom(vec![(-128, 0), (1, 0)]);
+    assert!(TileMap::empty(5, 5).set_neighborhood(far.clone()).is_err());
+    // The diagram leaves out the far neighbors
+    let diagram = far.diagram();
+    assert_eq!(diagram.len(), 11);
+    assert_eq!(diagram[5], ".....ox....");
+    assert_eq!(
+        "0,1;1,-1".parse::<Neighborhood>().unwrap().diagram(),
+        vec![".x.", ".o.", "..x"]
+    );
+}
+
+#[test]
+fn void_tiles_are_not_neighbors() {
+    let mut tile_map = tile_map("*..\n.*.\n...");
+    tile_map.set_mask(&ShapeMask::from_text("###\n#.#\n###").unwrap());
+    // The bomb of the void center is removed and no longer counted
+    assert_eq!(tile_map.bomb_count(), 1);
+    assert_eq!(*tile_map.get_tile(Coordinates { x: 1, y: 1 }), Tile::Void);
+    assert_eq!(
+        neighbors(&tile_map, 0, 1),
+        set(&[(0, 0), (1, 0), (1, 2), (0, 2)])
+    );
+    assert_eq!(tile_map.bomb_count_at(Coordinates { x: 0, y: 1 }), 1);
+    assert_eq!(*tile_map.get_tile(Coordinates { x: 2, y: 0 }), Tile::Empty);
+}
+
+#[test]
+fn multi_mine_counts_sum_the_mines() {
+    let mut tile_map = TileMap::empty(5, 5);
+    tile_map.set_max_mines_per_cell(5);
+    assert_eq!(tile_map.max_mines_per_cell(), 3);
+    tile_map.set_bombs_with(40, &mut StdRng::seed_from_u64(37));
+    let tiles: Vec<Coordinates> = (0..5)
+        .flat_map(|y| (0..5).map(move |x| Coordinates { x, y }))
+        .collect();
+    let mines: Vec<u8> = tiles.iter().map(|c| tile_map.mines_at(*c)).collect();
+    assert_eq!(mines.iter().map(|m| u16::from(*m)).sum::<u16>(), 40);
+    assert!(mines.iter().all(|m| *m <= 3));
+    assert!(mines.iter().any(|m| *m > 1));
+    for coords in tiles.iter().filter(|c| !tile_map.is_bomb_at(**c)) {
+        let expected: u8 = tile_map
+            .neighbors(*coords)
+            .map(|c| tile_map.mines_at(c))
+            .sum();
+        assert_eq!(tile_map.bomb_count_at(*coords), expected);
+    }
+}
+
+#[test]
+fn multi_mine_bombs_fit_the_tiles() {
+    let mut tile_map = TileMap::empty(2, 2);
+    tile_map.set_max_mines_per_cell(2);
+    tile_map.set_bombs_with(10, &mut StdRng::seed_from_u64(37));
+    assert_eq!(tile_map.bomb_count(), 8);
+    assert!(tile_map.iter().all(|tile| *tile == Tile::Bomb(2)));
+}
@@ -0,0 +1,83 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::{BoardOptions, LayoutError, Puzzle};
+
+fn coords(x: u16, y: u16) -> Coordinates {
+    Coordinates { x, y }
+}
+
+/// The puzzle shipped with the game
+fn puzzle() -> Puzzle {
+    serde_json::from_str(include_str!("../../assets/puzzles/six_by_six.puzzle.json")).unwrap()
+}
+
+#[test]
+fn puzzles_are_parsed() {
+    let puzzle = puzzle();
+    assert_eq!(puzzle.title, "Six by Six");
+    assert_eq!(puzzle.par_time, 120);
+    assert_eq!((puzzle.layout.width, puzzle.layout.height), (6, 6));
+    assert_eq!(puzzle.layout.mines.len(), 6);
+    assert_eq!(puzzle.revealed.len(), 11);
+    assert_eq!(puzzle.flagged, vec![coords(0, 0)]);
+    assert!(puzzle.validate().is_ok());
+    // The start state is optional
+    let puzzle: Puzzle = serde_json::from_str(
+        r#"{"title": "Empty", "par_time": 10, "layout": {"width": 2, "height": 1, "mines": []}}"#,
+    )
+    .unwrap();
+    assert!(puzzle.revealed.is_empty() && puzzle.flagged.is_empty());
+}
+
+#[test]
+fn invalid_start_states_are_refused() {
+    let mut puzzle = puzzle();
+    puzzle.revealed.push(coords(6, 0));
+    assert!(matches!(
+        puzzle.validate(),
+        Err(LayoutError::OutOfBounds(c)) if c == coords(6, 0)
+    ));
+
+    let mut puzzle = self::puzzle();
+    puzzle.flagged.push(coords(0, 6));
+    assert!(matches!(
+        puzzle.validate(),
+        Err(LayoutError::OutOfBounds(c)) if c == coords(0, 6)
+    ));
+
+    let mut puzzle = self::puzzle();
+    puzzle.revealed.push(coords(3, 0));
+    assert!(matches!(
+        puzzle.validate(),
+        Err(LayoutError::RevealedMine(c)) if c == coords(3, 0)
+    ));
+
+    let mut puzzle = self::puzzle();
+    puzzle.flagged.push(coords(0, 1));
+    assert!(matches!(
+        puzzle.validate(),
+        Err(LayoutError::RevealedFlag(c)) if c == coords(0, 1)
+    ));
+}
+
+#[test]
+fn boards_start_from_the_puzzle_state() {
+    let puzzle = puzzle();
+    let board = HeadlessBoard::new(BoardOptions {
+        puzzle: Some(puzzle.clone()),
+        ..Default::default()
+    });
+    let board = board.board();
+    assert_eq!(board.tile_map.bomb_count(), 6);
+    for coords in puzzle.layout.mines.iter() {
+        assert!(board.tile_map.is_bomb_at(*coords));
+    }
+    assert_eq!(board.covered_tiles.len(), 36 - puzzle.revealed.len());
+    for coords in puzzle.revealed.iter() {
+        assert!(!board.covered_tiles.contains(coords));
+    }
+    assert_eq!(board.marked_tiles.len(), 1);
+    assert!(board.marked_tiles.contains_key(&coords(0, 0)));
+    // Puzzles don't get a safe start
+    assert!(board.pending_uncovers.is_empty());
+}
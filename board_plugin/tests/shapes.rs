@@ -0,0 +1,19 @@
+use board_plugin::components::Coordinates;
+use board_plugin::ShapeMask;
+
+#[test]
+fn masks_round_trip() {
+    let mask = ShapeMask::from_text("#.\n##").unwrap();
+    let json = serde_json::to_string(&mask).unwrap();
+    assert_eq!(serde_json::from_str::<ShapeMask>(&json).unwrap(), mask);
+    assert!(mask.contains(Coordinates { x: 0, y: 1 }));
+    assert!(!mask.contains(Coordinates { x: 1, y: 1 }));
+}
+
+#[test]
+fn masks_must_match_their_size() {
+    let short = r#"{"width": 2, "height": 2, "cells": [true, true, false]}"#;
+    assert!(serde_json::from_str::<ShapeMask>(short).is_err());
+    let long = r#"{"width": 1, "height": 1, "cells": [true, true]}"#;
+    assert!(serde_json::from_str::<ShapeMask>(long).is_err());
+}
@@ -0,0 +1,205 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::solver::{
+    find_deduction, find_hint, BoardView, HintKind, MineProbabilities, Technique, TileView,
+};
+use board_plugin::{BoardLayout, BoardOptions, TileMap};
+
+/// View of a grid drawn top line first: `*` is a covered bomb, `#` a covered safe tile, `F` a
+/// marked bomb, `x` a marked safe tile and `.` an uncovered tile
+fn view(grid: &str) -> BoardView {
+    let layout: String = grid
+        .chars()
+        .map(|c| match c {
+            '*' | 'F' => '*',
+            '\n' => '\n',
+            _ => '.',
+        })
+        .collect();
+    let tile_map = TileMap::from_layout(&BoardLayout::from_text(&layout).unwrap());
+    let mut view = BoardView::covered(&tile_map);
+    let height = tile_map.height();
+    for (row, line) in grid.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            let coords = Coordinates {
+                x: x as u16,
+                y: height - 1 - row as u16,
+            };
+            match c {
+                'F' | 'x' => view.set(coords, TileView::Marked),
+                '.' => view.set(coords, TileView::Revealed(tile_map.bomb_count_at(coords))),
+                _ => (),
+            }
+        }
+    }
+    view
+}
+
+fn coords(x: u16, y: u16) -> Coordinates {
+    Coordinates { x, y }
+}
+
+#[test]
+fn single_cell_finds_the_last_bombs() {
+    let deduction = find_deduction(&view("*.\n..")).unwrap();
+    assert_eq!(deduction.technique, Technique::SingleCell);
+    assert_eq!(deduction.mines, vec![coords(0, 1)]);
+    assert!(deduction.safe.is_empty());
+}
+
+#[test]
+fn single_cell_finds_satisfied_numbers() {
+    let deduction = find_deduction(&view("F#\n..")).unwrap();
+    assert_eq!(deduction.technique, Technique::SingleCell);
+    assert_eq!(deduction.safe, vec![coords(1, 1)]);
+    assert_eq!(deduction.reasons, vec![coords(0, 0)]);
+}
+
+#[test]
+fn subset_removes_the_shared_neighbors() {
+    // The 1 at the left edge holds the bomb of the middle 1
+    let deduction = find_deduction(&view("#*#\n...")).unwrap();
+    assert_eq!(deduction.technique, Technique::Subset);
+    assert_eq!(deduction.safe, vec![coords(2, 1)]);
+    assert_eq!(deduction.reasons, vec![coords(0, 0), coords(1, 0)]);
+}
+
+#[test]
+fn pattern_enumerates_overlapping_numbers() {
+    let deduction = find_deduction(&view("*#*\n..*\n..#")).unwrap();
+    assert_eq!(deduction.technique, Technique::Pattern);
+    assert_eq!(deduction.mines, vec![coords(2, 2)]);
+}
+
+#[test]
+fn global_count_uses_the_remaining_bombs() {
+    let deduction = find_deduction(&view("#*.#\n*..*")).unwrap();
+    assert_eq!(deduction.technique, Technique::GlobalCount);
+    assert_eq!(deduction.mines, vec![coords(1, 1)]);
+}
+
+#[test]
+fn no_deduction_on_a_fifty_fifty() {
+    assert!(find_deduction(&view("*#\n..")).is_none());
+}
+
+fn assert_close(probabilities: &MineProbabilities, x: u16, y: u16, expected: f32) {
+    let p = probabilities.get(coords(x, y)).unwrap();
+    assert!(
+        (p - expected).abs() < 1e-5,
+        "{} at ({}, {}), expected {}",
+        p,
+        x,
+        y,
+        expected
+    );
+}
+
+#[test]
+fn one_two_one_probabilities_are_exact() {
+    let probabilities = MineProbabilities::compute(&view("*#*\n...")).unwrap();
+    assert!(probabilities.is_exact());
+    assert_close(&probabilities, 0, 1, 1.);
+    assert_close(&probabilities, 1, 1, 0.);
+    assert_close(&probabilities, 2, 1, 1.);
+    assert!(probabilities.get(coords(0, 0)).is_none());
+}
+
+#[test]
+fn probabilities_weigh_the_interior() {
+    // With 2 bombs, either the middle tile and an interior tile, or both outer tiles: the
+    // frontier configurations don't have the same weight
+    let probabilities = MineProbabilities::compute(&view("#.*.#*#")).unwrap();
+    assert!(probabilities.is_exact());
+    assert_close(&probabilities, 2, 0, 2. / 3.);
+    assert_close(&probabilities, 0, 0, 1. / 3.);
+    assert_close(&probabilities, 4, 0, 1. / 3.);
+    assert_close(&probabilities, 5, 0, 1. / 3.);
+    assert_close(&probabilities, 6, 0, 1. / 3.);
+}
+
+/// Uncovered tiles on every other row and column, surrounded by covered tiles: a single frontier
+/// too large to be enumerated
+fn lattice(size: usize) -> String {
+    (0..size)
+        .map(|row| {
+            let line: String = (0..size)
+                .map(|x| match (x % 2, row % 2, (x + 3 * row) % 5) {
+                    (1, 1, _) => '.',
+                    (_, _, 0) | (_, _, 2) => '*',
+                    _ => '#',
+                })
+                .collect();
+            line + "\n"
+        })
+        .collect()
+}
+
+#[test]
+fn large_frontiers_are_sampled() {
+    let view = view(&lattice(21));
+    assert!(find_deduction(&view).is_none());
+    let probabilities = MineProbabilities::compute(&view).unwrap();
+    assert!(!probabilities.is_exact());
+    assert_eq!(probabilities.iter().count(), view.covered_tiles().len());
+    assert!(probabilities.iter().all(|(_, p)| (0. ..=1.).contains(&p)));
+}
+
+#[test]
+fn wrong_marks_are_only_found_from_the_numbers() {
+    let view = view("xF\n..");
+    assert_eq!(
+        view.overmarked(),
+        Some((coords(0, 0), vec![coords(0, 1), coords(1, 1)]))
+    );
+    // A wrong mark the numbers agree with isn't pointed out
+    assert_eq!(self::view("x*\n.#").overmarked(), None);
+}
+
+#[test]
+fn hints_point_out_marks_exceeding_a_number() {
+    let mut board = HeadlessBoard::new(BoardOptions {
+        layout: Some(BoardLayout::from_text("..*\n..*\n...").unwrap()),
+        ..Default::default()
+    });
+    // The opening leaves the right column covered, its bottom tile being safe
+    board.uncover(coords(0, 0));
+    board.toggle_mark(coords(2, 1));
+    let hint = find_hint(board.board()).unwrap();
+    assert_eq!((hint.coords, hint.kind), (coords(2, 0), HintKind::Safe));
+    board.toggle_mark(coords(2, 0));
+    let hint = find_hint(board.board()).unwrap();
+    assert_eq!(hint.kind, HintKind::WrongMark);
+    assert_eq!(hint.reasons, vec![coords(1, 0)]);
+    assert!(board.board().marked_tiles.contains_key(&hint.coords));
+}
+
+/// Largest gap between the sampled and the exact probabilities of `grid`
+fn sampling_error(grid: &str) -> f32 {
+    let view = view(grid);
+    let exact = MineProbabilities::compute(&view).unwrap();
+    let sampled = MineProbabilities::sampled(&view).unwrap();
+    assert!(exact.is_exact() && !sampled.is_exact());
+    assert_eq!(exact.iter().count(), sampled.iter().count());
+    exact
+        .iter()
+        .map(|(coords, p)| (sampled.get(coords).unwrap() - p).abs())
+        .fold(0., f32::max)
+}
+
+#[test]
+fn sampling_matches_the_enumeration() {
+    // Consecutive samples are correlated, so the estimations are only close
+    assert!(sampling_error("#.*.#*#") < 0.15);
+    assert!(sampling_error("#*##*#\n#....#\n*....*\n#....#\n##*#*#\n*#####") < 0.15);
+}
+
+#[test]
+fn sampling_is_seeded_by_the_view() {
+    let view = view(&lattice(21));
+    let first = MineProbabilities::compute(&view).unwrap();
+    let second = MineProbabilities::compute(&view).unwrap();
+    assert!(first
+        .iter()
+        .all(|(coords, p)| second.get(coords) == Some(p)));
+}
@@ -0,0 +1,113 @@
+use bevy::ecs::event::Events;
+use board_plugin::components::Coordinates;
+use board_plugin::events::BoardCompletedEvent;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::sound::{SoundEffect, SoundPlugin, SoundQueue};
+use board_plugin::{BoardAction, BoardLayout, BoardOptions, SoundOptions};
+
+/// Board of the text `layout` with the sound plugin
+fn board(layout: &str) -> HeadlessBoard {
+    HeadlessBoard::with_plugin(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text(layout).unwrap()),
+            ..Default::default()
+        },
+        SoundPlugin,
+    )
+}
+
+/// Sounds queued since the last call
+fn take_effects(board: &mut HeadlessBoard) -> Vec<SoundEffect> {
+    board
+        .world_mut()
+        .resource_mut::<SoundQueue>()
+        .0
+        .drain(..)
+        .map(|sound| sound.effect)
+        .collect()
+}
+
+/// Flood fill of 28 tiles, leaving the top left safe tile covered
+const OPENING: &str = "*.......\n**......\n........\n........";
+
+#[test]
+fn number_reveals() {
+    let mut board = board("*..\n...\n...");
+    board.uncover(Coordinates { x: 1, y: 1 });
+    assert_eq!(take_effects(&mut board), vec![SoundEffect::Reveal]);
+}
+
+#[test]
+fn big_opening_plays_once_over() {
+    let mut board = board(OPENING);
+    board.uncover(Coordinates { x: 7, y: 0 });
+    assert_eq!(
+        take_effects(&mut board),
+        vec![SoundEffect::Reveal, SoundEffect::Opening { tiles: 28 }]
+    );
+    board.uncover(Coordinates { x: 1, y: 3 });
+    assert_eq!(
+        take_effects(&mut board),
+        vec![SoundEffect::Reveal, SoundEffect::Victory]
+    );
+}
+
+#[test]
+fn opening_volume_grows_with_its_size() {
+    let options = SoundOptions::default();
+    let volume = |tiles| SoundEffect::Opening { tiles }.volume(&options);
+    assert!(volume(10) < volume(20));
+    assert!(volume(20) < volume(40));
+    assert_eq!(volume(40), volume(400));
+    assert_eq!(volume(400), SoundEffect::Reveal.volume(&options));
+}
+
+#[test]
+fn flags_and_unflags() {
+    let mut board = board("*.");
+    board.toggle_mark(Coordinates { x: 0, y: 0 });
+    board.toggle_mark(Coordinates { x: 0, y: 0 });
+    assert_eq!(
+        take_effects(&mut board),
+        vec![SoundEffect::Flag, SoundEffect::Unflag]
+    );
+}
+
+#[test]
+fn explosion_plays_once() {
+    let mut board = board("*..");
+    board.uncover(Coordinates { x: 0, y: 0 });
+    assert_eq!(take_effects(&mut board), vec![SoundEffect::Explosion]);
+}
+
+#[test]
+fn bombs_uncovered_after_a_win_are_silent() {
+    let mut board = board(OPENING);
+    board.uncover(Coordinates { x: 7, y: 0 });
+    board.uncover(Coordinates { x: 1, y: 3 });
+    take_effects(&mut board);
+    board.command(None, BoardAction::Reveal(Coordinates { x: 0, y: 3 }));
+    assert_eq!(take_effects(&mut board), vec![]);
+}
+
+#[test]
+fn explosions_on_the_winning_frame_are_silent() {
+    let mut board = board("*..");
+    board
+        .world_mut()
+        .resource_mut::<Events<BoardCompletedEvent>>()
+        .send(BoardCompletedEvent);
+    board.uncover(Coordinates { x: 0, y: 0 });
+    let effects = take_effects(&mut board);
+    assert!(effects.contains(&SoundEffect::Victory));
+    assert!(!effects.contains(&SoundEffect::Explosion));
+}
+
+#[test]
+fn muted_queues_nothing() {
+    let mut board = board("*..");
+    board.world_mut().resource_mut::<SoundOptions>().muted = true;
+    board.toggle_mark(Coordinates { x: 1, y: 0 });
+    board.uncover(Coordinates { x: 2, y: 0 });
+    assert_eq!(take_effects(&mut board), vec![]);
+}
@@ -0,0 +1,88 @@
+use board_plugin::components::Coordinates;
+use board_plugin::{MarkGrid, TileSet};
+
+fn coords(x: u16, y: u16) -> Coordinates {
+    Coordinates { x, y }
+}
+
+#[test]
+fn tile_sets_insert_and_remove() {
+    for (width, height) in [(65, 1), (9, 9)] {
+        let mut set = TileSet::new(width, height);
+        let last = coords(width - 1, height - 1);
+        assert!(set.is_empty());
+        assert!(set.insert(coords(0, 0)));
+        assert!(set.insert(last));
+        assert!(!set.insert(last));
+        assert!(set.contains(&last));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![coords(0, 0), last]);
+        assert!(set.remove(&coords(0, 0)));
+        assert!(!set.remove(&coords(0, 0)));
+        assert!(!set.contains(&coords(0, 0)));
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![last]);
+    }
+}
+
+#[test]
+fn tile_sets_ignore_tiles_out_of_the_board() {
+    let mut set = TileSet::new(9, 9);
+    assert!(!set.insert(coords(9, 0)));
+    assert!(!set.insert(coords(0, 9)));
+    assert!(!set.contains(&coords(9, 0)));
+    assert!(!set.remove(&coords(9, 0)));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn tile_sets_iterate_across_words() {
+    // Tiles 63, 64 and 128 are the last bit of a word and the first bits of the next ones
+    let mut set = TileSet::new(65, 2);
+    let tiles = vec![coords(63, 0), coords(64, 0), coords(63, 1), coords(64, 1)];
+    set.extend(tiles.iter().rev().copied());
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.iter().collect::<Vec<_>>(), tiles);
+    // Every tile of a 9x9 board, spread over 2 words
+    let mut set = TileSet::new(9, 9);
+    let tiles: Vec<_> = (0..9)
+        .flat_map(|y| (0..9).map(move |x| coords(x, y)))
+        .collect();
+    set.extend(tiles.iter().copied());
+    assert_eq!(set.len(), 81);
+    assert_eq!(set.iter().collect::<Vec<_>>(), tiles);
+}
+
+#[test]
+fn mark_grids_count_the_marked_tiles() {
+    let mut grid = MarkGrid::new(65, 1);
+    assert_eq!(grid.insert(coords(64, 0), 1), None);
+    assert_eq!(grid.insert(coords(3, 0), 2), None);
+    assert_eq!((grid.len(), grid.total()), (2, 3));
+    assert_eq!(grid.get(&coords(3, 0)), Some(&2));
+    assert!(!grid.contains_key(&coords(4, 0)));
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        vec![(coords(3, 0), 2), (coords(64, 0), 1)]
+    );
+    assert_eq!(grid.insert(coords(65, 0), 1), None);
+    assert_eq!((grid.len(), grid.total()), (2, 3));
+}
+
+#[test]
+fn mark_grids_total_follows_overwrites() {
+    let mut grid = MarkGrid::new(9, 9);
+    grid.insert(coords(4, 4), 1);
+    assert_eq!(grid.insert(coords(4, 4), 3), Some(1));
+    assert_eq!((grid.len(), grid.total()), (1, 3));
+    assert_eq!(grid.insert(coords(4, 4), 2), Some(3));
+    assert_eq!((grid.len(), grid.total()), (1, 2));
+    // A count of 0 unmarks the tile
+    assert_eq!(grid.insert(coords(4, 4), 0), Some(2));
+    assert_eq!((grid.len(), grid.total()), (0, 0));
+    assert_eq!(grid.insert(coords(8, 8), 1), None);
+    assert_eq!(grid.remove(&coords(8, 8)), Some(1));
+    assert_eq!(grid.remove(&coords(8, 8)), None);
+    assert!(grid.is_empty());
+    assert_eq!(grid.total(), 0);
+}
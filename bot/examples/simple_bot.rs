@@ -0,0 +1,71 @@
+use minesweeper_bot::{BotCommand, BotState, BotStatus, COVERED, FLAGGED};
+use std::io::{stdin, stdout, BufRead, Write};
+
+/// Flags the covered neighbors of the numbers having as many of them, chords the numbers having
+/// as many flags, and reveals the first covered tile otherwise.
+///
+/// Run it in a tournament with
+/// `cargo run -p minesweeper-bot --bin tournament -- -- target/debug/examples/simple_bot`
+fn main() -> std::io::Result<()> {
+    let mut stdout = stdout();
+    for line in stdin().lock().lines() {
+        let state: BotState = serde_json::from_str(&line?)?;
+        // Finished games get no answer, the next one follows
+        if state.status != BotStatus::Playing {
+            continue;
+        }
+        serde_json::to_writer(&mut stdout, &next_command(&state))?;
+        stdout.write_all(b"\n")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+fn neighbors(state: &BotState, x: u16, y: u16) -> Vec<(u16, u16, char)> {
+    let mut neighbors = Vec::new();
+    for dy in -1..=1_i32 {
+        for dx in -1..=1_i32 {
+            let (nx, ny) = (i32::from(x) + dx, i32::from(y) + dy);
+            if (dx, dy) == (0, 0) || nx < 0 || ny < 0 {
+                continue;
+            }
+            if let Some(tile) = state.tile(nx as u16, ny as u16) {
+                neighbors.push((nx as u16, ny as u16, tile));
+            }
+        }
+    }
+    neighbors
+}
+
+fn next_command(state: &BotState) -> BotCommand {
+    let mut first_covered = None;
+    for y in 0..state.height {
+        for x in 0..state.width {
+            let tile = state.tile(x, y).unwrap_or(COVERED);
+            if tile == COVERED {
+                first_covered.get_or_insert((x, y));
+            }
+            let count = match tile.to_digit(10) {
+                Some(count) if count > 0 => count as usize,
+                _ => continue,
+            };
+            let neighbors = neighbors(state, x, y);
+            let flags = neighbors.iter().filter(|(.., t)| *t == FLAGGED).count();
+            let covered: Vec<_> = neighbors.iter().filter(|(.., t)| *t == COVERED).collect();
+            if covered.is_empty() {
+                continue;
+            }
+            if flags == count {
+                return BotCommand::Chord { x, y };
+            }
+            if flags + covered.len() == count {
+                let (x, y, _) = *covered[0];
+                return BotCommand::Flag { x, y };
+            }
+        }
+    }
+    match first_covered {
+        Some((x, y)) => BotCommand::Reveal { x, y },
+        None => BotCommand::State,
+    }
+}
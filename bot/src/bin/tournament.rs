@@ -0,0 +1,62 @@
+use minesweeper_bot::{play_games, report, BotOptions, ProcessBot};
+use std::io::stdout;
+
+/// Plays the same boards with every bot given after `--`, as a command line such as
+/// `python3 my_bot.py`, and reports their win rates
+fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (options, bots) = match args.iter().position(|a| a == "--") {
+        Some(separator) => (&args[..separator], &args[separator + 1..]),
+        None => (&args[..], &[][..]),
+    };
+    if bots.is_empty() {
+        eprintln!("usage: tournament [beginner|intermediate|expert] [--games=<count>] [--seed=<number>] -- <bot command>...");
+        std::process::exit(1);
+    }
+    // Board of the games, as `beginner`, `intermediate` or `expert`
+    let board: BotOptions = match options.iter().find(|a| !a.starts_with("--")) {
+        Some(difficulty) => difficulty.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => BotOptions::default(),
+    };
+    let games = parse_option(options, "--games=").unwrap_or(100);
+    // Every bot plays the boards of the seeds from `--seed=<number>`
+    let seed = parse_option(options, "--seed=").unwrap_or_else(rand::random);
+    println!("{} games per bot from seed {}", games, seed);
+    let mut records = Vec::new();
+    for command in bots {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = match words.next() {
+            Some(program) => program,
+            None => continue,
+        };
+        let args: Vec<String> = words.collect();
+        let mut bot = ProcessBot::spawn(&program, &args).unwrap_or_else(|e| {
+            eprintln!("Failed to start {}: {}", command, e);
+            std::process::exit(1);
+        });
+        let record = play_games(&mut bot, board, seed, games);
+        println!("{}: {}", command, record);
+        records.push((command.clone(), record));
+    }
+    println!();
+    report(&mut stdout(), &records)
+}
+
+/// Value of the `--name=<value>` option
+fn parse_option<T: std::str::FromStr>(options: &[String], name: &str) -> Option<T>
+where
+    T::Err: std::fmt::Display,
+{
+    options
+        .iter()
+        .find_map(|a| a.strip_prefix(name))
+        .map(|value| {
+            value.parse().unwrap_or_else(|e| {
+                eprintln!("invalid {}{:?}: {}", name, value, e);
+                std::process::exit(1);
+            })
+        })
+}
@@ -0,0 +1,204 @@
+use crate::{BotCommand, BotState, BotStatus, COVERED, FLAGGED, MINE, VOID};
+use board_plugin::components::Coordinates;
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::{BoardOptions, Tile};
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// Boards played by the bots
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BotOptions {
+    pub map_size: (u16, u16),
+    pub bomb_count: u16,
+}
+
+impl Default for BotOptions {
+    fn default() -> Self {
+        Self {
+            map_size: (16, 16),
+            bomb_count: 40,
+        }
+    }
+}
+
+impl BotOptions {
+    /// Options of the board generated from `seed`, starting with an opening
+    #[must_use]
+    pub fn board(&self, seed: u64) -> BoardOptions {
+        BoardOptions {
+            map_size: self.map_size,
+            bomb_count: self.bomb_count,
+            seed: Some(seed),
+            safe_start: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl FromStr for BotOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "beginner" => Ok(Self {
+                map_size: (9, 9),
+                bomb_count: 10,
+            }),
+            "intermediate" => Ok(Self::default()),
+            "expert" => Ok(Self {
+                map_size: (30, 16),
+                bomb_count: 99,
+            }),
+            _ => Err(format!(
+                "unknown difficulty {:?}, expected beginner, intermediate or expert",
+                s
+            )),
+        }
+    }
+}
+
+/// Game played by a bot through `BotCommand`s
+pub struct BotGame {
+    board: HeadlessBoard,
+    moves: u32,
+}
+
+impl BotGame {
+    /// Generates the board of `seed`, the same seed always giving the same board
+    #[must_use]
+    pub fn new(options: BotOptions, seed: u64) -> Self {
+        Self {
+            board: HeadlessBoard::new(options.board(seed)),
+            moves: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn status(&self) -> BotStatus {
+        match self.board.status() {
+            GameStatus::Playing => BotStatus::Playing,
+            GameStatus::Won => BotStatus::Won,
+            GameStatus::Lost => BotStatus::Lost,
+        }
+    }
+
+    /// State of the game as seen by the player
+    #[must_use]
+    pub fn state(&self) -> BotState {
+        let board = self.board.board();
+        let tile_map = &board.tile_map;
+        let rows = (0..tile_map.height())
+            .map(|y| {
+                (0..tile_map.width())
+                    .map(|x| {
+                        let coords = Coordinates { x, y };
+                        if board.covered_tiles.contains(&coords) {
+                            if board.marked_tiles.contains_key(&coords) {
+                                FLAGGED
+                            } else {
+                                COVERED
+                            }
+                        } else {
+                            match *tile_map.get_tile(coords) {
+                                Tile::Bomb(_) => MINE,
+                                Tile::BombNeighbor(count) => {
+                                    std::char::from_digit(u32::from(count), 36).unwrap_or('?')
+                                }
+                                Tile::Empty => '0',
+                                Tile::Void => VOID,
+                            }
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        BotState {
+            status: self.status(),
+            width: tile_map.width(),
+            height: tile_map.height(),
+            mines: tile_map.bomb_count(),
+            flags: board.marked_count(),
+            moves: self.moves,
+            board: rows,
+            error: None,
+        }
+    }
+
+    /// Plays `command`, returning the new state
+    pub fn play(&mut self, command: BotCommand) -> BotState {
+        match self.try_play(command) {
+            Ok(()) => self.state(),
+            Err(error) => BotState {
+                error: Some(error),
+                ..self.state()
+            },
+        }
+    }
+
+    fn try_play(&mut self, command: BotCommand) -> Result<(), String> {
+        let (x, y) = match command {
+            BotCommand::State => return Ok(()),
+            BotCommand::Reveal { x, y }
+            | BotCommand::Flag { x, y }
+            | BotCommand::Chord { x, y } => (x, y),
+        };
+        if self.status() != BotStatus::Playing {
+            return Err("the game is over".to_string());
+        }
+        let coords = Coordinates { x, y };
+        let board = self.board.board();
+        let tile_map = &board.tile_map;
+        if x >= tile_map.width() || y >= tile_map.height() || tile_map.get_tile(coords).is_void() {
+            return Err(format!("{} is out of the board", coords));
+        }
+        let covered = board.covered_tiles.contains(&coords);
+        let flagged = board.marked_tiles.contains_key(&coords);
+        match command {
+            BotCommand::Reveal { .. } if !covered => {
+                return Err(format!("{} is already uncovered", coords))
+            }
+            BotCommand::Reveal { .. } if flagged => return Err(format!("{} is flagged", coords)),
+            BotCommand::Flag { .. } if !covered => return Err(format!("{} is uncovered", coords)),
+            BotCommand::Chord { .. } if covered => return Err(format!("{} is covered", coords)),
+            BotCommand::Flag { .. } => self.board.toggle_mark(coords),
+            _ => self.board.uncover(coords),
+        }
+        self.moves += 1;
+        Ok(())
+    }
+}
+
+/// Plays `game` with the commands read from `reader`, a line of JSON each, writing the state to
+/// `writer` first and after each command, until the game is over or `reader` is closed
+pub fn run<R: BufRead, W: Write>(
+    game: &mut BotGame,
+    reader: R,
+    mut writer: W,
+) -> io::Result<BotStatus> {
+    write_state(&mut writer, &game.state())?;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let state = match serde_json::from_str(&line) {
+            Ok(command) => game.play(command),
+            Err(e) => BotState {
+                error: Some(format!("invalid command: {}", e)),
+                ..game.state()
+            },
+        };
+        write_state(&mut writer, &state)?;
+        if state.status != BotStatus::Playing {
+            break;
+        }
+    }
+    Ok(game.status())
+}
+
+/// Writes `state` as a line of JSON
+pub fn write_state<W: Write>(writer: &mut W, state: &BotState) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, state)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
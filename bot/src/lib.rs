@@ -0,0 +1,5 @@
+pub use {game::*, protocol::*, tournament::*};
+
+mod game;
+mod protocol;
+mod tournament;
@@ -0,0 +1,29 @@
+use minesweeper_bot::{run, BotGame, BotOptions, BotStatus};
+use std::io::{stdin, stdout};
+
+fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Board of the game, as `beginner`, `intermediate` or `expert`
+    let options: BotOptions = match args.iter().find(|a| !a.starts_with("--")) {
+        Some(difficulty) => difficulty.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => BotOptions::default(),
+    };
+    // Same board on every run with `--seed=<number>`
+    let seed = match args.iter().find_map(|a| a.strip_prefix("--seed=")) {
+        Some(seed) => seed.parse().unwrap_or_else(|e| {
+            eprintln!("invalid seed {:?}: {}", seed, e);
+            std::process::exit(1);
+        }),
+        None => rand::random(),
+    };
+    // The standard output only carries the game states
+    eprintln!("Playing the board of seed {}", seed);
+    let mut game = BotGame::new(options, seed);
+    if run(&mut game, stdin().lock(), stdout().lock())? == BotStatus::Playing {
+        eprintln!("Game abandoned");
+    }
+    Ok(())
+}
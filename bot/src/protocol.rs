@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Command sent by a bot, as a line of JSON such as `{"command": "reveal", "x": 3, "y": 4}`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum BotCommand {
+    /// Uncovers a covered tile
+    Reveal { x: u16, y: u16 },
+    /// Flags a covered tile, or removes its flag
+    Flag { x: u16, y: u16 },
+    /// Uncovers the covered neighbors of an uncovered number having as many flagged neighbors
+    Chord { x: u16, y: u16 },
+    /// Asks for the state without playing
+    State,
+}
+
+/// Status of a bot game
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// Covered tile in `BotState::board`
+pub const COVERED: char = '#';
+/// Flagged covered tile in `BotState::board`
+pub const FLAGGED: char = 'F';
+/// Mine uncovered by the bot in `BotState::board`, which lost the game
+pub const MINE: char = '*';
+/// Cell missing from a shaped board in `BotState::board`
+pub const VOID: char = ' ';
+
+/// State of a game as seen by the player, sent to the bot as a line of JSON after each command.
+///
+/// Covered tiles are only shown as covered or flagged, the mines are never revealed to the bot
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BotState {
+    pub status: BotStatus,
+    pub width: u16,
+    pub height: u16,
+    /// Mines of the board
+    pub mines: u16,
+    /// Flags placed by the bot
+    pub flags: u32,
+    /// Commands played, `state` commands excluded
+    pub moves: u32,
+    /// A row of tiles per `y`, from 0, with a character per `x`: `COVERED`, `FLAGGED`, the
+    /// number of neighbor mines of an uncovered tile, `MINE` or `VOID`
+    pub board: Vec<String>,
+    /// Why the last command was refused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BotState {
+    /// Tile character at `(x, y)`
+    #[must_use]
+    pub fn tile(&self, x: u16, y: u16) -> Option<char> {
+        self.board
+            .get(usize::from(y))
+            .and_then(|row| row.chars().nth(usize::from(x)))
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{write_state, BotCommand, BotGame, BotOptions, BotState, BotStatus};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// Time given to a bot to answer a state
+pub const BOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Player of the bot games
+pub trait Bot {
+    /// Answers the state of a game being played with the next command
+    fn play(&mut self, state: &BotState) -> io::Result<BotCommand>;
+
+    /// Receives the final state of a game
+    fn game_over(&mut self, _state: &BotState) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Starts over after a game lost on error, the bot being out of step with the games
+    fn restart(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// External program playing through its standard input and output: it reads a state per line
+/// and answers a command per line, the final state of a game being followed by the first state of
+/// the next one
+pub struct ProcessBot {
+    program: String,
+    args: Vec<String>,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<io::Result<String>>,
+}
+
+impl ProcessBot {
+    /// Starts `program` with `args`
+    pub fn spawn(program: &str, args: &[String]) -> io::Result<Self> {
+        let (child, stdin, lines) = Self::start(program, args)?;
+        Ok(Self {
+            program: program.to_string(),
+            args: args.to_vec(),
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Starts the process of the bot, with the receiver of its output lines
+    fn start(
+        program: &str,
+        args: &[String],
+    ) -> io::Result<(Child, ChildStdin, Receiver<io::Result<String>>)> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = child.stdout.take().expect("piped stdout");
+        // Lines are read by a background thread, so that a silent bot can be timed out
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok((child, stdin, lines))
+    }
+
+    fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Bot for ProcessBot {
+    fn play(&mut self, state: &BotState) -> io::Result<BotCommand> {
+        write_state(&mut self.stdin, state)?;
+        let line = match self.lines.recv_timeout(BOT_TIMEOUT) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no command in time",
+                ))
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err(io::ErrorKind::UnexpectedEof.into()),
+        };
+        serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn game_over(&mut self, state: &BotState) -> io::Result<()> {
+        write_state(&mut self.stdin, state)
+    }
+
+    /// Starts the program again, a late answer to the lost game being taken for an answer to the
+    /// next one otherwise
+    fn restart(&mut self) -> io::Result<()> {
+        self.stop();
+        let (child, stdin, lines) = Self::start(&self.program, &self.args)?;
+        self.child = child;
+        self.stdin = stdin;
+        self.lines = lines;
+        Ok(())
+    }
+}
+
+impl Drop for ProcessBot {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Games of a bot
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Record {
+    pub games: u32,
+    pub won: u32,
+    /// Games lost by failing to answer, with an invalid command or too many commands
+    pub errors: u32,
+    /// Commands of the won games
+    pub winning_moves: u32,
+}
+
+impl Record {
+    /// Share of the won games, from 0 to 1
+    #[must_use]
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.;
+        }
+        f64::from(self.won) / f64::from(self.games)
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} won ({:.1}%), {} errors",
+            self.won,
+            self.games,
+            self.win_rate() * 100.,
+            self.errors
+        )?;
+        if self.won > 0 {
+            write!(
+                f,
+                ", {:.1} commands per win",
+                f64::from(self.winning_moves) / f64::from(self.won)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays a game of `bot`, giving up after `max_moves` commands, `state` commands included
+pub fn play_game(bot: &mut impl Bot, game: &mut BotGame, max_moves: u32) -> io::Result<BotState> {
+    let mut state = game.state();
+    for _ in 0..max_moves {
+        if state.status != BotStatus::Playing {
+            bot.game_over(&state)?;
+            return Ok(state);
+        }
+        let command = bot.play(&state)?;
+        state = game.play(command);
+    }
+    Err(io::Error::other(format!(
+        "no result after {} commands",
+        max_moves
+    )))
+}
+
+/// Plays `games` games of `bot`, on the boards of the seeds from `seed`
+pub fn play_games(bot: &mut impl Bot, options: BotOptions, seed: u64, games: u32) -> Record {
+    let (width, height) = options.map_size;
+    // Enough to reveal and flag every tile several times
+    let max_moves = 4 * u32::from(width) * u32::from(height);
+    let mut record = Record::default();
+    for game in 0..games {
+        let mut board = BotGame::new(options, seed.wrapping_add(u64::from(game)));
+        record.games += 1;
+        match play_game(bot, &mut board, max_moves) {
+            Ok(state) if state.status == BotStatus::Won => {
+                record.won += 1;
+                record.winning_moves += state.moves;
+            }
+            Ok(_) => (),
+            Err(e) => {
+                eprintln!("Game {} lost on error: {}", game, e);
+                record.errors += 1;
+                if let Err(e) = bot.restart() {
+                    eprintln!("Failed to restart the bot: {}", e);
+                }
+            }
+        }
+    }
+    record
+}
+
+/// Writes the records of the bots, from the best win rate
+pub fn report<W: Write>(writer: &mut W, records: &[(String, Record)]) -> io::Result<()> {
+    let mut records: Vec<_> = records.iter().collect();
+    records.sort_by(|(_, a), (_, b)| b.win_rate().total_cmp(&a.win_rate()));
+    for (rank, (name, record)) in records.into_iter().enumerate() {
+        writeln!(writer, "{}. {}: {}", rank + 1, name, record)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use minesweeper_bot::{
+    play_games, report, run, Bot, BotCommand, BotGame, BotOptions, BotState, BotStatus, Record,
+    COVERED, MINE,
+};
+use std::io::{self, Cursor};
+
+fn beginner() -> BotOptions {
+    "beginner".parse().unwrap()
+}
+
+/// Reveals the first covered tile
+struct FirstCovered;
+
+impl Bot for FirstCovered {
+    fn play(&mut self, state: &BotState) -> io::Result<BotCommand> {
+        (0..state.height)
+            .flat_map(|y| (0..state.width).map(move |x| (x, y)))
+            .find(|(x, y)| state.tile(*x, *y) == Some(COVERED))
+            .map(|(x, y)| BotCommand::Reveal { x, y })
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+}
+
+/// Never plays
+struct Idle;
+
+impl Bot for Idle {
+    fn play(&mut self, _state: &BotState) -> io::Result<BotCommand> {
+        Ok(BotCommand::State)
+    }
+}
+
+/// Fails its first game, and plays again once restarted
+struct FailsOnce {
+    failed: bool,
+    restarts: u32,
+}
+
+impl Bot for FailsOnce {
+    fn play(&mut self, state: &BotState) -> io::Result<BotCommand> {
+        if !self.failed {
+            self.failed = true;
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        FirstCovered.play(state)
+    }
+
+    fn restart(&mut self) -> io::Result<()> {
+        self.restarts += 1;
+        Ok(())
+    }
+}
+
+fn mines_shown(state: &BotState) -> usize {
+    state
+        .board
+        .iter()
+        .flat_map(|row| row.chars())
+        .filter(|tile| *tile == MINE)
+        .count()
+}
+
+#[test]
+fn covered_mines_are_never_shown() {
+    for seed in 0..5 {
+        let mut game = BotGame::new(beginner(), seed);
+        let mut state = game.state();
+        while state.status == BotStatus::Playing {
+            assert_eq!(mines_shown(&state), 0);
+            state = game.play(FirstCovered.play(&state).unwrap());
+        }
+        // Only the exploded mine is shown
+        assert_eq!(
+            mines_shown(&state),
+            usize::from(state.status == BotStatus::Lost)
+        );
+    }
+}
+
+#[test]
+fn same_seed_same_game() {
+    let mut first = BotGame::new(beginner(), 7);
+    let mut second = BotGame::new(beginner(), 7);
+    assert_eq!(first.state(), second.state());
+    for _ in 0..5 {
+        let command = FirstCovered.play(&first.state()).unwrap();
+        assert_eq!(first.play(command), second.play(command));
+    }
+}
+
+#[test]
+fn commands_are_json_lines() {
+    let mut game = BotGame::new(beginner(), 3);
+    let covered = FirstCovered.play(&game.state()).unwrap();
+    let (x, y) = match covered {
+        BotCommand::Reveal { x, y } => (x, y),
+        command => panic!("unexpected {:?}", command),
+    };
+    let input = format!(
+        "{{\"command\": \"state\"}}\nreveal 1 2\n{{\"command\": \"flag\", \"x\": {}, \"y\": {}}}\n{{\"command\": \"chord\", \"x\": {}, \"y\": {}}}\n",
+        x, y, x, y
+    );
+    let mut output = Vec::new();
+    let status = run(&mut game, Cursor::new(input), &mut output).unwrap();
+    assert_eq!(status, BotStatus::Playing);
+    let states: Vec<BotState> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(states.len(), 5);
+    // The state command doesn't count as a move
+    assert_eq!((states[1].moves, &states[1].error), (0, &None));
+    assert!(states[2].error.is_some());
+    assert_eq!((states[3].moves, states[3].flags), (1, 1));
+    // A covered tile can't be chorded
+    assert!(states[4].error.is_some());
+    assert_eq!(states[4].moves, 1);
+}
+
+#[test]
+fn tournaments_report_win_rates() {
+    let first_covered = play_games(&mut FirstCovered, beginner(), 0, 4);
+    assert_eq!(first_covered.games, 4);
+    assert_eq!(first_covered.errors, 0);
+    let idle = play_games(&mut Idle, beginner(), 0, 2);
+    assert_eq!(
+        idle,
+        Record {
+            games: 2,
+            won: 0,
+            errors: 2,
+            winning_moves: 0,
+        }
+    );
+    // Bots are restarted after a game lost on error, and only then
+    let mut fails_once = FailsOnce {
+        failed: false,
+        restarts: 0,
+    };
+    let record = play_games(&mut fails_once, beginner(), 0, 4);
+    assert_eq!((record.games, record.errors), (4, 1));
+    assert_eq!(fails_once.restarts, 1);
+    let mut output = Vec::new();
+    let records = [
+        ("idle".to_string(), idle),
+        ("first covered".to_string(), first_covered),
+    ];
+    report(&mut output, &records).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.lines().last().unwrap().starts_with("2. "));
+    assert!(output.contains("idle: 0/2 won (0.0%), 2 errors"));
+}
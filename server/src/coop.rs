@@ -0,0 +1,198 @@
+use crate::{accept, spawn_reader, HelloMessage, Player, ServerOptions};
+use board_plugin::coop::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::versus::write_message;
+use board_plugin::PlayerId;
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+/// Time between the end of a board and the start of the next one
+const NEXT_BOARD_DELAY: Duration = Duration::from_secs(5);
+
+/// Event of the shared board thread
+enum Event {
+    /// A connection sent its `Hello`
+    Joined(Player),
+    /// Message of a player, `None` once it left
+    Message(PlayerId, Option<ClientMessage>),
+}
+
+impl HelloMessage for ClientMessage {
+    type Reply = ServerMessage;
+    const VERSION: u32 = PROTOCOL_VERSION;
+
+    fn hello(self) -> Option<(u32, String)> {
+        match self {
+            Self::Hello { version, name } => Some((version, name)),
+            _ => None,
+        }
+    }
+
+    fn error(message: String) -> ServerMessage {
+        ServerMessage::Error { message }
+    }
+}
+
+/// Accepts the players on a single shared board, played in its own thread.
+///
+/// The actions of the players are applied in the order they are received, and sent back to every
+/// player as the next command of the board history. Players joining a board being played receive
+/// its history. A new board starts a few seconds after the previous one is cleared or a mine is
+/// revealed
+pub fn serve_coop(listener: TcpListener, options: ServerOptions) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let readers = sender.clone();
+    std::thread::spawn(move || run_shared_board(&receiver, &readers, options));
+    accept::<ClientMessage, _>(listener, &sender, Event::Joined)
+}
+
+/// Sends `message` to every player but `except`, the closed connections being noticed by their
+/// reader
+fn send_all(
+    streams: &mut BTreeMap<PlayerId, TcpStream>,
+    message: &ServerMessage,
+    except: Option<PlayerId>,
+) {
+    for (player, stream) in streams.iter_mut() {
+        if Some(*player) != except {
+            let _ = write_message(stream, message);
+        }
+    }
+}
+
+/// Plays the shared boards, one after the other
+fn run_shared_board(receiver: &Receiver<Event>, readers: &Sender<Event>, options: ServerOptions) {
+    let mut streams: BTreeMap<PlayerId, TcpStream> = BTreeMap::new();
+    let mut names: BTreeMap<PlayerId, String> = BTreeMap::new();
+    let mut spec = options.board();
+    let mut board = HeadlessBoard::new(spec.options());
+    let mut history = Vec::new();
+    // Start of the next board, once the current one is over
+    let mut next_board: Option<Instant> = None;
+    loop {
+        let received = match next_board {
+            Some(start) => receiver.recv_timeout(start.saturating_duration_since(Instant::now())),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let event = match received {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => {
+                next_board = None;
+                spec = options.board();
+                board = HeadlessBoard::new(spec.options());
+                history.clear();
+                let start = ServerMessage::Start {
+                    board: spec,
+                    history: Vec::new(),
+                };
+                send_all(&mut streams, &start, None);
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        match event {
+            Event::Joined(Player {
+                name,
+                mut stream,
+                reader,
+            }) => {
+                let player = match (0..=u8::MAX)
+                    .map(PlayerId)
+                    .find(|player| !streams.contains_key(player))
+                {
+                    Some(player) => player,
+                    None => {
+                        let error = ServerMessage::Error {
+                            message: "the board is full".to_string(),
+                        };
+                        let _ = write_message(&mut stream, &error);
+                        continue;
+                    }
+                };
+                let welcome = ServerMessage::Welcome {
+                    version: PROTOCOL_VERSION,
+                    player,
+                };
+                let start = ServerMessage::Start {
+                    board: spec,
+                    history: history.clone(),
+                };
+                let _ = write_message(&mut stream, &welcome);
+                let _ = write_message(&mut stream, &start);
+                for (other, other_name) in &names {
+                    let joined = ServerMessage::Joined {
+                        player: *other,
+                        name: other_name.clone(),
+                    };
+                    let _ = write_message(&mut stream, &joined);
+                }
+                println!("{} joined", name);
+                streams.insert(player, stream);
+                names.insert(player, name.clone());
+                send_all(&mut streams, &ServerMessage::Joined { player, name }, None);
+                // The board is over, the player waits for the next one as the others do
+                if next_board.is_some() {
+                    let won = board.status() == GameStatus::Won;
+                    if let Some(stream) = streams.get_mut(&player) {
+                        let _ = write_message(stream, &ServerMessage::Over { won });
+                    }
+                }
+                // A closed connection or an invalid message leaves the board
+                spawn_reader(reader, readers.clone(), move |message| {
+                    Event::Message(player, message)
+                });
+            }
+            Event::Message(player, None) => {
+                if let Some(stream) = streams.remove(&player) {
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+                if let Some(name) = names.remove(&player) {
+                    println!("{} left", name);
+                }
+                send_all(&mut streams, &ServerMessage::Left { player }, None);
+            }
+            Event::Message(_, Some(ClientMessage::Hello { .. })) => (),
+            Event::Message(player, Some(ClientMessage::Cursor(coords))) => {
+                let cursor = ServerMessage::Cursor {
+                    player,
+                    x: coords.x,
+                    y: coords.y,
+                };
+                send_all(&mut streams, &cursor, Some(player));
+            }
+            Event::Message(player, Some(ClientMessage::Action(action))) => {
+                let coords = action.coords();
+                let refused = if next_board.is_some() {
+                    Some("the board is over, the next one starts soon".to_string())
+                } else if coords.x >= spec.width || coords.y >= spec.height {
+                    Some(format!("{} is out of the board", coords))
+                } else {
+                    None
+                };
+                if let Some(message) = refused {
+                    let error = ServerMessage::Error { message };
+                    if let Some(stream) = streams.get_mut(&player) {
+                        let _ = write_message(stream, &error);
+                    }
+                    continue;
+                }
+                // Every command is sent, the ones without any effect on the board included, so
+                // that the boards of the players go through the same history
+                let command = board.command(Some(player), action);
+                history.push(command);
+                send_all(&mut streams, &ServerMessage::Command(command), None);
+                let won = match board.status() {
+                    GameStatus::Playing => continue,
+                    GameStatus::Won => true,
+                    GameStatus::Lost => false,
+                };
+                println!("Board {}", if won { "cleared" } else { "lost" });
+                send_all(&mut streams, &ServerMessage::Over { won }, None);
+                next_board = Some(Instant::now() + NEXT_BOARD_DELAY);
+            }
+        }
+    }
+}
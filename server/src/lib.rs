@@ -0,0 +1,310 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::versus::{
+    read_message, write_message, BoardSpec, ClientMessage, EndReason, ServerMessage,
+    PROTOCOL_VERSION,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, BufReader};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+pub use coop::*;
+
+mod coop;
+
+/// Time given to a new connection to send its `Hello`
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Boards of the matches, or of the shared boards
+#[derive(Debug, Copy, Clone)]
+pub struct ServerOptions {
+    pub map_size: (u16, u16),
+    pub bomb_count: u16,
+    /// Seed of every board, a random one per board when `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            map_size: (16, 16),
+            bomb_count: 40,
+            seed: None,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Board of the next match
+    #[must_use]
+    pub fn board(&self) -> BoardSpec {
+        BoardSpec {
+            seed: self.seed.unwrap_or_else(rand::random),
+            width: self.map_size.0,
+            height: self.map_size.1,
+            bomb_count: self.bomb_count,
+        }
+    }
+}
+
+/// Connected player, having sent its `Hello`
+#[derive(Debug)]
+struct Player {
+    name: String,
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+/// Client messages of a protocol whose connections start with a `Hello`
+trait HelloMessage: DeserializeOwned {
+    /// Server messages of the protocol
+    type Reply: Serialize;
+    /// Protocol version spoken by the server
+    const VERSION: u32;
+
+    /// Version and name of a `Hello`, `None` for the other messages
+    fn hello(self) -> Option<(u32, String)>;
+
+    /// Message refusing a `Hello`
+    fn error(message: String) -> Self::Reply;
+}
+
+impl HelloMessage for ClientMessage {
+    type Reply = ServerMessage;
+    const VERSION: u32 = PROTOCOL_VERSION;
+
+    fn hello(self) -> Option<(u32, String)> {
+        match self {
+            Self::Hello { version, name } => Some((version, name)),
+            _ => None,
+        }
+    }
+
+    fn error(message: String) -> ServerMessage {
+        ServerMessage::Error { message }
+    }
+}
+
+/// Accepts the players, and runs a match per pair of players in its own thread
+pub fn serve(listener: TcpListener, options: ServerOptions) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || pair_players(&receiver, options));
+    accept::<ClientMessage, _>(listener, &sender, |player| player)
+}
+
+/// Accepts the connections of `listener`, sending `joined(player)` once their `Hello` is received.
+///
+/// Each connection waits for its `Hello` in its own thread, so that a silent connection doesn't
+/// hold back the others
+fn accept<M: HelloMessage, E: Send + 'static>(
+    listener: TcpListener,
+    sender: &Sender<E>,
+    joined: fn(Player) -> E,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        let sender = sender.clone();
+        std::thread::spawn(move || match handshake::<M>(stream) {
+            Ok(player) => {
+                let _ = sender.send(joined(player));
+            }
+            Err(e) => eprintln!("Refused a connection: {}", e),
+        });
+    }
+    Ok(())
+}
+
+/// Welcomes the players having sent their `Hello`, and starts a match once two of them joined
+fn pair_players(receiver: &Receiver<Player>, options: ServerOptions) {
+    let mut waiting: Option<Player> = None;
+    for mut player in receiver.iter() {
+        // The waiting player may have left in the meantime
+        if let Some(first) = waiting.take() {
+            if is_connected(&first.stream) {
+                waiting = Some(first);
+            } else {
+                println!("{} left", first.name);
+            }
+        }
+        let number = if waiting.is_some() { 1 } else { 0 };
+        let welcome = ServerMessage::Welcome {
+            version: PROTOCOL_VERSION,
+            player: number,
+        };
+        if let Err(e) = write_message(&mut player.stream, &welcome) {
+            eprintln!("Failed to welcome {}: {}", player.name, e);
+            continue;
+        }
+        println!("{} joined", player.name);
+        match waiting.take() {
+            None => waiting = Some(player),
+            Some(first) => {
+                let board = options.board();
+                std::thread::spawn(move || run_match(first, player, board));
+            }
+        }
+    }
+}
+
+/// Waits for the `Hello` of a new connection
+fn handshake<M: HelloMessage>(mut stream: TcpStream) -> io::Result<Player> {
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let message: Option<M> = read_message(&mut reader)?;
+    let error = match message.map(M::hello) {
+        Some(Some((version, name))) if version == M::VERSION => {
+            stream.set_read_timeout(None)?;
+            return Ok(Player {
+                name,
+                stream,
+                reader,
+            });
+        }
+        Some(Some((version, _))) => format!(
+            "unsupported protocol version {}, the server speaks version {}",
+            version,
+            M::VERSION
+        ),
+        Some(None) => "the first message must be a hello".to_string(),
+        None => return Err(io::ErrorKind::UnexpectedEof.into()),
+    };
+    write_message(&mut stream, &M::error(error.clone()))?;
+    Err(io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Reads the messages of a player in its own thread, sending them as `event(message)`. A closed
+/// connection or an invalid message is sent as `None`, and stops the reading
+fn spawn_reader<M, E>(
+    mut reader: BufReader<TcpStream>,
+    sender: Sender<E>,
+    event: impl Fn(Option<M>) -> E + Send + 'static,
+) where
+    M: DeserializeOwned,
+    E: Send + 'static,
+{
+    std::thread::spawn(move || loop {
+        let message = read_message(&mut reader).ok().flatten();
+        let closed = message.is_none();
+        if sender.send(event(message)).is_err() || closed {
+            break;
+        }
+    });
+}
+
+/// Is `stream` still open, without waiting for any message. Messages sent before the match
+/// starts are left to be read
+fn is_connected(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return false;
+    }
+    let connected = match stream.peek(&mut [0]) {
+        Ok(read) => read > 0,
+        Err(e) => e.kind() == io::ErrorKind::WouldBlock,
+    };
+    stream.set_nonblocking(false).is_ok() && connected
+}
+
+/// Sends `message` to every stream, the closed ones being noticed by their reader
+fn broadcast(streams: &mut [TcpStream], message: &ServerMessage) {
+    for stream in streams.iter_mut() {
+        let _ = write_message(stream, message);
+    }
+}
+
+/// Plays a match between two players on `board`, replaying their actions on a board of their
+/// own to decide the result
+fn run_match(first: Player, second: Player, board: BoardSpec) {
+    println!("Match of {} against {}", first.name, second.name);
+    let names = [first.name.clone(), second.name.clone()];
+    let (sender, receiver) = mpsc::channel();
+    let mut streams = Vec::new();
+    for (number, player) in vec![first, second].into_iter().enumerate() {
+        // A closed connection or an invalid message leaves the match
+        spawn_reader(player.reader, sender.clone(), move |message| {
+            (number, message)
+        });
+        streams.push(player.stream);
+    }
+    // The match is aborted once both readers are gone
+    drop(sender);
+    for (number, stream) in streams.iter_mut().enumerate() {
+        let start = ServerMessage::Start {
+            board,
+            opponent: names[1 - number].clone(),
+        };
+        let _ = write_message(stream, &start);
+    }
+    let mut boards = [
+        HeadlessBoard::new(board.options()),
+        HeadlessBoard::new(board.options()),
+    ];
+    let (winner, reason) = loop {
+        let (number, message) = match receiver.recv() {
+            Ok(received) => received,
+            Err(_) => break (None, EndReason::Disconnected),
+        };
+        let opponent = 1 - number;
+        let (reveal, coords) = match message {
+            Some(ClientMessage::Hello { .. }) => continue,
+            Some(ClientMessage::Reveal { x, y }) => (true, Coordinates { x, y }),
+            Some(ClientMessage::Flag { x, y }) => (false, Coordinates { x, y }),
+            None => break (Some(opponent as u8), EndReason::Disconnected),
+        };
+        if coords.x >= board.width || coords.y >= board.height {
+            let error = ServerMessage::Error {
+                message: format!("{} is out of the board", coords),
+            };
+            let _ = write_message(&mut streams[number], &error);
+            continue;
+        }
+        let player_board = &mut boards[number];
+        let before = player_board.board().covered_tiles.clone();
+        if reveal {
+            player_board.uncover(coords);
+        } else {
+            player_board.toggle_mark(coords);
+        }
+        let after = player_board.board();
+        let progress = ServerMessage::Progress {
+            player: number as u8,
+            uncovered: before
+                .iter()
+                .filter(|coords| !after.covered_tiles.contains(coords))
+                .collect(),
+            flags: after.marked_count(),
+            remaining: after
+                .covered_tiles
+                .len()
+                .saturating_sub(after.tile_map.bomb_tiles()) as u32,
+        };
+        broadcast(&mut streams, &progress);
+        match player_board.status() {
+            GameStatus::Playing => (),
+            GameStatus::Won => break (Some(number as u8), EndReason::Cleared),
+            GameStatus::Lost => break (Some(opponent as u8), EndReason::Exploded),
+        }
+    };
+    match winner {
+        Some(winner) => println!(
+            "{} won against {} ({:?})",
+            names[usize::from(winner)],
+            names[1 - usize::from(winner)],
+            reason
+        ),
+        None => println!("Match of {} against {} aborted", names[0], names[1]),
+    }
+    broadcast(&mut streams, &ServerMessage::Result { winner, reason });
+    for stream in &streams {
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+}
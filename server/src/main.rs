@@ -0,0 +1,51 @@
+use board_plugin::{coop, versus};
+use minesweeper_server::{serve, serve_coop, ServerOptions};
+use std::net::TcpListener;
+
+fn main() -> std::io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Board of the matches, as `beginner`, `intermediate` or `expert`
+    let (map_size, bomb_count) = match args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(String::as_str)
+    {
+        Some("beginner") => ((9, 9), 10),
+        Some("expert") => ((30, 16), 99),
+        _ => ((16, 16), 40),
+    };
+    // Same board on every match with `--seed=<number>`
+    let seed = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--seed="))
+        .map(|seed| {
+            seed.parse().unwrap_or_else(|e| {
+                eprintln!("invalid seed {:?}: {}", seed, e);
+                std::process::exit(1);
+            })
+        });
+    // A board shared by every player with `--coop`, instead of versus matches
+    let shared = args.iter().any(|a| a == "--coop");
+    let port = if shared {
+        coop::DEFAULT_PORT
+    } else {
+        versus::DEFAULT_PORT
+    };
+    let address = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--address="))
+        .map_or_else(|| format!("0.0.0.0:{}", port), str::to_string);
+    let listener = TcpListener::bind(&address)?;
+    let options = ServerOptions {
+        map_size,
+        bomb_count,
+        seed,
+    };
+    if shared {
+        println!("Co-op server listening on {}", listener.local_addr()?);
+        serve_coop(listener, options)
+    } else {
+        println!("Versus server listening on {}", listener.local_addr()?);
+        serve(listener, options)
+    }
+}
@@ -0,0 +1,50 @@
+//! Fixtures shared by the server tests, each test using some of them
+#![allow(dead_code)]
+
+use board_plugin::versus::Connection;
+use minesweeper_server::ServerOptions;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+/// Options of the test servers, always playing the same board
+pub fn options() -> ServerOptions {
+    ServerOptions {
+        map_size: (9, 9),
+        bomb_count: 10,
+        seed: Some(42),
+    }
+}
+
+/// Starts `serve` on a free localhost port
+pub fn start_server(serve: fn(TcpListener, ServerOptions) -> io::Result<()>) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || serve(listener, options()));
+    address
+}
+
+/// Connects a client sending `hello`
+pub fn connect<Out, In>(address: SocketAddr, hello: &Out) -> Connection<Out, In>
+where
+    Out: Serialize,
+    In: DeserializeOwned + Send + 'static,
+{
+    let mut client = Connection::connect(address).unwrap();
+    client.send(hello).unwrap();
+    client
+}
+
+/// Next message of `client`, failing after a few seconds
+pub fn receive<Out, In>(client: &Connection<Out, In>) -> In
+where
+    Out: Serialize,
+    In: DeserializeOwned + Send + 'static,
+{
+    client
+        .recv_timeout(Duration::from_secs(10))
+        .unwrap()
+        .expect("no message from the server")
+}
@@ -0,0 +1,137 @@
+use board_plugin::components::Coordinates;
+use board_plugin::coop::{ClientMessage, ServerMessage, PROTOCOL_VERSION};
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::versus::Connection;
+use board_plugin::{BoardAction, BoardCommand, PlayerId};
+use common::{connect, options, receive, start_server};
+use minesweeper_server::serve_coop;
+use std::net::SocketAddr;
+
+mod common;
+
+type Client = Connection<ClientMessage, ServerMessage>;
+
+fn hello(name: &str, version: u32) -> ClientMessage {
+    ClientMessage::Hello {
+        version,
+        name: name.to_string(),
+    }
+}
+
+/// Connects a client, returning its player and the history of the board
+fn join(address: SocketAddr, name: &str) -> (Client, PlayerId, Vec<BoardCommand>) {
+    let client: Client = connect(address, &hello(name, PROTOCOL_VERSION));
+    let player = match receive(&client) {
+        ServerMessage::Welcome { player, .. } => player,
+        message => panic!("unexpected {:?}", message),
+    };
+    let history = match receive(&client) {
+        ServerMessage::Start { history, .. } => history,
+        message => panic!("unexpected {:?}", message),
+    };
+    // The connected players, this one last
+    loop {
+        match receive(&client) {
+            ServerMessage::Joined { player: joined, .. } if joined == player => break,
+            ServerMessage::Joined { .. } => continue,
+            message => panic!("unexpected {:?}", message),
+        }
+    }
+    (client, player, history)
+}
+
+/// Next command received by `client`
+fn command(client: &Client) -> BoardCommand {
+    loop {
+        match receive(client) {
+            ServerMessage::Command(command) => break command,
+            ServerMessage::Joined { .. }
+            | ServerMessage::Cursor { .. }
+            | ServerMessage::Over { .. } => continue,
+            message => panic!("unexpected {:?}", message),
+        }
+    }
+}
+
+#[test]
+fn players_share_the_same_history() {
+    let address = start_server(serve_coop);
+    let (mut alice, alice_player, history) = join(address, "alice");
+    assert!(history.is_empty());
+    let (mut bob, bob_player, _) = join(address, "bob");
+    assert_eq!((alice_player, bob_player), (PlayerId(0), PlayerId(1)));
+
+    // Cursors are only sent to the other players
+    let coords = Coordinates { x: 4, y: 4 };
+    alice.send(&ClientMessage::Cursor(coords)).unwrap();
+    let cursor = loop {
+        match receive(&bob) {
+            ServerMessage::Cursor { player, x, y } => break (player, Coordinates { x, y }),
+            ServerMessage::Joined { .. } => continue,
+            message => panic!("unexpected {:?}", message),
+        }
+    };
+    assert_eq!(cursor, (alice_player, coords));
+
+    // Both players act on the same tile at once, and get the same order
+    alice
+        .send(&ClientMessage::Action(BoardAction::Reveal(coords)))
+        .unwrap();
+    bob.send(&ClientMessage::Action(BoardAction::Flag(coords)))
+        .unwrap();
+    let alice_commands = [command(&alice), command(&alice)];
+    let bob_commands = [command(&bob), command(&bob)];
+    assert_eq!(alice_commands, bob_commands);
+    assert_eq!(
+        alice_commands
+            .iter()
+            .map(|command| command.sequence)
+            .collect::<Vec<_>>(),
+        [0, 1]
+    );
+
+    // Late players get the history of the board
+    let (_carol, carol_player, history) = join(address, "carol");
+    assert_eq!(carol_player, PlayerId(2));
+    assert_eq!(history, alice_commands);
+}
+
+#[test]
+fn late_players_wait_for_the_next_board() {
+    let address = start_server(serve_coop);
+    let (mut alice, _, _) = join(address, "alice");
+    let local = HeadlessBoard::new(options().board().options());
+    let tile_map = &local.board().tile_map;
+    let mine = (0..9)
+        .flat_map(|y| (0..9).map(move |x| Coordinates { x, y }))
+        .find(|coords| tile_map.is_bomb_at(*coords))
+        .unwrap();
+    alice
+        .send(&ClientMessage::Action(BoardAction::Reveal(mine)))
+        .unwrap();
+    let over = loop {
+        match receive(&alice) {
+            ServerMessage::Command(_) => continue,
+            message => break message,
+        }
+    };
+    assert_eq!(over, ServerMessage::Over { won: false });
+
+    // Actions are refused until the next board starts
+    alice
+        .send(&ClientMessage::Action(BoardAction::Flag(mine)))
+        .unwrap();
+    assert!(matches!(receive(&alice), ServerMessage::Error { .. }));
+
+    // Players joining in between are told the board is over
+    let (bob, _, history) = join(address, "bob");
+    assert_eq!(history.len(), 1);
+    assert_eq!(receive(&bob), ServerMessage::Over { won: false });
+}
+
+#[test]
+fn other_protocol_versions_are_refused() {
+    let address = start_server(serve_coop);
+    let client: Client = connect(address, &hello("future", PROTOCOL_VERSION + 1));
+    assert!(matches!(receive(&client), ServerMessage::Error { .. }));
+}
@@ -0,0 +1,164 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::HeadlessBoard;
+use board_plugin::versus::{
+    read_message, write_message, BoardSpec, ClientMessage, Connection, EndReason, ServerMessage,
+    PROTOCOL_VERSION,
+};
+use common::{connect, receive, start_server};
+use minesweeper_server::serve;
+use std::io::BufReader;
+use std::net::{Shutdown, SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+mod common;
+
+type Client = Connection<ClientMessage, ServerMessage>;
+
+fn hello(name: &str, version: u32) -> ClientMessage {
+    ClientMessage::Hello {
+        version,
+        name: name.to_string(),
+    }
+}
+
+/// Connects a client, returning its player number
+fn join(address: SocketAddr, name: &str) -> (Client, u8) {
+    let client: Client = connect(address, &hello(name, PROTOCOL_VERSION));
+    match receive(&client) {
+        ServerMessage::Welcome { version, player } => {
+            assert_eq!(version, PROTOCOL_VERSION);
+            (client, player)
+        }
+        message => panic!("unexpected {:?}", message),
+    }
+}
+
+fn start(client: &Client) -> (BoardSpec, String) {
+    match receive(client) {
+        ServerMessage::Start { board, opponent } => (board, opponent),
+        message => panic!("unexpected {:?}", message),
+    }
+}
+
+#[test]
+fn first_to_clear_the_board_wins() {
+    let address = start_server(serve);
+    let (mut alice, alice_number) = join(address, "alice");
+    let (bob, bob_number) = join(address, "bob");
+    assert_eq!((alice_number, bob_number), (0, 1));
+    let (board, opponent) = start(&alice);
+    assert_eq!(opponent, "bob");
+    assert_eq!(start(&bob), (board, "alice".to_string()));
+
+    // Headless clients generate the same board as the server
+    let mut local = HeadlessBoard::new(board.options());
+    let tile_map = local.board().tile_map.clone();
+    for y in 0..board.height {
+        for x in 0..board.width {
+            let coords = Coordinates { x, y };
+            if tile_map.is_bomb_at(coords) || !local.board().covered_tiles.contains(&coords) {
+                continue;
+            }
+            local.uncover(coords);
+            alice.send(&ClientMessage::Reveal { x, y }).unwrap();
+        }
+    }
+
+    let mut bob_saw_progress = false;
+    let result = loop {
+        match receive(&bob) {
+            ServerMessage::Progress {
+                player, uncovered, ..
+            } => {
+                assert_eq!(player, alice_number);
+                bob_saw_progress |= !uncovered.is_empty();
+            }
+            message => break message,
+        }
+    };
+    assert!(bob_saw_progress);
+    let expected = ServerMessage::Result {
+        winner: Some(alice_number),
+        reason: EndReason::Cleared,
+    };
+    assert_eq!(result, expected);
+    let result = loop {
+        match receive(&alice) {
+            ServerMessage::Progress { .. } => continue,
+            message => break message,
+        }
+    };
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn revealing_a_mine_loses() {
+    let address = start_server(serve);
+    let (_alice, _) = join(address, "alice");
+    let (mut bob, _) = join(address, "bob");
+    let (board, _) = start(&bob);
+    let local = HeadlessBoard::new(board.options());
+    let tile_map = &local.board().tile_map;
+    let mine = (0..board.height)
+        .flat_map(|y| (0..board.width).map(move |x| Coordinates { x, y }))
+        .find(|coords| tile_map.is_bomb_at(*coords))
+        .unwrap();
+    bob.send(&ClientMessage::Reveal {
+        x: mine.x,
+        y: mine.y,
+    })
+    .unwrap();
+    let result = loop {
+        match receive(&bob) {
+            ServerMessage::Progress { .. } => continue,
+            message => break message,
+        }
+    };
+    assert_eq!(
+        result,
+        ServerMessage::Result {
+            winner: Some(0),
+            reason: EndReason::Exploded,
+        }
+    );
+}
+
+#[test]
+fn other_protocol_versions_are_refused() {
+    let address = start_server(serve);
+    let client: Client = connect(address, &hello("future", PROTOCOL_VERSION + 1));
+    assert!(matches!(receive(&client), ServerMessage::Error { .. }));
+}
+
+#[test]
+fn silent_connections_do_not_hold_back_the_others() {
+    let address = start_server(serve);
+    let _silent = TcpStream::connect(address).unwrap();
+    let started = Instant::now();
+    let (alice, _) = join(address, "alice");
+    let (_bob, _) = join(address, "bob");
+    assert_eq!(start(&alice).1, "bob");
+    // The silent connection is only refused after the 5 seconds handshake timeout
+    assert!(started.elapsed() < Duration::from_secs(4));
+}
+
+#[test]
+fn players_leaving_before_their_match_are_not_paired() {
+    let address = start_server(serve);
+    let mut gone = TcpStream::connect(address).unwrap();
+    write_message(&mut gone, &hello("gone", PROTOCOL_VERSION)).unwrap();
+    let mut reader = BufReader::new(gone.try_clone().unwrap());
+    let welcome: Option<ServerMessage> = read_message(&mut reader).unwrap();
+    assert!(matches!(
+        welcome,
+        Some(ServerMessage::Welcome { player: 0, .. })
+    ));
+    gone.shutdown(Shutdown::Both).unwrap();
+    drop((gone, reader));
+    std::thread::sleep(Duration::from_millis(100));
+    let (alice, alice_number) = join(address, "alice");
+    let (bob, bob_number) = join(address, "bob");
+    assert_eq!((alice_number, bob_number), (0, 1));
+    assert_eq!(start(&alice).1, "bob");
+    assert_eq!(start(&bob).1, "alice");
+}
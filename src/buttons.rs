@@ -1,17 +1,100 @@
 use bevy::prelude::*;
 
+use crate::settings::Difficulty;
+
 /// Button action type
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub enum ButtonAction {
     Clear,
     Generate,
-    Cheat,
+    Hint,
+    Probabilities,
+    Settings,
+    NewGame,
+    Continue,
+    Daily,
+    /// Starts the daily challenge, once a day
+    PlayDaily,
+    /// Copies the summary of the daily challenge to the clipboard
+    CopySummary,
+    Stats,
+    Quit,
+    Beginner,
+    Intermediate,
+    Expert,
+    Resume,
+    MainMenu,
+    /// Leaves the current screen, pausing the game when playing
+    Back,
+}
+
+impl ButtonAction {
+    /// Buttons of the in-game bar
+    pub const BAR: [Self; 5] = [
+        Self::Clear,
+        Self::Hint,
+        Self::Probabilities,
+        Self::Generate,
+        Self::Settings,
+    ];
+
+    /// Text of the button
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clear => "CLEAR",
+            Self::Generate => "GENERATE",
+            Self::Hint => "HINT",
+            Self::Probabilities => "ODDS",
+            Self::Settings => "OPTIONS",
+            Self::NewGame => "NEW GAME",
+            Self::Continue => "CONTINUE",
+            Self::Daily => "DAILY",
+            Self::PlayDaily => "PLAY",
+            Self::CopySummary => "COPY SUMMARY",
+            Self::Stats => "STATS",
+            Self::Quit => "QUIT",
+            Self::Beginner => "BEGINNER",
+            Self::Intermediate => "INTERMEDIATE",
+            Self::Expert => "EXPERT",
+            Self::Resume => "RESUME",
+            Self::MainMenu => "MAIN MENU",
+            Self::Back => "BACK",
+        }
+    }
+
+    /// Difficulty started by the button
+    #[must_use]
+    pub fn difficulty(self) -> Option<Difficulty> {
+        match self {
+            Self::Beginner => Some(Difficulty::Beginner),
+            Self::Intermediate => Some(Difficulty::Intermediate),
+            Self::Expert => Some(Difficulty::Expert),
+            _ => None,
+        }
+    }
 }
 
+/// Button which can't be clicked, drawn with `ButtonColors::disabled`
+#[derive(Component)]
+pub struct Disabled;
+
 #[derive(Debug)]
 pub struct ButtonColors {
     pub normal: Color,
     pub hovered: Color,
     pub pressed: Color,
+    pub disabled: Color,
+}
+
+impl Default for ButtonColors {
+    fn default() -> Self {
+        Self {
+            normal: Color::GRAY,
+            hovered: Color::DARK_GRAY,
+            pressed: Color::BLACK,
+            disabled: Color::rgba(0.5, 0.5, 0.5, 0.3),
+        }
+    }
 }
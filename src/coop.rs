@@ -0,0 +1,112 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::components::PlayerFlag;
+use board_plugin::coop::{CoopClient, CoopEvent, ServerMessage};
+use board_plugin::{Board, BoardOptions, PlayerId};
+use std::collections::HashSet;
+
+use crate::hot_seat::player_color;
+use crate::{log_state_error, AppState};
+
+/// Cursor of another player of the shared board
+#[derive(Component)]
+pub struct PlayerCursor(pub PlayerId);
+
+/// Starts the boards sent by the co-op server, and draws the cursors and flags of the players in
+/// their colors
+pub struct CoopPlugin;
+
+impl Plugin for CoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(handle_coop_events)
+            .add_system(update_cursors)
+            .add_system(color_flags);
+        log::info!("Loaded Co-op Plugin");
+    }
+}
+
+/// Starts the shared boards
+fn handle_coop_events(
+    mut coop_evr: EventReader<CoopEvent>,
+    mut state: ResMut<State<AppState>>,
+    client: Option<Res<CoopClient>>,
+    board_options: Option<ResMut<BoardOptions>>,
+) {
+    let (client, mut board_options) = match (client, board_options) {
+        (Some(client), Some(options)) => (client, options),
+        _ => return,
+    };
+    for CoopEvent(message) in coop_evr.iter() {
+        match message {
+            ServerMessage::Start { board, history } => {
+                log::info!("Shared board started, {} commands played", history.len());
+                board.apply(&mut board_options);
+                // Ends the previous board under the menus, if any
+                log_state_error(state.replace(AppState::InGame));
+            }
+            ServerMessage::Joined { player, name } => log::info!("{} joined as {}", name, player),
+            ServerMessage::Left { player } => log::info!("{} left", client.name(*player)),
+            ServerMessage::Over { won } => {
+                log::info!("Shared board {}", if *won { "cleared" } else { "lost" });
+            }
+            ServerMessage::Welcome { .. }
+            | ServerMessage::Error { .. }
+            | ServerMessage::Command(_)
+            | ServerMessage::Cursor { .. } => (),
+        }
+    }
+}
+
+/// Moves the cursors of the other players over the tiles they hover
+fn update_cursors(
+    mut commands: Commands,
+    client: Option<Res<CoopClient>>,
+    board: Option<Res<Board>>,
+    mut cursors: Query<(Entity, &PlayerCursor, &mut Transform)>,
+) {
+    let (client, board) = match (client, board) {
+        (Some(client), Some(board)) => (client, board),
+        _ => return,
+    };
+    let topology = board.tile_map.topology();
+    let mut shown = HashSet::new();
+    for (entity, PlayerCursor(player), mut transform) in cursors.iter_mut() {
+        match client.cursors.get(player) {
+            Some(coords) => {
+                transform.translation = topology.tile_center(*coords, board.tile_size).extend(6.);
+                shown.insert(*player);
+            }
+            None => commands.entity(entity).despawn_recursive(),
+        }
+    }
+    // The cursors are children of the board, despawned with it
+    for (player, coords) in client.cursors.iter() {
+        if shown.contains(player) {
+            continue;
+        }
+        let color = player_color(*player);
+        commands.entity(board.entity).with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(board.tile_size)),
+                        color: Color::rgba(color.r(), color.g(), color.b(), 0.4),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(
+                        topology.tile_center(*coords, board.tile_size).extend(6.),
+                    ),
+                    ..Default::default()
+                })
+                .insert(Name::new(format!("Cursor of {}", client.name(*player))))
+                .insert(PlayerCursor(*player));
+        });
+    }
+}
+
+/// Draws the flags in the color of the player who placed them
+fn color_flags(mut flags: Query<(&PlayerFlag, &mut Sprite), Added<PlayerFlag>>) {
+    for (PlayerFlag(player), mut sprite) in flags.iter_mut() {
+        sprite.color = player_color(*player);
+    }
+}
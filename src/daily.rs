@@ -0,0 +1,536 @@
+use std::fmt::{self, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::events::{
+    BoardCompletedEvent, BombExplosionEvent, TileMarkEvent, TileTriggerEvent,
+};
+use board_plugin::solver::{bbbv, is_no_guess};
+use board_plugin::{AssistLevel, Board, BoardOptions, BoardShape, Neighborhood, Topology};
+use serde::{Deserialize, Serialize};
+
+use crate::buttons::{ButtonAction, Disabled};
+use crate::settings::{config_file, Difficulty};
+use crate::{log_state_error, AppState, Hints, StartTime};
+
+/// Daily results file, next to the settings file
+const DAILY_FILE: &str = "daily.json";
+/// Number of past results shown on the daily screen
+const HISTORY_LINES: usize = 5;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Challenge of a UTC day: the same board for every player on that day
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DailyChallenge {
+    /// Days since 1970-01-01
+    pub day: i64,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+}
+
+impl DailyChallenge {
+    /// Challenge of the `day`-th day since 1970-01-01
+    #[must_use]
+    pub fn of_day(day: i64) -> Self {
+        // 1970-01-01 was a Thursday
+        let difficulty = match (day + 3).rem_euclid(7) {
+            0 | 1 => Difficulty::Beginner,
+            2..=4 => Difficulty::Intermediate,
+            _ => Difficulty::Expert,
+        };
+        Self {
+            day,
+            difficulty,
+            seed: splitmix64(day as u64),
+        }
+    }
+
+    /// Challenge of the current UTC date, `None` when the system clock can't be read
+    #[must_use]
+    pub fn today() -> Option<Self> {
+        // The standard clock isn't available on the web
+        if cfg!(target_arch = "wasm32") {
+            return None;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        Some(Self::of_day((now.as_secs() / SECONDS_PER_DAY) as i64))
+    }
+
+    /// UTC date of the challenge, as `YYYY-MM-DD`
+    #[must_use]
+    pub fn date(&self) -> String {
+        let (year, month, day) = civil_from_days(self.day);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// Sets the board of the challenge in `options`: a single player no-guess board of the
+    /// preset, without any variant or assist. The board needs guesses in the unlikely case no
+    /// no-guess board is found
+    pub fn apply(&self, options: &mut BoardOptions) {
+        options.map_size = self.difficulty.map_size();
+        options.bomb_count = self.difficulty.bomb_count();
+        options.seed = Some(self.seed);
+        options.no_guess = true;
+        options.safe_start = true;
+        options.topology = Topology::Square;
+        options.shape = BoardShape::Rectangle;
+        options.wrap = false;
+        options.neighborhood = Neighborhood::Adjacent;
+        options.max_mines_per_cell = 1;
+        options.layout = None;
+        options.puzzle = None;
+        options.assist = AssistLevel::None;
+        options.rating_band = None;
+        options.hot_seat = None;
+    }
+}
+
+/// Spreads the bits of consecutive days over the whole seed
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Year, month and day of the `days`-th day since 1970-01-01, in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Days since 0000-03-01, in 400 years eras starting after a leap day
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months starting from March
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+/// End of a daily attempt
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    /// Started but never finished
+    Abandoned,
+    Lost,
+    Won,
+}
+
+/// Scored attempt of a daily challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    /// UTC date of the challenge, as `YYYY-MM-DD`
+    pub date: String,
+    pub difficulty: Difficulty,
+    pub outcome: Outcome,
+    pub seconds: u32,
+    /// Bechtel's Board Benchmark Value of the board
+    pub bbbv: u32,
+    /// Tiles clicked and marked
+    pub clicks: u32,
+    #[serde(default)]
+    pub hints: u32,
+    /// Could the board be cleared without guessing, `false` if no no-guess board was found
+    #[serde(default = "default_no_guess")]
+    pub no_guess: bool,
+}
+
+fn default_no_guess() -> bool {
+    true
+}
+
+impl DailyResult {
+    /// 3BV cleared per second, for a won attempt
+    #[must_use]
+    pub fn bbbv_per_second(&self) -> Option<f32> {
+        (self.outcome == Outcome::Won).then_some(self.bbbv as f32 / self.seconds.max(1) as f32)
+    }
+
+    /// Text shared by the player, without the board
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "Mine Sweeper daily {} ({})\n{}",
+            self.date, self.difficulty, self
+        )
+    }
+}
+
+impl fmt::Display for DailyResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let time = format!("{}:{:02}", self.seconds / 60, self.seconds % 60);
+        match (self.outcome, self.bbbv_per_second()) {
+            (Outcome::Won, Some(speed)) => write!(
+                f,
+                "Won in {} - {:.2} 3BV/s - {} clicks",
+                time, speed, self.clicks
+            )?,
+            (Outcome::Lost, _) => write!(f, "Lost after {} - {} clicks", time, self.clicks)?,
+            _ => write!(f, "Abandoned")?,
+        }
+        if !self.no_guess {
+            write!(f, " - guessing board")?;
+        }
+        match self.hints {
+            0 => Ok(()),
+            1 => write!(f, " - 1 hint"),
+            hints => write!(f, " - {} hints", hints),
+        }
+    }
+}
+
+/// Daily challenge results, one per played day. Must be used as a resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyHistory {
+    pub results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    /// Result of `challenge`, if it was played
+    #[must_use]
+    pub fn result(&self, challenge: &DailyChallenge) -> Option<&DailyResult> {
+        let date = challenge.date();
+        self.results.iter().rev().find(|result| result.date == date)
+    }
+
+    /// Lines of the daily screen: today's challenge and the last results
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = match DailyChallenge::today() {
+            Some(challenge) => vec![
+                format!("{}: {}", challenge.date(), challenge.difficulty),
+                match self.result(&challenge) {
+                    Some(result) => result.to_string(),
+                    None => "Not played yet".to_string(),
+                },
+            ],
+            None => vec!["The date is unknown".to_string()],
+        };
+        lines.extend(
+            self.results
+                .iter()
+                .rev()
+                .skip_while(|result| {
+                    DailyChallenge::today().is_some_and(|today| result.date == today.date())
+                })
+                .take(HISTORY_LINES)
+                .map(|result| format!("{}: {}", result.date, result)),
+        );
+        lines
+    }
+
+    /// Loads the saved results, none if there are none or they can't be read
+    #[must_use]
+    pub fn load() -> Self {
+        let path = match config_file(DAILY_FILE) {
+            Some(path) if path.exists() => path,
+            _ => return Self::default(),
+        };
+        let history = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        history.unwrap_or_else(|e| {
+            log::error!(
+                "Failed to load daily results from {}: {}",
+                path.display(),
+                e
+            );
+            Self::default()
+        })
+    }
+
+    /// Writes the daily results file, creating its directory if needed
+    pub fn save(&self) -> Result<(), String> {
+        let path = match config_file(DAILY_FILE) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| e.to_string())
+    }
+}
+
+/// Daily challenge being played. Must be used as a resource, removed once the game is over
+#[derive(Debug)]
+pub struct DailyGame {
+    pub challenge: DailyChallenge,
+    /// Board of the challenge, once generated
+    pub board: Option<Entity>,
+    /// Board shown when the challenge was started, being replaced
+    replaced_board: Option<Entity>,
+    /// Options of the regular games, restored once the challenge board is generated
+    options: BoardOptions,
+    pub clicks: u32,
+}
+
+/// Daily challenge: a no-guess board seeded by the UTC date, scored once per day
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DailyHistory::load())
+            .add_system(daily_buttons)
+            .add_system(track_daily_game)
+            .add_system(finish_daily_game);
+        log::info!("Loaded Daily Plugin");
+    }
+}
+
+/// Starts the challenge of the day, and copies its summary
+#[allow(clippy::type_complexity)]
+fn daily_buttons(
+    mut commands: Commands,
+    interaction_query: Query<
+        (&Interaction, &ButtonAction),
+        (Changed<Interaction>, With<Button>, Without<Disabled>),
+    >,
+    mut state: ResMut<State<AppState>>,
+    board: Option<Res<Board>>,
+    board_options: Option<ResMut<BoardOptions>>,
+    mut history: ResMut<DailyHistory>,
+) {
+    let mut board_options = match board_options {
+        Some(options) => options,
+        None => return,
+    };
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let challenge = match DailyChallenge::today() {
+            Some(challenge) => challenge,
+            None => continue,
+        };
+        match action {
+            ButtonAction::PlayDaily if history.result(&challenge).is_none() => {
+                log::info!("Daily challenge {}", challenge.date());
+                commands.insert_resource(DailyGame {
+                    challenge,
+                    board: None,
+                    replaced_board: board.as_ref().map(|board| board.entity),
+                    options: board_options.clone(),
+                    clicks: 0,
+                });
+                challenge.apply(&mut board_options);
+                // The attempt counts from now on, quitting the game doesn't give another one
+                history.results.push(DailyResult {
+                    date: challenge.date(),
+                    difficulty: challenge.difficulty,
+                    outcome: Outcome::Abandoned,
+                    seconds: 0,
+                    bbbv: 0,
+                    clicks: 0,
+                    hints: 0,
+                    no_guess: true,
+                });
+                if let Err(e) = history.save() {
+                    log::error!("Failed to save daily results: {}", e);
+                }
+                log_state_error(state.replace(AppState::InGame));
+            }
+            ButtonAction::CopySummary => {
+                if let Some(result) = history.result(&challenge) {
+                    let summary = result.summary();
+                    log::info!("{}", summary);
+                    match copy_to_clipboard(&summary) {
+                        Ok(()) => log::info!("Summary copied to the clipboard"),
+                        Err(e) => log::error!("Failed to copy the summary: {}", e),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Finds the board of the challenge once generated, and restores the options of the regular
+/// games, and counts its clicks
+fn track_daily_game(
+    daily: Option<ResMut<DailyGame>>,
+    board: Option<Res<Board>>,
+    board_options: Option<ResMut<BoardOptions>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    let clicks = tile_trigger_evr.iter().count() + tile_mark_evr.iter().count();
+    let (mut daily, board) = match (daily, board) {
+        (Some(daily), Some(board)) => (daily, board),
+        _ => return,
+    };
+    if daily.board.is_none() && daily.replaced_board != Some(board.entity) {
+        daily.board = Some(board.entity);
+        if let Some(mut board_options) = board_options {
+            *board_options = daily.options.clone();
+        }
+    }
+    if daily.board == Some(board.entity) {
+        daily.clicks += clicks as u32;
+    }
+}
+
+/// Scores the finished challenge, and saves it
+#[allow(clippy::too_many_arguments)]
+fn finish_daily_game(
+    mut commands: Commands,
+    daily: Option<Res<DailyGame>>,
+    board: Option<Res<Board>>,
+    mut history: ResMut<DailyHistory>,
+    hints: Res<Hints>,
+    start_time: Option<Res<StartTime>>,
+    time: Res<Time>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    let won = board_completed_evr.iter().count() > 0;
+    let lost = bomb_explosion_evr.iter().count() > 0;
+    let (daily, board) = match (daily, board) {
+        (Some(daily), Some(board)) if (won || lost) && daily.board == Some(board.entity) => {
+            (daily, board)
+        }
+        _ => return,
+    };
+    commands.remove_resource::<DailyGame>();
+    let date = daily.challenge.date();
+    let result = match history
+        .results
+        .iter_mut()
+        .rev()
+        .find(|result| result.date == date)
+    {
+        Some(result) => result,
+        None => return,
+    };
+    result.outcome = if won { Outcome::Won } else { Outcome::Lost };
+    result.seconds = start_time.map_or(0, |start| {
+        start.elapsed(time.seconds_since_startup()) as u32
+    });
+    result.bbbv = bbbv(&board.tile_map);
+    result.clicks = daily.clicks;
+    result.hints = hints.count;
+    result.no_guess = is_no_guess(&board.tile_map);
+    log::info!("{}", result.summary());
+    if let Err(e) = history.save() {
+        log::error!("Failed to save daily results: {}", e);
+    }
+}
+
+/// Puts `text` on the system clipboard
+#[cfg(not(target_arch = "wasm32"))]
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text.to_string()))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("no clipboard access on the web, the summary is in the log".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Days since 1970-01-01 of 2000-02-29, a Tuesday
+    const LEAP_DAY: i64 = 11_016;
+    /// Days since 1970-01-01 of 2026-10-19, a Monday
+    const MONDAY: i64 = 20_745;
+
+    #[test]
+    fn dates_follow_the_gregorian_calendar() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(LEAP_DAY), (2000, 2, 29));
+        assert_eq!(civil_from_days(LEAP_DAY + 1), (2000, 3, 1));
+        assert_eq!(civil_from_days(MONDAY), (2026, 10, 19));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(DailyChallenge::of_day(MONDAY).date(), "2026-10-19");
+    }
+
+    #[test]
+    fn weekdays_pick_the_preset() {
+        assert_eq!(
+            DailyChallenge::of_day(0).difficulty,
+            Difficulty::Intermediate
+        );
+        assert_eq!(
+            DailyChallenge::of_day(LEAP_DAY).difficulty,
+            Difficulty::Beginner
+        );
+        assert_eq!(
+            DailyChallenge::of_day(MONDAY).difficulty,
+            Difficulty::Beginner
+        );
+        assert_eq!(
+            DailyChallenge::of_day(MONDAY + 2).difficulty,
+            Difficulty::Intermediate
+        );
+        assert_eq!(
+            DailyChallenge::of_day(MONDAY + 5).difficulty,
+            Difficulty::Expert
+        );
+        assert_eq!(
+            DailyChallenge::of_day(MONDAY + 6).difficulty,
+            Difficulty::Expert
+        );
+    }
+
+    #[test]
+    fn days_have_their_own_seed() {
+        assert_eq!(DailyChallenge::of_day(0).seed, 0xE220_A839_7B1D_CDAF);
+        assert_eq!(
+            DailyChallenge::of_day(MONDAY),
+            DailyChallenge::of_day(MONDAY)
+        );
+        assert_ne!(
+            DailyChallenge::of_day(MONDAY).seed,
+            DailyChallenge::of_day(MONDAY + 1).seed
+        );
+    }
+
+    fn result(outcome: Outcome, seconds: u32, hints: u32) -> DailyResult {
+        DailyResult {
+            date: "2026-10-19".to_string(),
+            difficulty: Difficulty::Beginner,
+            outcome,
+            seconds,
+            bbbv: 50,
+            clicks: 80,
+            hints,
+            no_guess: true,
+        }
+    }
+
+    #[test]
+    fn results_are_summarized() {
+        let won = result(Outcome::Won, 125, 0);
+        assert_eq!(won.to_string(), "Won in 2:05 - 0.40 3BV/s - 80 clicks");
+        assert_eq!(
+            won.summary(),
+            "Mine Sweeper daily 2026-10-19 (Beginner)\nWon in 2:05 - 0.40 3BV/s - 80 clicks"
+        );
+        assert_eq!(
+            result(Outcome::Lost, 7, 1).to_string(),
+            "Lost after 0:07 - 80 clicks - 1 hint"
+        );
+        assert_eq!(
+            result(Outcome::Abandoned, 0, 2).to_string(),
+            "Abandoned - 2 hints"
+        );
+        let guessing = DailyResult {
+            no_guess: false,
+            ..result(Outcome::Won, 50, 0)
+        };
+        assert_eq!(
+            guessing.to_string(),
+            "Won in 0:50 - 1.00 3BV/s - 80 clicks - guessing board"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::{Board, PlayerId, Players};
+
+use crate::{log_state_error, AppState};
+
+/// Text colors of the hot-seat players
+pub const PLAYER_COLORS: [Color; 4] = [Color::RED, Color::BLUE, Color::DARK_GREEN, Color::PURPLE];
+
+/// Current player and scores of a hot-seat game, in the UI bar
+#[derive(Component)]
+pub struct PlayerUI;
+
+/// Shows the turns of the hot-seat games, and their standings once over
+pub struct HotSeatPlugin;
+
+impl Plugin for HotSeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_player_ui).add_system(show_standings);
+        log::info!("Loaded Hot Seat Plugin");
+    }
+}
+
+#[must_use]
+pub fn player_color(player: PlayerId) -> Color {
+    PLAYER_COLORS[usize::from(player.0) % PLAYER_COLORS.len()]
+}
+
+/// A line per player, from the first to the last, as shown on the standings screen
+#[must_use]
+pub fn standings_lines(players: &Players) -> Vec<String> {
+    players
+        .standings()
+        .into_iter()
+        .map(|(rank, player, score)| {
+            format!(
+                "{}. {}: {} points, {} tiles, {} mines{}",
+                rank,
+                player,
+                score.points,
+                score.revealed,
+                score.mines,
+                if score.eliminated { ", out" } else { "" }
+            )
+        })
+        .collect()
+}
+
+/// Writes the current player and the scores, nothing for a single player game
+fn update_player_ui(board: Option<Res<Board>>, mut texts: Query<&mut Text, With<PlayerUI>>) {
+    let players = board.as_ref().and_then(|board| board.players.as_ref());
+    let (value, color) = match players {
+        Some(players) => {
+            let scores: Vec<_> = (0..players.scores.len() as u8)
+                .map(PlayerId)
+                .map(|player| match players.score(player) {
+                    score if score.eliminated => format!("P{} out", player.0 + 1),
+                    score => format!("P{} {}", player.0 + 1, score.points),
+                })
+                .collect();
+            (
+                format!("{}'s turn - {}", players.current, scores.join(" ")),
+                player_color(players.current),
+            )
+        }
+        None => (String::new(), Color::BLACK),
+    };
+    for mut text in texts.iter_mut() {
+        // The board changes on every uncovered tile, the text layout only when the text does
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+/// Shows the standings of a finished hot-seat game, once per board
+fn show_standings(
+    mut state: ResMut<State<AppState>>,
+    board: Option<Res<Board>>,
+    mut shown_board: Local<Option<Entity>>,
+) {
+    let board = match board {
+        Some(board) if board.players.is_some() => board,
+        _ => return,
+    };
+    // Finished games are paused under `AppState::Out`, cleared ones are left
+    let finished = state.current() == &AppState::Out && !state.inactives().is_empty();
+    if finished && *shown_board != Some(board.entity) {
+        *shown_board = Some(board.entity);
+        log_state_error(state.push(AppState::Standings));
+    }
+}
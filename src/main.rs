@@ -1,40 +1,105 @@
 mod buttons;
+mod coop;
+mod daily;
+mod hot_seat;
+mod menu;
+mod settings;
+mod stats;
+mod versus;
 
 use std::time::Duration;
 
+use bevy::app::AppExit;
+use bevy::asset::LoadState;
+use bevy::ecs::schedule::StateError;
 use bevy::log;
 use bevy::log::{Level, LogSettings};
 use bevy::prelude::*;
+use bevy::window::RequestRedraw;
 use bevy::winit::{UpdateMode, WinitSettings};
-use board_plugin::components::Uncover;
-use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::components::{FlagDrop, RevealAnimation, Shockwave};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent, HintEvent};
+use board_plugin::solver::{find_hint, ActiveHint, ProbabilityOverlay};
 
-use crate::buttons::{ButtonAction, ButtonColors};
+use crate::buttons::{ButtonAction, ButtonColors, Disabled};
+use crate::coop::CoopPlugin;
+use crate::daily::DailyPlugin;
+use crate::hot_seat::{HotSeatPlugin, PlayerUI};
+use crate::menu::MenuPlugin;
+use crate::settings::{BarUI, NumberAtlases, Settings, SettingsMenu, SettingsPlugin};
+use crate::stats::StatsPlugin;
+use crate::versus::VersusPlugin;
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::RegisterInspectable;
-use board_plugin::{Board, BoardAssets, BoardOptions, BoardPlugin, BoardPosition, SpriteMaterial};
+use board_plugin::coop::{CoopClient, CoopClientPlugin};
+use board_plugin::endless::EndlessPlugin;
+use board_plugin::sound::SoundPlugin;
+use board_plugin::versus::{VersusClient, VersusClientPlugin};
+use board_plugin::{
+    AnimationOptions, AssistLevel, Board, BoardAssets, BoardCommands, BoardOptions, BoardPlugin,
+    BoardPosition, BoardShape, ChunkMap, EndlessOptions, Neighborhood, Puzzle, RenderMode,
+    SoundOptions, SpriteMaterial, Topology,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum AppState {
+    /// Loading the board options and assets
+    Loading,
     InGame,
+    Endless,
     Out,
+    MainMenu,
+    /// Difficulty selection of a new game
+    Difficulty,
+    /// Pause menu, shown over the game
+    Paused,
+    /// Settings menu, pausing the game under it
+    Settings,
+    Stats,
+    /// Daily challenge screen, with today's result and the previous ones
+    Daily,
+    /// Ranking of a finished hot-seat game, shown over the board
+    Standings,
+    /// Result of a versus match, shown over the board
+    VersusResult,
+}
+
+impl AppState {
+    /// Is a board played or shown in this state, the other states being menus
+    #[must_use]
+    pub fn is_game(&self) -> bool {
+        matches!(self, Self::InGame | Self::Endless | Self::Out)
+    }
 }
 
 #[derive(Default)]
-pub struct Cheating {
+pub struct Hints {
     pub count: u32,
 }
 pub struct StartTime {
     pub epoch: f64,
+    /// Time at which the game was paused, if it is
+    pub paused_at: Option<f64>,
 }
 impl StartTime {
     pub fn new(epoch: f64) -> Self {
-        StartTime { epoch }
+        StartTime {
+            epoch,
+            paused_at: None,
+        }
+    }
+
+    /// Seconds played since `epoch`, not counting the pauses
+    pub fn elapsed(&self, now: f64) -> f64 {
+        self.paused_at.unwrap_or(now) - self.epoch
     }
 }
 
 #[derive(Component)]
-pub struct CheatUI;
+pub struct HintUI;
+
+#[derive(Component)]
+pub struct HintTextUI;
 
 #[derive(Component)]
 pub struct TimeUI;
@@ -42,13 +107,22 @@ pub struct TimeUI;
 #[derive(Component)]
 pub struct BombCountUI;
 
+/// Diagram of the tiles counted by the numbers
+#[derive(Component)]
+pub struct NeighborhoodUI;
+
 fn main() {
     let mut app = App::new();
+    // Saved user settings, their errors being logged once the log is set up
+    let (settings, settings_error) = match Settings::load() {
+        Ok(settings) => (settings, None),
+        Err(e) => (Settings::default(), Some(e)),
+    };
     // Window setup
     app.insert_resource(WindowDescriptor {
         title: "Mine Sweeper!".to_string(),
-        width: 700.,
-        height: 750.,
+        width: settings.window_size.0,
+        height: settings.window_size.1,
         ..Default::default()
     })
     .insert_resource(WinitSettings {
@@ -67,18 +141,109 @@ fn main() {
     })
     // Bevy default plugins
     .add_plugins(DefaultPlugins);
+    if let Some(e) = settings_error {
+        log::error!("{}, using the default settings", e);
+    }
     // Debug hierarchy inspector
     #[cfg(feature = "debug")]
     {
         app.add_plugin(bevy_inspector_egui::WorldInspectorPlugin::new());
         app.register_inspectable::<ButtonAction>();
     }
+    // Endless board, as `--endless`, always generating the same board with `--seed=<number>`
+    if std::env::args().any(|arg| arg == "--endless") {
+        let seed = std::env::args()
+            .find_map(|arg| arg.strip_prefix("--seed=").map(str::to_string))
+            .and_then(|seed| {
+                seed.parse()
+                    .map_err(|e| log::error!("invalid seed {:?}: {}", seed, e))
+                    .ok()
+            });
+        app.insert_resource(EndlessOptions {
+            seed,
+            ..Default::default()
+        })
+        .add_plugin(EndlessPlugin {
+            running_state: AppState::Endless,
+        });
+    }
+    // Sound effects, at a volume from 0 to 1 with `--volume=<volume>` or muted with `--mute`
+    let volume = std::env::args()
+        .find_map(|arg| arg.strip_prefix("--volume=").map(str::to_string))
+        .and_then(|volume| {
+            volume
+                .parse()
+                .map_err(|e| log::error!("invalid volume {:?}: {}", volume, e))
+                .ok()
+        });
+    app.insert_resource(SoundOptions {
+        volume: volume.unwrap_or(settings.volume),
+        muted: std::env::args().any(|arg| arg == "--mute"),
+        ..Default::default()
+    })
+    .add_plugin(SoundPlugin);
+    // User settings, edited from the settings menu
+    app.insert_resource(settings).add_plugin(SettingsPlugin);
+    // Menu screens and the stats they show
+    app.add_plugin(StatsPlugin).add_plugin(MenuPlugin);
+    // Daily challenge, the same no-guess board for everyone on a UTC day
+    app.add_plugin(DailyPlugin);
+    // Turns and standings of the hot-seat games
+    app.add_plugin(HotSeatPlugin);
+    // Versus match against another player, through the server at `--connect=<address>`, named
+    // with `--name=<name>`
+    if let Some(address) =
+        std::env::args().find_map(|arg| arg.strip_prefix("--connect=").map(str::to_string))
+    {
+        let name = std::env::args()
+            .find_map(|arg| arg.strip_prefix("--name=").map(str::to_string))
+            .unwrap_or_else(|| "Player".to_string());
+        match VersusClient::connect(address.as_str(), &name) {
+            Ok(client) => {
+                log::info!("Connected to {}, waiting for an opponent", address);
+                app.insert_resource(client)
+                    .add_plugin(VersusClientPlugin)
+                    .add_plugin(VersusPlugin);
+            }
+            Err(e) => log::error!("Failed to connect to {}: {}", address, e),
+        }
+    }
+    // Board shared with other players, through the server at `--coop=<address>`
+    if let Some(address) =
+        std::env::args().find_map(|arg| arg.strip_prefix("--coop=").map(str::to_string))
+    {
+        let name = std::env::args()
+            .find_map(|arg| arg.strip_prefix("--name=").map(str::to_string))
+            .unwrap_or_else(|| "Player".to_string());
+        match CoopClient::connect(address.as_str(), &name) {
+            Ok(client) => {
+                log::info!("Joined the shared board of {}", address);
+                app.insert_resource(client)
+                    .insert_resource(BoardCommands::shared())
+                    .add_plugin(CoopClientPlugin)
+                    .add_plugin(CoopPlugin);
+            }
+            Err(e) => log::error!("Failed to connect to {}: {}", address, e),
+        }
+    }
+    // Changes are shown instantly with `--reduced-motion`
+    app.insert_resource(AnimationOptions {
+        reduced_motion: std::env::args().any(|arg| arg == "--reduced-motion"),
+        ..Default::default()
+    });
     // Board plugin
     app.add_plugin(BoardPlugin {
         running_state: AppState::InGame,
     })
-    .add_state(AppState::Out)
-    .add_system_set(SystemSet::on_update(AppState::Out).with_system(setup_board))
+    .add_state(AppState::Loading)
+    .add_system_set(SystemSet::on_update(AppState::Loading).with_system(setup_board))
+    // Game timer, stopped while a menu or the end of the game is shown over the board
+    .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(start_game))
+    .add_system_set(SystemSet::on_enter(AppState::Endless).with_system(start_game))
+    .add_system_set(SystemSet::on_pause(AppState::InGame).with_system(pause_game))
+    .add_system_set(SystemSet::on_pause(AppState::Endless).with_system(pause_game))
+    .add_system_set(SystemSet::on_resume(AppState::InGame).with_system(resume_game))
+    .add_system_set(SystemSet::on_resume(AppState::Endless).with_system(resume_game))
     // Startup system (cameras)
     .add_startup_system(setup_camera)
     // UI
@@ -86,32 +251,132 @@ fn main() {
     // State handling
     .add_system(input_handler)
     .add_system(update_ui)
+    .add_system(update_neighborhood_ui)
     .add_system(check_end_of_game)
+    .add_system(request_animation_frames)
     // Run the app
     .run();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn setup_board(
     mut commands: Commands,
     mut state: ResMut<State<AppState>>,
     asset_server: Res<AssetServer>,
     mut run_state: Local<u8>,
+    mut puzzle_handle: Local<Option<Handle<Puzzle>>>,
+    puzzles: Res<Assets<Puzzle>>,
+    board_options: Option<ResMut<BoardOptions>>,
+    mut windows: ResMut<Windows>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    settings: Res<Settings>,
 ) {
     match *run_state {
         0 => {
-            // Board plugin options
+            // A puzzle file can be given as argument
+            *puzzle_handle = std::env::args()
+                .skip(1)
+                .find(|path| path.ends_with(".puzzle.json"))
+                .map(|path| asset_server.load(path.as_str()));
+            // Assist level, as `--assist=<none|flag|reveal|full>`, overriding the settings
+            let assist = std::env::args()
+                .find_map(|arg| arg.strip_prefix("--assist=").map(str::to_string))
+                .map(|level| {
+                    level.parse().unwrap_or_else(|e| {
+                        log::error!("{}", e);
+                        AssistLevel::None
+                    })
+                })
+                .unwrap_or(settings.assist);
+            // Hexagonal tiles, as `--hex`
+            let topology = if std::env::args().any(|arg| arg == "--hex") {
+                Topology::Hex
+            } else {
+                Topology::Square
+            };
+            // Wrapping edges as `--wrap`, drawing the opposite edges around the board with
+            // `--ghosts`
+            let wrap = std::env::args().any(|arg| arg == "--wrap");
+            let wrap_ghosts = std::env::args().any(|arg| arg == "--ghosts");
+            // Board outline, as `--shape=<heart|donut>` or a mask file as `--shape=<path>`
+            let shape = std::env::args()
+                .find_map(|arg| arg.strip_prefix("--shape=").map(str::to_string))
+                .map(|shape| {
+                    BoardShape::load(&shape).unwrap_or_else(|e| {
+                        log::error!("Failed to load shape {}: {}", shape, e);
+                        BoardShape::Rectangle
+                    })
+                })
+                .unwrap_or_default();
+            // Multi-mine tiles, as `--multi-mine=<2|3>`
+            let max_mines_per_cell = std::env::args()
+                .find_map(|arg| arg.strip_prefix("--multi-mine=").map(str::to_string))
+                .map(|max| {
+                    max.parse::<u8>()
+                        .map(|max| max.clamp(1, 3))
+                        .unwrap_or_else(|e| {
+                            log::error!("invalid mine count {:?}: {}", max, e);
+                            1
+                        })
+                })
+                .unwrap_or(1);
+            // Tiles counted by the numbers, as `--neighborhood=<adjacent|orthogonal|knight|radius2>`
+            // or custom delta coordinates as `--neighborhood=x,y;x,y;...`
+            let neighborhood = std::env::args()
+                .find_map(|arg| arg.strip_prefix("--neighborhood=").map(str::to_string))
+                .map(|neighborhood| {
+                    neighborhood.parse().unwrap_or_else(|e| {
+                        log::error!("{}", e);
+                        Neighborhood::Adjacent
+                    })
+                })
+                .unwrap_or_default();
+            // Tiles drawn in chunk images, as `--chunks`
+            let render_mode = if std::env::args().any(|arg| arg == "--chunks") {
+                RenderMode::Chunks
+            } else {
+                RenderMode::Sprites
+            };
+            // Difficulty rating band, as `--rating=<min>-<max>`
+            let rating_band = std::env::args()
+                .find_map(|arg| arg.strip_prefix("--rating=").map(str::to_string))
+                .and_then(|band| {
+                    let (min, max) = band.split_once('-')?;
+                    match (min.parse(), max.parse()) {
+                        (Ok(min), Ok(max)) => Some((min, max)),
+                        _ => {
+                            log::error!("invalid rating band {:?}, expected MIN-MAX", band);
+                            None
+                        }
+                    }
+                });
+            // Board plugin options, sized by the difficulty of the settings
             commands.insert_resource(BoardOptions {
-                map_size: (30, 16),
-                bomb_count: 99,
+                map_size: settings.difficulty.map_size(),
+                bomb_count: settings.difficulty.bomb_count(),
+                max_mines_per_cell,
                 tile_padding: 1.,
                 safe_start: true,
                 position: BoardPosition::Centered {
                     offset: Vec3::new(0., 25., 0.),
                 },
+                topology,
+                shape,
+                wrap,
+                wrap_ghosts,
+                neighborhood,
+                assist,
+                question_marks: settings.question_marks,
+                rating_band,
+                render_mode,
                 ..Default::default()
             });
-            // Board assets
-            commands.insert_resource(BoardAssets {
+            // Bomb counters are drawn from the sprite sheet of the theme, or written with the
+            // font with `--font-numbers`
+            let number_atlases = (!std::env::args().any(|arg| arg == "--font-numbers"))
+                .then(|| NumberAtlases::load(&asset_server, &mut texture_atlases));
+            // Board assets, colored by the theme of the settings
+            let mut board_assets = BoardAssets {
                 label: "Default".to_string(),
                 board_material: SpriteMaterial {
                     color: Color::WHITE,
@@ -127,6 +392,7 @@ fn setup_board(
                 },
                 bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
                 bomb_counter_colors: BoardAssets::default_colors(),
+                bomb_counter_atlas: None,
                 flag_material: SpriteMaterial {
                     texture: asset_server.load("sprites/flag.png"),
                     color: Color::WHITE,
@@ -135,22 +401,53 @@ fn setup_board(
                     texture: asset_server.load("sprites/bomb.png"),
                     color: Color::WHITE,
                 },
-            });
+            };
+            settings
+                .theme
+                .apply(&mut board_assets, number_atlases.as_ref());
+            commands.insert_resource(board_assets);
+            if let Some(number_atlases) = number_atlases {
+                commands.insert_resource(number_atlases);
+            }
             *run_state = 1;
             bevy::log::info!("Loaded assets");
         }
         1 => {
-            // Launch game
-            bevy::log::info!("Switch to ingame");
-            if *state.current() != AppState::InGame {
-                state.set(AppState::InGame).unwrap();
-                *run_state = 2;
+            // Wait for the puzzle to be loaded
+            if let (Some(handle), Some(mut options)) = (puzzle_handle.as_ref(), board_options) {
+                match puzzles.get(handle) {
+                    Some(puzzle) => {
+                        log::info!("Loaded puzzle {}", puzzle.title);
+                        if let Some(window) = windows.get_primary_mut() {
+                            window.set_title(format!("Mine Sweeper! - {}", puzzle.title));
+                        }
+                        options.puzzle = Some(puzzle.clone());
+                    }
+                    None if asset_server.get_load_state(handle) == LoadState::Failed => {
+                        log::error!("Failed to load puzzle, using a random board");
+                    }
+                    None => return,
+                }
+                *puzzle_handle = None;
             }
+            // Show the main menu
+            bevy::log::info!("Switch to main menu");
+            state.set(AppState::MainMenu).unwrap();
+            *run_state = 2;
         }
         _ => {}
     }
 }
 
+/// State of a running game, `AppState::Endless` if the endless mode is enabled
+fn playing_state(endless: bool) -> AppState {
+    if endless {
+        AppState::Endless
+    } else {
+        AppState::InGame
+    }
+}
+
 fn setup_camera(mut commands: Commands) {
     // 2D orthographic camera
     commands.spawn_bundle(Camera2dBundle::default());
@@ -159,52 +456,34 @@ fn setup_camera(mut commands: Commands) {
 #[allow(clippy::type_complexity)]
 #[allow(clippy::too_many_arguments)]
 fn input_handler(
-    mut commands: Commands,
     button_colors: Res<ButtonColors>,
     mut interaction_query: Query<
-        (&Interaction, &ButtonAction, &mut UiColor),
+        (&Interaction, &ButtonAction, &mut UiColor, Option<&Disabled>),
         (Changed<Interaction>, With<Button>),
     >,
     mut state: ResMut<State<AppState>>,
-    mut board: Option<ResMut<Board>>,
-    mut cheating: ResMut<Cheating>,
-    mut start_time: ResMut<StartTime>,
-    time: Res<Time>,
+    board: Option<Res<Board>>,
+    mut board_options: Option<ResMut<BoardOptions>>,
+    mut hints: ResMut<Hints>,
+    mut hint_ewr: EventWriter<HintEvent>,
+    mut probability_overlay: ResMut<ProbabilityOverlay>,
+    endless_options: Option<Res<EndlessOptions>>,
+    keys: Res<Input<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut exit_ewr: EventWriter<AppExit>,
 ) {
-    for (interaction, action, mut color) in interaction_query.iter_mut() {
+    let playing = playing_state(endless_options.is_some());
+    let mut actions = Vec::new();
+    for (interaction, action, mut color, disabled) in interaction_query.iter_mut() {
+        if disabled.is_some() {
+            *color = button_colors.disabled.into();
+            continue;
+        }
         match *interaction {
             Interaction::Clicked => {
                 *color = button_colors.pressed.into();
-                match action {
-                    ButtonAction::Clear => {
-                        log::debug!("clearing detected");
-                        if state.current() == &AppState::InGame {
-                            log::info!("clearing game");
-                            state.set(AppState::Out).unwrap();
-                        }
-                    }
-                    ButtonAction::Generate => {
-                        log::debug!("loading detected");
-                        if state.current() == &AppState::Out {
-                            log::info!("loading game");
-                            if state.current() == &AppState::Out {
-                                cheating.count = 0;
-                                start_time.epoch = time.seconds_since_startup();
-                            }
-                            state.set(AppState::InGame).unwrap();
-                        }
-                    }
-                    &ButtonAction::Cheat => {
-                        if let Some(ref mut board) = board {
-                            if let Some(coord) = board.find_safe_covered_coord() {
-                                for entity in board.tile_to_uncover(&coord) {
-                                    commands.entity(entity).insert(Uncover);
-                                    cheating.count += 1;
-                                }
-                            }
-                        }
-                    }
-                }
+                actions.push(*action);
             }
             Interaction::Hovered => {
                 *color = button_colors.hovered.into();
@@ -214,46 +493,243 @@ fn input_handler(
             }
         }
     }
+    // The next pressed key is bound to the button picked in the settings menu
+    if let Some(action) = settings_menu.rebinding {
+        if let Some(key) = keys.get_just_pressed().next() {
+            log::info!("{:?} key: {:?}", action, key);
+            settings.key_bindings.set_key(action, *key);
+            settings_menu.rebinding = None;
+        }
+    } else {
+        // Only the navigation keys work on the menu screens
+        let menu_open = !state.current().is_game();
+        actions.extend(settings.key_bindings.just_pressed(&keys).filter(|action| {
+            !menu_open || matches!(action, ButtonAction::Settings | ButtonAction::Back)
+        }));
+    }
+    // There is a game to go back to under the menus
+    let can_continue = state.inactives().iter().any(AppState::is_game);
+    for action in actions {
+        match action {
+            ButtonAction::Clear => {
+                log::debug!("clearing detected");
+                if state.current() == &playing {
+                    log::info!("clearing game");
+                    state.set(AppState::Out).unwrap();
+                }
+            }
+            ButtonAction::Generate => {
+                log::debug!("loading detected");
+                if state.current() == &AppState::Out {
+                    log::info!("loading game");
+                    state.set(playing.clone()).unwrap();
+                }
+            }
+            ButtonAction::Hint => {
+                if state.current() != &AppState::InGame {
+                    continue;
+                }
+                if let Some(hint) = board.as_ref().and_then(|board| find_hint(board)) {
+                    log::info!("Hint on {}: {}", hint.coords, hint.explanation);
+                    hints.count += 1;
+                    hint_ewr.send(HintEvent(hint));
+                }
+            }
+            ButtonAction::Probabilities => {
+                probability_overlay.enabled = !probability_overlay.enabled;
+                log::info!("Probability overlay: {}", probability_overlay.enabled);
+            }
+            ButtonAction::Settings => {
+                settings_menu.rebinding = None;
+                if state.current() == &AppState::Settings {
+                    log_state_error(state.pop());
+                } else {
+                    log_state_error(state.push(AppState::Settings));
+                }
+            }
+            ButtonAction::NewGame => log_state_error(state.push(AppState::Difficulty)),
+            ButtonAction::Stats => log_state_error(state.push(AppState::Stats)),
+            ButtonAction::Daily => log_state_error(state.push(AppState::Daily)),
+            // Handled by the daily plugin
+            ButtonAction::PlayDaily | ButtonAction::CopySummary => (),
+            ButtonAction::Continue if can_continue => log_state_error(state.pop()),
+            ButtonAction::Continue => (),
+            ButtonAction::Resume => log_state_error(state.pop()),
+            ButtonAction::MainMenu => log_state_error(state.set(AppState::MainMenu)),
+            ButtonAction::Quit => exit_ewr.send(AppExit),
+            ButtonAction::Beginner | ButtonAction::Intermediate | ButtonAction::Expert => {
+                if let Some(difficulty) = action.difficulty() {
+                    log::info!("New {} game", difficulty);
+                    settings.difficulty = difficulty;
+                }
+                // The board is generated on this frame, before the settings are applied
+                if let Some(options) = board_options.as_mut() {
+                    settings.apply(options);
+                }
+                // Ends the game under the menus, if any
+                log_state_error(state.replace(playing.clone()));
+            }
+            // Pauses the game, or goes back to the previous screen
+            ButtonAction::Back => {
+                if state.current().is_game() {
+                    log_state_error(state.push(AppState::Paused));
+                } else if state.current() != &AppState::MainMenu || can_continue {
+                    settings_menu.rebinding = None;
+                    log_state_error(state.pop());
+                }
+            }
+        }
+    }
 }
 
-#[allow(clippy::type_complexity)]
+/// Logs the state changes which failed, another one being already queued on this frame
+fn log_state_error(result: Result<(), StateError>) {
+    if let Err(e) = result {
+        log::error!("Failed to change the screen: {}", e);
+    }
+}
+
+/// Resets the hints and the timer of a new game
+fn start_game(mut hints: ResMut<Hints>, mut start_time: ResMut<StartTime>, time: Res<Time>) {
+    hints.count = 0;
+    *start_time = StartTime::new(time.seconds_since_startup());
+}
+
+/// Stops the timer while the game is under a menu or finished
+fn pause_game(mut start_time: ResMut<StartTime>, time: Res<Time>) {
+    start_time.paused_at = Some(time.seconds_since_startup());
+}
+
+fn resume_game(mut start_time: ResMut<StartTime>, time: Res<Time>) {
+    if let Some(paused_at) = start_time.paused_at.take() {
+        start_time.epoch += time.seconds_since_startup() - paused_at;
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn update_ui(
     mut query: ParamSet<(
-        Query<&mut Text, With<CheatUI>>,
+        Query<&mut Text, With<HintUI>>,
         Query<&mut Text, With<TimeUI>>,
         Query<&mut Text, With<BombCountUI>>,
+        Query<&mut Text, With<HintTextUI>>,
     )>,
-    cheating: Res<Cheating>,
-    //mut time_text_query: Query<&mut Text, (With<TimeUI>, Without<CheatUI>)>,
+    hints: Res<Hints>,
+    active_hint: Res<ActiveHint>,
+    //mut time_text_query: Query<&mut Text, (With<TimeUI>, Without<HintUI>)>,
     start_time: Res<StartTime>,
     time: Res<Time>,
     state: Res<State<AppState>>,
     board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    chunk_map: Option<Res<ChunkMap>>,
 ) {
-    if state.current() == &AppState::InGame {
-        if let Ok(mut cheat_text) = query.p0().get_single_mut() {
-            cheat_text.sections[0].value = format!("Cheats: {}", cheating.count);
+    if state.current() != &AppState::Out {
+        if let Ok(mut hint_text) = query.p0().get_single_mut() {
+            hint_text.sections[0].value = format!("Hints: {}", hints.count);
+        }
+        if let Ok(mut explanation_text) = query.p3().get_single_mut() {
+            explanation_text.sections[0].value = match &active_hint.0 {
+                Some(hint) => hint.explanation.clone(),
+                None => String::new(),
+            };
         }
         if let Ok(mut time_text) = query.p1().get_single_mut() {
-            let time_passed = (time.seconds_since_startup() - start_time.epoch) as u32;
+            let time_passed = start_time.elapsed(time.seconds_since_startup()) as u32;
             let seconds = time_passed % 60;
             let minutes = time_passed / 60;
-            time_text.sections[0].value = format!("Time: {minutes}:{seconds:02}");
+            time_text.sections[0].value =
+                match board_options.as_ref().and_then(|o| o.puzzle.as_ref()) {
+                    Some(puzzle) => format!(
+                        "Time: {minutes}:{seconds:02}/{}:{:02}",
+                        puzzle.par_time / 60,
+                        puzzle.par_time % 60
+                    ),
+                    None => format!("Time: {minutes}:{seconds:02}"),
+                };
         }
         if let (Ok(mut bomb_count_text), Some(board)) = (query.p2().get_single_mut(), board) {
             let bomb_count = board.tile_map.bomb_count();
-            let marked_fields = board.marked_tiles.len();
+            let marked_fields = board.marked_count();
             bomb_count_text.sections[0].value = format!("{marked_fields}/{bomb_count}");
         }
+        // Endless boards have no bomb count, the score is shown instead
+        if let (Ok(mut score_text), Some(chunk_map)) = (query.p2().get_single_mut(), chunk_map) {
+            score_text.sections[0].value = format!("Score: {}", chunk_map.cleared());
+        }
+    }
+}
+
+/// Keeps updating the app while tiles are being uncovered or animated, the app being only
+/// updated on input otherwise
+#[allow(clippy::type_complexity)]
+fn request_animation_frames(
+    board: Option<Res<Board>>,
+    animations: Query<(), Or<(With<RevealAnimation>, With<FlagDrop>, With<Shockwave>)>>,
+    mut redraw_events: EventWriter<RequestRedraw>,
+) {
+    let uncovering = board.is_some_and(|board| !board.pending_uncovers.is_empty());
+    if uncovering || !animations.is_empty() {
+        redraw_events.send(RequestRedraw);
     }
 }
 
-fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, time: Res<Time>) {
-    let button_materials = ButtonColors {
-        normal: Color::GRAY,
-        hovered: Color::DARK_GRAY,
-        pressed: Color::BLACK,
+/// Draws the neighborhood of the board options as a grid of cells, the tile in black and its
+/// neighbors in red
+fn update_neighborhood_ui(
+    mut commands: Commands,
+    board_options: Option<Res<BoardOptions>>,
+    query: Query<Entity, With<NeighborhoodUI>>,
+) {
+    let board_options = match board_options {
+        Some(o) if o.is_changed() => o,
+        _ => return,
     };
+    let diagram = board_options.neighborhood.diagram();
+    // The diagram fits in the hint line
+    let cell_size = 30. / diagram.len() as f32;
+    for entity in query.iter() {
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|parent| {
+            for line in diagram.iter() {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..Default::default()
+                        },
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for cell in line.chars() {
+                            let color = match cell {
+                                'o' => Color::BLACK,
+                                'x' => Color::RED,
+                                _ => Color::GRAY,
+                            };
+                            parent.spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(cell_size), Val::Px(cell_size)),
+                                    ..Default::default()
+                                },
+                                color: color.into(),
+                                ..Default::default()
+                            });
+                        }
+                    });
+            }
+        });
+    }
+}
+
+fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    button_colors: Res<ButtonColors>,
+) {
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
@@ -274,102 +750,175 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>, time: Res<Ti
                         justify_content: JustifyContent::Center,
                         ..Default::default()
                     },
-                    color: Color::WHITE.into(),
+                    color: settings.theme.bar_color().into(),
                     ..Default::default()
                 })
                 .insert(Name::new("UI"))
+                .insert(BarUI)
                 .with_children(|parent| {
                     let font = asset_server.load("fonts/pixeled.ttf");
-                    setup_single_menu(
-                        parent,
-                        "CLEAR",
-                        button_materials.normal.into(),
-                        font.clone(),
-                        ButtonAction::Clear,
-                    );
-                    setup_single_menu(
-                        parent,
-                        "CHEAT",
-                        button_materials.normal.into(),
-                        font.clone(),
-                        ButtonAction::Cheat,
-                    );
-                    setup_single_menu(
-                        parent,
-                        "GENERATE",
-                        button_materials.normal.into(),
-                        font,
-                        ButtonAction::Generate,
-                    );
+                    for action in ButtonAction::BAR {
+                        setup_single_menu(
+                            parent,
+                            action.label(),
+                            button_colors.normal.into(),
+                            font.clone(),
+                            action,
+                        );
+                    }
                 });
             parent
                 .spawn_bundle(NodeBundle {
                     style: Style {
-                        size: Size::new(Val::Percent(100.), Val::Px(50.)),
-                        align_items: AlignItems::Center,
-                        justify_content: JustifyContent::SpaceBetween,
+                        size: Size::new(Val::Percent(100.), Val::Px(80.)),
+                        flex_direction: FlexDirection::Column,
                         ..Default::default()
                     },
-                    color: Color::WHITE.into(),
+                    color: settings.theme.bar_color().into(),
                     ..Default::default()
                 })
+                .insert(BarUI)
                 .with_children(|parent| {
                     let font = asset_server.load("fonts/pixeled.ttf");
                     parent
-                        .spawn_bundle(TextBundle {
-                            text: Text::from_section(
-                                "Cheats: 0".to_string(),
-                                TextStyle {
-                                    font: font.clone(),
-                                    font_size: 60.0,
-                                    color: Color::BLACK,
-                                },
-                            )
-                            .with_alignment(TextAlignment {
-                                vertical: VerticalAlign::Center,
-                                horizontal: HorizontalAlign::Center,
-                            }),
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.), Val::Px(30.)),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
                             ..Default::default()
                         })
-                        .insert(CheatUI);
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        position: UiRect {
+                                            left: Val::Px(5.),
+                                            top: Val::Px(0.),
+                                            ..Default::default()
+                                        },
+                                        flex_direction: FlexDirection::ColumnReverse,
+                                        ..Default::default()
+                                    },
+                                    color: Color::NONE.into(),
+                                    ..Default::default()
+                                })
+                                .insert(Name::new("Neighborhood"))
+                                .insert(NeighborhoodUI);
+                            parent
+                                .spawn_bundle(TextBundle {
+                                    text: Text::from_section(
+                                        String::new(),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    ..Default::default()
+                                })
+                                .insert(HintTextUI);
+                            parent
+                                .spawn_bundle(TextBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        position: UiRect {
+                                            right: Val::Px(5.),
+                                            top: Val::Px(0.),
+                                            ..Default::default()
+                                        },
+                                        ..Default::default()
+                                    },
+                                    text: Text::from_section(
+                                        String::new(),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 20.0,
+                                            color: Color::BLACK,
+                                        },
+                                    ),
+                                    ..Default::default()
+                                })
+                                .insert(Name::new("Players"))
+                                .insert(PlayerUI);
+                        });
                     parent
-                        .spawn_bundle(TextBundle {
-                            text: Text::from_section(
-                                "0/0".to_string(),
-                                TextStyle {
-                                    font: font.clone(),
-                                    font_size: 60.0,
-                                    color: Color::BLACK,
-                                },
-                            )
-                            .with_alignment(TextAlignment {
-                                vertical: VerticalAlign::Center,
-                                horizontal: HorizontalAlign::Center,
-                            }),
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.), Val::Px(50.)),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::SpaceBetween,
+                                ..Default::default()
+                            },
+                            color: Color::NONE.into(),
                             ..Default::default()
                         })
-                        .insert(BombCountUI);
-                    parent
-                        .spawn_bundle(TextBundle {
-                            text: Text::from_section(
-                                "Time: 0:00".to_string(),
-                                TextStyle {
-                                    font,
-                                    font_size: 60.0,
-                                    color: Color::BLACK,
-                                },
-                            )
-                            .with_alignment(TextAlignment {
-                                vertical: VerticalAlign::Center,
-                                horizontal: HorizontalAlign::Center,
-                            }),
-                            ..Default::default()
-                        })
-                        .insert(TimeUI);
+                        .with_children(|parent| {
+                            parent
+                                .spawn_bundle(TextBundle {
+                                    text: Text::from_section(
+                                        "Hints: 0".to_string(),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 60.0,
+                                            color: Color::BLACK,
+                                        },
+                                    )
+                                    .with_alignment(
+                                        TextAlignment {
+                                            vertical: VerticalAlign::Center,
+                                            horizontal: HorizontalAlign::Center,
+                                        },
+                                    ),
+                                    ..Default::default()
+                                })
+                                .insert(HintUI);
+                            parent
+                                .spawn_bundle(TextBundle {
+                                    text: Text::from_section(
+                                        "0/0".to_string(),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 60.0,
+                                            color: Color::BLACK,
+                                        },
+                                    )
+                                    .with_alignment(
+                                        TextAlignment {
+                                            vertical: VerticalAlign::Center,
+                                            horizontal: HorizontalAlign::Center,
+                                        },
+                                    ),
+                                    ..Default::default()
+                                })
+                                .insert(BombCountUI);
+                            parent
+                                .spawn_bundle(TextBundle {
+                                    text: Text::from_section(
+                                        "Time: 0:00".to_string(),
+                                        TextStyle {
+                                            font,
+                                            font_size: 60.0,
+                                            color: Color::BLACK,
+                                        },
+                                    )
+                                    .with_alignment(
+                                        TextAlignment {
+                                            vertical: VerticalAlign::Center,
+                                            horizontal: HorizontalAlign::Center,
+                                        },
+                                    ),
+                                    ..Default::default()
+                                })
+                                .insert(TimeUI);
+                        });
                 });
         });
-    commands.insert_resource(button_materials);
-    commands.insert_resource(Cheating::default());
+    commands.insert_resource(Hints::default());
     commands.insert_resource(StartTime::new(time.seconds_since_startup()));
 }
 
@@ -418,23 +967,22 @@ fn setup_single_menu(
 }
 
 fn check_end_of_game(
-    mut commands: Commands,
     mut win_events: EventReader<BoardCompletedEvent>,
     mut bomb_explode_events: EventReader<BombExplosionEvent>,
     mut state: ResMut<State<AppState>>,
-    board: Option<Res<Board>>,
+    board: Option<ResMut<Board>>,
 ) {
-    if win_events.iter().next().is_some() || bomb_explode_events.iter().next().is_some() {
-        state.push(AppState::Out).unwrap();
-        if let Some(board) = board {
-            board
-                .get_all_bomb_coordinates()
-                .iter()
-                .filter_map(|coordinate| board.covered_tiles.get(coordinate))
-                .for_each(|entity| {
+    let over = win_events.iter().count() > 0 || bomb_explode_events.iter().count() > 0;
+    // Uncovering the other bombs sends more explosions once the game is over
+    if over && state.current() != &AppState::Out {
+        log_state_error(state.push(AppState::Out));
+        if let Some(mut board) = board {
+            for coordinate in board.get_all_bomb_coordinates() {
+                if board.covered_tiles.contains(&coordinate) {
                     bevy::log::info!("Uncover bomb");
-                    commands.entity(*entity).insert(Uncover);
-                });
+                    board.queue_uncover(coordinate);
+                }
+            }
         }
     }
     //bevy::log::info!("Frame update");
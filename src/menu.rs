@@ -0,0 +1,232 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::versus::VersusClient;
+use board_plugin::Board;
+
+use crate::buttons::{ButtonAction, ButtonColors, Disabled};
+use crate::daily::{DailyChallenge, DailyHistory};
+use crate::hot_seat::standings_lines;
+use crate::settings::{spawn_setting_buttons, Settings, SettingsMenu};
+use crate::stats::Stats;
+use crate::versus::result_lines;
+use crate::AppState;
+
+/// Menu screen, drawn over the game
+#[derive(Debug, Copy, Clone)]
+pub struct Screen {
+    pub title: &'static str,
+    pub buttons: &'static [ButtonAction],
+}
+
+/// Menu screen of `state`, `None` for the game states
+#[must_use]
+pub fn screen(state: &AppState) -> Option<Screen> {
+    let (title, buttons): (_, &'static [ButtonAction]) = match state {
+        AppState::MainMenu => (
+            "Mine Sweeper!",
+            &[
+                ButtonAction::NewGame,
+                ButtonAction::Continue,
+                ButtonAction::Daily,
+                ButtonAction::Stats,
+                ButtonAction::Settings,
+                ButtonAction::Quit,
+            ],
+        ),
+        AppState::Difficulty => (
+            "New game",
+            &[
+                ButtonAction::Beginner,
+                ButtonAction::Intermediate,
+                ButtonAction::Expert,
+                ButtonAction::Back,
+            ],
+        ),
+        AppState::Paused => (
+            "Paused",
+            &[
+                ButtonAction::Resume,
+                ButtonAction::Settings,
+                ButtonAction::MainMenu,
+            ],
+        ),
+        AppState::Settings => ("Settings", &[ButtonAction::Back]),
+        AppState::Stats => ("Stats", &[ButtonAction::Back]),
+        AppState::Standings => ("Standings", &[ButtonAction::Back, ButtonAction::MainMenu]),
+        AppState::VersusResult => ("Versus", &[ButtonAction::Back, ButtonAction::MainMenu]),
+        AppState::Daily => (
+            "Daily",
+            &[
+                ButtonAction::PlayDaily,
+                ButtonAction::CopySummary,
+                ButtonAction::Back,
+            ],
+        ),
+        AppState::Loading | AppState::InGame | AppState::Endless | AppState::Out => return None,
+    };
+    Some(Screen { title, buttons })
+}
+
+/// Root node of the menu screen
+#[derive(Component)]
+pub struct MenuUI;
+
+/// Draws the menu screen of the current state
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ButtonColors>().add_system(update_menu);
+        log::info!("Loaded Menu Plugin");
+    }
+}
+
+/// Spawns the menu screen of the state when it changes
+#[allow(clippy::too_many_arguments)]
+fn update_menu(
+    mut commands: Commands,
+    state: Res<State<AppState>>,
+    asset_server: Res<AssetServer>,
+    colors: Res<ButtonColors>,
+    settings: Res<Settings>,
+    settings_menu: Res<SettingsMenu>,
+    stats: Res<Stats>,
+    daily_history: Res<DailyHistory>,
+    board: Option<Res<Board>>,
+    versus_client: Option<Res<VersusClient>>,
+    menus: Query<Entity, With<MenuUI>>,
+) {
+    if !state.is_changed() && !stats.is_changed() && !daily_history.is_changed() {
+        return;
+    }
+    for entity in menus.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let screen = match screen(state.current()) {
+        Some(screen) => screen,
+        None => return,
+    };
+    // There is a game to go back to under the menus
+    let can_continue = state.inactives().iter().any(AppState::is_game);
+    // The daily challenge is played once, and its result shared afterwards
+    let today = DailyChallenge::today();
+    let daily_played = today.is_some_and(|today| daily_history.result(&today).is_some());
+    let font = asset_server.load("fonts/pixeled.ttf");
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0., 0., 0., 0.85).into(),
+            ..Default::default()
+        })
+        .insert(Name::new(format!("{} menu", screen.title)))
+        .insert(MenuUI)
+        .with_children(|parent| {
+            spawn_text(parent, screen.title, &font, 50.);
+            if *state.current() == AppState::Stats {
+                for line in stats.lines() {
+                    spawn_text(parent, &line, &font, 18.);
+                }
+            }
+            if *state.current() == AppState::Standings {
+                if let Some(players) = board.as_ref().and_then(|board| board.players.as_ref()) {
+                    for line in standings_lines(players) {
+                        spawn_text(parent, &line, &font, 18.);
+                    }
+                }
+            }
+            if *state.current() == AppState::VersusResult {
+                if let Some(client) = &versus_client {
+                    for line in result_lines(client) {
+                        spawn_text(parent, &line, &font, 18.);
+                    }
+                }
+            }
+            if *state.current() == AppState::Daily {
+                for line in daily_history.lines() {
+                    spawn_text(parent, &line, &font, 18.);
+                }
+            }
+            if *state.current() == AppState::Settings {
+                spawn_setting_buttons(parent, &font, &colors, &settings, &settings_menu);
+            }
+            for action in screen.buttons {
+                let enabled = match action {
+                    ButtonAction::Continue => can_continue,
+                    ButtonAction::Daily => today.is_some(),
+                    ButtonAction::PlayDaily => today.is_some() && !daily_played,
+                    ButtonAction::CopySummary => daily_played,
+                    _ => true,
+                };
+                spawn_button(parent, *action, &font, &colors, enabled);
+            }
+        });
+}
+
+fn spawn_text(parent: &mut ChildBuilder, text: &str, font: &Handle<Font>, font_size: f32) {
+    parent.spawn_bundle(TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(10.)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            text.to_string(),
+            TextStyle {
+                font: font.clone(),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        ..Default::default()
+    });
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    action: ButtonAction,
+    font: &Handle<Font>,
+    colors: &ButtonColors,
+    enabled: bool,
+) {
+    let color = if enabled {
+        colors.normal
+    } else {
+        colors.disabled
+    };
+    let mut button = parent.spawn_bundle(ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Percent(60.), Val::Px(50.)),
+            margin: UiRect::all(Val::Px(6.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        color: color.into(),
+        ..Default::default()
+    });
+    button
+        .insert(action)
+        .insert(Name::new(action.label()))
+        .with_children(|builder| {
+            builder.spawn_bundle(TextBundle {
+                text: Text::from_section(
+                    action.label(),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..Default::default()
+            });
+        });
+    if !enabled {
+        button.insert(Disabled);
+    }
+}
@@ -0,0 +1,627 @@
+use std::fmt::{self, Formatter};
+use std::path::PathBuf;
+
+use bevy::log;
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+use board_plugin::{
+    AssistLevel, BoardAssets, BoardOptions, HotSeatOptions, MinePenalty, SoundOptions, ATLAS_GLYPHS,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::buttons::{ButtonAction, ButtonColors};
+
+/// Settings file, in the `minesweeper-tutorial` directory of the user configuration directory
+const SETTINGS_FILE: &str = "settings.json";
+/// Delay between the last change and the saving of the settings, in seconds. Resizing the window
+/// changes them on every frame
+const SAVE_DELAY: f64 = 0.5;
+
+/// Board size and bomb count presets
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// 9x9 tiles with 10 bombs
+    Beginner,
+    /// 16x16 tiles with 40 bombs
+    Intermediate,
+    /// 30x16 tiles with 99 bombs
+    #[default]
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    /// Difficulty of a board of `map_size` tiles with `bomb_count` bombs, if it is a preset
+    #[must_use]
+    pub fn of(map_size: (u16, u16), bomb_count: u16) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|d| d.map_size() == map_size && d.bomb_count() == bomb_count)
+    }
+
+    #[must_use]
+    pub fn map_size(self) -> (u16, u16) {
+        match self {
+            Self::Beginner => (9, 9),
+            Self::Intermediate => (16, 16),
+            Self::Expert => (30, 16),
+        }
+    }
+
+    #[must_use]
+    pub fn bomb_count(self) -> u16 {
+        match self {
+            Self::Beginner => 10,
+            Self::Intermediate => 40,
+            Self::Expert => 99,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Beginner => Self::Intermediate,
+            Self::Intermediate => Self::Expert,
+            Self::Expert => Self::Beginner,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Beginner => "Beginner",
+            Self::Intermediate => "Intermediate",
+            Self::Expert => "Expert",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Colors of the board, the UI bars and the window background
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Sets the board colors of `board_assets`, and its bomb counter glyphs from
+    /// `number_atlases`. Bomb counters are written with the font without atlases
+    pub fn apply(self, board_assets: &mut BoardAssets, number_atlases: Option<&NumberAtlases>) {
+        let (board, tile, covered_tile) = match self {
+            Self::Light => (Color::WHITE, Color::DARK_GRAY, Color::GRAY),
+            Self::Dark => (
+                Color::rgb(0.15, 0.15, 0.18),
+                Color::rgb(0.3, 0.3, 0.33),
+                Color::rgb(0.55, 0.55, 0.6),
+            ),
+        };
+        board_assets.board_material.color = board;
+        board_assets.tile_material.color = tile;
+        board_assets.covered_tile_material.color = covered_tile;
+        board_assets.bomb_counter_atlas = number_atlases.map(|atlases| atlases.get(self));
+    }
+
+    /// Bomb counter sprite sheet, the dark theme glyphs are outlined to stand out of the lighter
+    /// tiles
+    #[must_use]
+    pub fn numbers_sprite(self) -> &'static str {
+        match self {
+            Self::Light => "sprites/numbers.png",
+            Self::Dark => "sprites/numbers_dark.png",
+        }
+    }
+
+    #[must_use]
+    pub fn clear_color(self) -> Color {
+        match self {
+            Self::Light => Color::rgb(0.4, 0.4, 0.4),
+            Self::Dark => Color::rgb(0.05, 0.05, 0.08),
+        }
+    }
+
+    /// Background of the UI bars
+    #[must_use]
+    pub fn bar_color(self) -> Color {
+        match self {
+            Self::Light => Color::WHITE,
+            Self::Dark => Color::GRAY,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+}
+
+/// Bomb counter glyphs of every theme. Must be used as a resource, missing when the bomb counters
+/// are written with the font
+#[derive(Debug, Clone)]
+pub struct NumberAtlases {
+    light: Handle<TextureAtlas>,
+    dark: Handle<TextureAtlas>,
+}
+
+impl NumberAtlases {
+    /// Loads the sprite sheets of the themes, 8 glyphs of 16x16 pixels each
+    pub fn load(asset_server: &AssetServer, texture_atlases: &mut Assets<TextureAtlas>) -> Self {
+        let mut load = |theme: Theme| {
+            texture_atlases.add(TextureAtlas::from_grid(
+                asset_server.load(theme.numbers_sprite()),
+                Vec2::splat(16.),
+                usize::from(ATLAS_GLYPHS),
+                1,
+            ))
+        };
+        Self {
+            light: load(Theme::Light),
+            dark: load(Theme::Dark),
+        }
+    }
+
+    /// Glyphs of `theme`
+    #[must_use]
+    pub fn get(&self, theme: Theme) -> Handle<TextureAtlas> {
+        match theme {
+            Theme::Light => self.light.clone(),
+            Theme::Dark => self.dark.clone(),
+        }
+    }
+}
+
+/// Keys triggering the UI buttons
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub clear: KeyCode,
+    pub generate: KeyCode,
+    pub hint: KeyCode,
+    pub probabilities: KeyCode,
+    pub settings: KeyCode,
+    pub back: KeyCode,
+}
+
+impl KeyBindings {
+    /// Buttons having a key
+    pub const ACTIONS: [ButtonAction; 6] = [
+        ButtonAction::Clear,
+        ButtonAction::Generate,
+        ButtonAction::Hint,
+        ButtonAction::Probabilities,
+        ButtonAction::Settings,
+        ButtonAction::Back,
+    ];
+
+    /// Key of the `action` button, if it has one
+    #[must_use]
+    pub fn key(&self, action: ButtonAction) -> Option<KeyCode> {
+        match action {
+            ButtonAction::Clear => Some(self.clear),
+            ButtonAction::Generate => Some(self.generate),
+            ButtonAction::Hint => Some(self.hint),
+            ButtonAction::Probabilities => Some(self.probabilities),
+            ButtonAction::Settings => Some(self.settings),
+            ButtonAction::Back => Some(self.back),
+            _ => None,
+        }
+    }
+
+    /// Binds `key` to the `action` button, if it can have one
+    pub fn set_key(&mut self, action: ButtonAction, key: KeyCode) {
+        let binding = match action {
+            ButtonAction::Clear => &mut self.clear,
+            ButtonAction::Generate => &mut self.generate,
+            ButtonAction::Hint => &mut self.hint,
+            ButtonAction::Probabilities => &mut self.probabilities,
+            ButtonAction::Settings => &mut self.settings,
+            ButtonAction::Back => &mut self.back,
+            _ => return,
+        };
+        *binding = key;
+    }
+
+    /// Buttons triggered by the keys pressed on this frame
+    pub fn just_pressed<'a>(
+        &'a self,
+        keys: &'a Input<KeyCode>,
+    ) -> impl Iterator<Item = ButtonAction> + 'a {
+        Self::ACTIONS
+            .iter()
+            .copied()
+            .filter(move |action| self.key(*action).is_some_and(|key| keys.just_pressed(key)))
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            clear: KeyCode::C,
+            generate: KeyCode::G,
+            hint: KeyCode::H,
+            probabilities: KeyCode::O,
+            settings: KeyCode::S,
+            back: KeyCode::Escape,
+        }
+    }
+}
+
+/// User settings, loaded at startup and saved on change. Must be used as a resource.
+///
+/// Board changes apply to the next generated board
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Last played difficulty
+    pub difficulty: Difficulty,
+    pub theme: Theme,
+    pub assist: AssistLevel,
+    pub question_marks: bool,
+    /// Hot-seat players sharing the board, 1 for a single player game
+    pub players: u8,
+    /// Penalty of the hot-seat players revealing a mine
+    pub mine_penalty: MinePenalty,
+    pub key_bindings: KeyBindings,
+    /// Sound volume, from 0 to 1
+    pub volume: f32,
+    /// Window size, in logical pixels
+    pub window_size: (f32, f32),
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::default(),
+            theme: Theme::default(),
+            assist: AssistLevel::None,
+            question_marks: false,
+            players: 1,
+            mine_penalty: MinePenalty::default(),
+            key_bindings: KeyBindings::default(),
+            volume: SoundOptions::default().volume,
+            window_size: (700., 750.),
+        }
+    }
+}
+
+impl Settings {
+    /// Settings file path, `None` when there is no configuration directory to save it in
+    #[must_use]
+    pub fn path() -> Option<PathBuf> {
+        config_file(SETTINGS_FILE)
+    }
+
+    /// Loads the saved settings, the default ones if there are none. Called before the log
+    /// setup, so errors are returned to be logged later
+    pub fn load() -> Result<Self, String> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut settings: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        // The volume button cycles through the tenths of the full volume
+        settings.volume = settings.volume.clamp(0., 1.);
+        Ok(settings)
+    }
+
+    /// Writes the settings file, creating its directory if needed
+    pub fn save(&self) -> Result<(), String> {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| e.to_string())?;
+        log::debug!("Saved settings to {}", path.display());
+        Ok(())
+    }
+
+    /// Applies the board settings to the options of the next board
+    pub fn apply(&self, options: &mut BoardOptions) {
+        options.map_size = self.difficulty.map_size();
+        options.bomb_count = self.difficulty.bomb_count();
+        options.assist = self.assist;
+        options.question_marks = self.question_marks;
+        options.hot_seat = (self.players > 1).then_some(HotSeatOptions {
+            players: self.players,
+            mine_penalty: self.mine_penalty,
+        });
+    }
+}
+
+/// Path of the `name` file in the `minesweeper-tutorial` directory of the user configuration
+/// directory, `None` when there is no such directory
+#[must_use]
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("minesweeper-tutorial").join(name))
+}
+
+/// State of the settings menu, open in the `AppState::Settings` state. Must be used as a resource
+#[derive(Debug, Default)]
+pub struct SettingsMenu {
+    /// Button waiting for a key to be bound to
+    pub rebinding: Option<ButtonAction>,
+}
+
+/// Setting edited by a settings menu button
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub enum SettingButton {
+    Difficulty,
+    Theme,
+    Assist,
+    QuestionMarks,
+    Players,
+    MinePenalty,
+    Volume,
+    Key(ButtonAction),
+}
+
+impl SettingButton {
+    const ALL: [Self; 13] = [
+        Self::Difficulty,
+        Self::Theme,
+        Self::Assist,
+        Self::QuestionMarks,
+        Self::Players,
+        Self::MinePenalty,
+        Self::Volume,
+        Self::Key(ButtonAction::Clear),
+        Self::Key(ButtonAction::Generate),
+        Self::Key(ButtonAction::Hint),
+        Self::Key(ButtonAction::Probabilities),
+        Self::Key(ButtonAction::Settings),
+        Self::Key(ButtonAction::Back),
+    ];
+
+    fn label(self, settings: &Settings, menu: &SettingsMenu) -> String {
+        match self {
+            Self::Difficulty => format!("Difficulty: {}", settings.difficulty),
+            Self::Theme => format!("Theme: {:?}", settings.theme),
+            Self::Assist => format!(
+                "Assist: {}",
+                match settings.assist {
+                    AssistLevel::None => "none",
+                    AssistLevel::AutoFlag => "flag",
+                    AssistLevel::AutoReveal => "reveal",
+                    AssistLevel::Full => "full",
+                }
+            ),
+            Self::QuestionMarks => format!(
+                "Question marks: {}",
+                if settings.question_marks { "on" } else { "off" }
+            ),
+            Self::Players => format!("Players: {}", settings.players),
+            Self::MinePenalty => match settings.mine_penalty {
+                MinePenalty::Points(points) => format!("Hot-seat mines: -{} points", points),
+                MinePenalty::Eliminate => "Hot-seat mines: eliminate".to_string(),
+            },
+            Self::Volume => format!("Volume: {:.0}%", settings.volume * 100.),
+            Self::Key(action) if menu.rebinding == Some(action) => {
+                format!("{} key: press a key", action.label())
+            }
+            Self::Key(action) => match settings.key_bindings.key(action) {
+                Some(key) => format!("{} key: {:?}", action.label(), key),
+                None => format!("{} key: none", action.label()),
+            },
+        }
+    }
+
+    /// Changes the setting to its next value
+    fn cycle(self, settings: &mut Settings, menu: &mut SettingsMenu) {
+        match self {
+            Self::Difficulty => settings.difficulty = settings.difficulty.next(),
+            Self::Theme => settings.theme = settings.theme.next(),
+            Self::Assist => {
+                settings.assist = match settings.assist {
+                    AssistLevel::None => AssistLevel::AutoFlag,
+                    AssistLevel::AutoFlag => AssistLevel::AutoReveal,
+                    AssistLevel::AutoReveal => AssistLevel::Full,
+                    AssistLevel::Full => AssistLevel::None,
+                }
+            }
+            Self::QuestionMarks => settings.question_marks = !settings.question_marks,
+            // A single player, or 2 to 4 hot-seat players
+            Self::Players => settings.players = settings.players.clamp(1, 4) % 4 + 1,
+            Self::MinePenalty => {
+                settings.mine_penalty = match settings.mine_penalty {
+                    MinePenalty::Points(_) => MinePenalty::Eliminate,
+                    MinePenalty::Eliminate => MinePenalty::default(),
+                }
+            }
+            // From mute to full volume by tenths
+            Self::Volume => {
+                let tenths = (settings.volume * 10.).round() as u8;
+                settings.volume = f32::from((tenths + 1) % 11) / 10.;
+            }
+            Self::Key(action) => menu.rebinding = Some(action),
+        }
+    }
+}
+
+/// Background of a UI bar, colored by the theme
+#[derive(Component)]
+pub struct BarUI;
+
+/// Applies and saves the `Settings` resource on change, and edits it from the settings menu
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Settings>() {
+            app.insert_resource(Settings::load().unwrap_or_else(|e| {
+                log::error!("{}, using the default settings", e);
+                Settings::default()
+            }));
+        }
+        let theme = app.world.resource::<Settings>().theme;
+        app.insert_resource(ClearColor(theme.clear_color()))
+            .init_resource::<SettingsMenu>()
+            .add_system(settings_menu_handler)
+            .add_system(update_settings_menu)
+            .add_system(record_window_size)
+            .add_system(apply_settings)
+            .add_system(save_settings);
+        log::info!("Loaded Settings Plugin");
+    }
+}
+
+/// Applies the changed settings to the next board, the sounds and the colors
+fn apply_settings(
+    settings: Res<Settings>,
+    board_options: Option<ResMut<BoardOptions>>,
+    board_assets: Option<ResMut<BoardAssets>>,
+    number_atlases: Option<Res<NumberAtlases>>,
+    sound_options: Option<ResMut<SoundOptions>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut bars: Query<&mut UiColor, With<BarUI>>,
+) {
+    // The settings are applied with the command line arguments on startup
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    if let Some(mut options) = board_options {
+        settings.apply(&mut options);
+    }
+    if let Some(mut board_assets) = board_assets {
+        settings
+            .theme
+            .apply(&mut board_assets, number_atlases.as_deref());
+    }
+    if let Some(mut sound_options) = sound_options {
+        sound_options.volume = settings.volume;
+    }
+    clear_color.0 = settings.theme.clear_color();
+    for mut color in bars.iter_mut() {
+        *color = settings.theme.bar_color().into();
+    }
+}
+
+/// Saves the settings once they stopped changing for `SAVE_DELAY`
+fn save_settings(settings: Res<Settings>, time: Res<Time>, mut changed_at: Local<Option<f64>>) {
+    if settings.is_changed() && !settings.is_added() {
+        *changed_at = Some(time.seconds_since_startup());
+    }
+    if changed_at.is_some_and(|at| time.seconds_since_startup() - at >= SAVE_DELAY) {
+        *changed_at = None;
+        if let Err(e) = settings.save() {
+            log::error!("Failed to save settings: {}", e);
+        }
+    }
+}
+
+/// Keeps the window size of the settings up to date
+fn record_window_size(mut settings: ResMut<Settings>, mut resized_evr: EventReader<WindowResized>) {
+    let size = resized_evr
+        .iter()
+        .rfind(|event| event.id.is_primary())
+        .map(|event| (event.width, event.height));
+    if let Some(size) = size {
+        if settings.window_size != size {
+            settings.window_size = size;
+        }
+    }
+}
+
+/// Edits the settings from the menu buttons
+#[allow(clippy::type_complexity)]
+fn settings_menu_handler(
+    button_colors: Res<ButtonColors>,
+    mut interaction_query: Query<
+        (&Interaction, &SettingButton, &mut UiColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<SettingsMenu>,
+) {
+    for (interaction, button, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = button_colors.pressed.into();
+                button.cycle(&mut settings, &mut menu);
+                log::info!("{}", button.label(&settings, &menu));
+            }
+            Interaction::Hovered => {
+                *color = button_colors.hovered.into();
+            }
+            Interaction::None => {
+                *color = button_colors.normal.into();
+            }
+        }
+    }
+}
+
+/// Writes the current settings on the settings menu buttons
+fn update_settings_menu(
+    settings: Res<Settings>,
+    menu: Res<SettingsMenu>,
+    buttons: Query<(&SettingButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !menu.is_changed() {
+        return;
+    }
+    for (button, children) in buttons.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = button.label(&settings, &menu);
+            }
+        }
+    }
+}
+
+/// Spawns a button per setting, see `menu::spawn_menu`
+pub fn spawn_setting_buttons(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    colors: &ButtonColors,
+    settings: &Settings,
+    menu: &SettingsMenu,
+) {
+    for button in SettingButton::ALL {
+        parent
+            .spawn_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(60.), Val::Px(30.)),
+                    margin: UiRect::all(Val::Px(3.)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                color: colors.normal.into(),
+                ..Default::default()
+            })
+            .insert(button)
+            .insert(Name::new(format!("{:?}", button)))
+            .with_children(|builder| {
+                builder.spawn_bundle(TextBundle {
+                    text: Text::from_section(
+                        button.label(settings, menu),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 14.,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    ..Default::default()
+                });
+            });
+    }
+}
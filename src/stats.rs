@@ -0,0 +1,160 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::{Board, BoardOptions};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{config_file, Difficulty};
+use crate::StartTime;
+
+/// Stats file, next to the settings file
+const STATS_FILE: &str = "stats.json";
+
+/// Results of the games of a difficulty
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyStats {
+    pub played: u32,
+    pub won: u32,
+    /// Best winning time, in seconds
+    pub best_time: Option<u32>,
+}
+
+/// Results of the random boards of each difficulty. Must be used as a resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub beginner: DifficultyStats,
+    pub intermediate: DifficultyStats,
+    pub expert: DifficultyStats,
+}
+
+impl Stats {
+    #[must_use]
+    pub fn get(&self, difficulty: Difficulty) -> &DifficultyStats {
+        match difficulty {
+            Difficulty::Beginner => &self.beginner,
+            Difficulty::Intermediate => &self.intermediate,
+            Difficulty::Expert => &self.expert,
+        }
+    }
+
+    fn get_mut(&mut self, difficulty: Difficulty) -> &mut DifficultyStats {
+        match difficulty {
+            Difficulty::Beginner => &mut self.beginner,
+            Difficulty::Intermediate => &mut self.intermediate,
+            Difficulty::Expert => &mut self.expert,
+        }
+    }
+
+    /// Adds a game of `difficulty`, lasting `seconds`
+    pub fn record(&mut self, difficulty: Difficulty, won: bool, seconds: u32) {
+        let stats = self.get_mut(difficulty);
+        stats.played += 1;
+        if won {
+            stats.won += 1;
+            stats.best_time = Some(stats.best_time.map_or(seconds, |best| best.min(seconds)));
+        }
+    }
+
+    /// A line per difficulty, as shown on the stats screen
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        Difficulty::ALL
+            .iter()
+            .map(|difficulty| {
+                let stats = self.get(*difficulty);
+                let best = match stats.best_time {
+                    Some(time) => format!("best {}:{:02}", time / 60, time % 60),
+                    None => "no win".to_string(),
+                };
+                format!(
+                    "{}: {}/{} won, {}",
+                    difficulty, stats.won, stats.played, best
+                )
+            })
+            .collect()
+    }
+
+    /// Loads the saved stats, empty ones if there are none or they can't be read
+    #[must_use]
+    pub fn load() -> Self {
+        let path = match config_file(STATS_FILE) {
+            Some(path) if path.exists() => path,
+            _ => return Self::default(),
+        };
+        let stats = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        stats.unwrap_or_else(|e| {
+            log::error!("Failed to load stats from {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Writes the stats file, creating its directory if needed
+    pub fn save(&self) -> Result<(), String> {
+        let path = match config_file(STATS_FILE) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| e.to_string())
+    }
+}
+
+/// Records the result of every finished single player random board of a difficulty in the
+/// `Stats` resource
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Stats::load()).add_system(record_games);
+        log::info!("Loaded Stats Plugin");
+    }
+}
+
+/// Adds the finished games to the stats, and saves them
+#[allow(clippy::too_many_arguments)]
+fn record_games(
+    mut stats: ResMut<Stats>,
+    board: Option<Res<Board>>,
+    board_options: Option<Res<BoardOptions>>,
+    start_time: Option<Res<StartTime>>,
+    time: Res<Time>,
+    mut recorded_board: Local<Option<Entity>>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    let won = board_completed_evr.iter().count() > 0;
+    let lost = bomb_explosion_evr.iter().count() > 0;
+    let board = match board {
+        Some(board) if won || lost => board,
+        _ => return,
+    };
+    // Uncovering the other bombs sends more explosions
+    if *recorded_board == Some(board.entity) {
+        return;
+    }
+    *recorded_board = Some(board.entity);
+    if board_options.is_some_and(|options| options.puzzle.is_some()) || board.players.is_some() {
+        return;
+    }
+    let tile_map = &board.tile_map;
+    let difficulty =
+        match Difficulty::of((tile_map.width(), tile_map.height()), tile_map.bomb_count()) {
+            Some(difficulty) => difficulty,
+            None => return,
+        };
+    // Paused time isn't counted, as for the daily results
+    let seconds = start_time.map_or(0, |start| {
+        start.elapsed(time.seconds_since_startup()) as u32
+    });
+    stats.record(difficulty, won, seconds);
+    if let Err(e) = stats.save() {
+        log::error!("Failed to save stats: {}", e);
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::versus::{
+    BoardSpec, EndReason, ServerMessage, VersusClient, VersusEvent, VersusStatus,
+};
+use board_plugin::BoardOptions;
+
+use crate::{log_state_error, AppState};
+
+/// Size of a mini-map tile, in pixels
+const MINI_MAP_TILE_SIZE: f32 = 6.;
+
+/// Mini-map of the opponent board, in the top right corner
+#[derive(Component)]
+pub struct MiniMapUI;
+
+/// Tile of the mini-map
+#[derive(Component)]
+pub struct MiniMapTile(pub Coordinates);
+
+/// Starts the boards sent by the versus server, shows the opponent progress and the results
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(handle_versus_events)
+            .add_system(update_mini_map);
+        log::info!("Loaded Versus Plugin");
+    }
+}
+
+/// Lines of the result screen
+#[must_use]
+pub fn result_lines(client: &VersusClient) -> Vec<String> {
+    let opponent = client
+        .opponent
+        .as_ref()
+        .map_or("The opponent", |opponent| opponent.name.as_str());
+    let mut lines = vec![match (&client.status, client.won()) {
+        (VersusStatus::Finished { reason, .. }, Some(won)) => match (won, reason) {
+            (true, EndReason::Cleared) => "You cleared the board first!".to_string(),
+            (true, EndReason::Exploded) => format!("{} revealed a mine, you win!", opponent),
+            (true, EndReason::Disconnected) => format!("{} left, you win!", opponent),
+            (false, EndReason::Cleared) => format!("{} cleared the board first", opponent),
+            (false, EndReason::Exploded) => "You revealed a mine".to_string(),
+            (false, EndReason::Disconnected) => "You left the match".to_string(),
+        },
+        (VersusStatus::Disconnected(e), _) => format!("Disconnected: {}", e),
+        _ => "The match is still played".to_string(),
+    }];
+    if let Some(progress) = &client.opponent {
+        lines.push(format!(
+            "{}: {} tiles left, {} flags",
+            progress.name, progress.remaining, progress.flags
+        ));
+    }
+    lines
+}
+
+/// Starts the match boards, and shows the results
+fn handle_versus_events(
+    mut commands: Commands,
+    mut versus_evr: EventReader<VersusEvent>,
+    mut state: ResMut<State<AppState>>,
+    board_options: Option<ResMut<BoardOptions>>,
+    mini_maps: Query<Entity, With<MiniMapUI>>,
+) {
+    let mut board_options = match board_options {
+        Some(options) => options,
+        None => return,
+    };
+    for VersusEvent(message) in versus_evr.iter() {
+        match message {
+            ServerMessage::Welcome { player, .. } => {
+                log::info!("Joined the versus server as player {}", player + 1);
+            }
+            ServerMessage::Start { board, opponent } => {
+                log::info!("Versus match against {}", opponent);
+                board.apply(&mut board_options);
+                for entity in mini_maps.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                spawn_mini_map(&mut commands, board);
+                log_state_error(state.replace(AppState::InGame));
+            }
+            ServerMessage::Result { .. } => {
+                // Shown over the finished game, or over the game still played
+                if state.current() != &AppState::VersusResult {
+                    log_state_error(state.push(AppState::VersusResult));
+                }
+            }
+            ServerMessage::Error { .. } | ServerMessage::Progress { .. } => (),
+        }
+    }
+}
+
+fn spawn_mini_map(commands: &mut Commands, board: &BoardSpec) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(10.),
+                    top: Val::Px(10.),
+                    ..Default::default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                ..Default::default()
+            },
+            color: Color::NONE.into(),
+            ..Default::default()
+        })
+        .insert(Name::new("Mini-map"))
+        .insert(MiniMapUI)
+        .with_children(|parent| {
+            // The top row first
+            for y in (0..board.height).rev() {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..Default::default()
+                        },
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for x in 0..board.width {
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: Style {
+                                        size: Size::new(
+                                            Val::Px(MINI_MAP_TILE_SIZE),
+                                            Val::Px(MINI_MAP_TILE_SIZE),
+                                        ),
+                                        margin: UiRect::all(Val::Px(0.5)),
+                                        ..Default::default()
+                                    },
+                                    color: Color::GRAY.into(),
+                                    ..Default::default()
+                                })
+                                .insert(MiniMapTile(Coordinates { x, y }));
+                        }
+                    });
+            }
+        });
+}
+
+/// Lights up the tiles uncovered by the opponent
+fn update_mini_map(
+    client: Option<Res<VersusClient>>,
+    mut tiles: Query<(&MiniMapTile, &mut UiColor)>,
+) {
+    let client = match client {
+        Some(client) if client.is_changed() => client,
+        _ => return,
+    };
+    let progress = match &client.opponent {
+        Some(progress) => progress,
+        None => return,
+    };
+    for (MiniMapTile(coords), mut color) in tiles.iter_mut() {
+        let uncovered = progress.uncovered.contains(coords);
+        if uncovered && color.0 != Color::WHITE {
+            *color = Color::WHITE.into();
+        }
+    }
+}
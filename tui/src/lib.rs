@@ -0,0 +1,185 @@
+use board_plugin::components::Coordinates;
+use board_plugin::headless::{GameStatus, HeadlessBoard};
+use board_plugin::{BoardOptions, Neighborhood, Topology};
+use colored::Colorize;
+use crossterm::event::KeyCode;
+use std::time::{Duration, Instant};
+
+/// Terminal front-end state: a headless board, a cursor and the game timer
+pub struct TuiGame {
+    options: BoardOptions,
+    board: HeadlessBoard,
+    cursor: Coordinates,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+}
+
+impl TuiGame {
+    pub fn new(options: BoardOptions) -> Self {
+        let board = HeadlessBoard::new(options.clone());
+        Self {
+            options,
+            board,
+            cursor: Coordinates::default(),
+            started: None,
+            finished: None,
+        }
+    }
+
+    /// Restarts with a freshly generated board
+    pub fn restart(&mut self) {
+        *self = Self::new(self.options.clone());
+    }
+
+    /// Handles a key press, returns `false` if the player wants to quit
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        let (width, height) = self.map_size();
+        match key {
+            KeyCode::Up => self.cursor.y = (self.cursor.y + 1).min(height - 1),
+            KeyCode::Down => self.cursor.y = self.cursor.y.saturating_sub(1),
+            KeyCode::Left => self.cursor.x = self.cursor.x.saturating_sub(1),
+            KeyCode::Right => self.cursor.x = (self.cursor.x + 1).min(width - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.started.get_or_insert_with(Instant::now);
+                self.board.uncover(self.cursor);
+            }
+            KeyCode::Char('f') => self.board.toggle_mark(self.cursor),
+            KeyCode::Char('n') => self.restart(),
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            _ => (),
+        }
+        if self.finished.is_none() && self.board.status() != GameStatus::Playing {
+            self.finished = Some(self.elapsed());
+        }
+        true
+    }
+
+    /// Headless board played by this front-end
+    #[must_use]
+    pub fn board(&self) -> &HeadlessBoard {
+        &self.board
+    }
+
+    #[must_use]
+    pub fn cursor(&self) -> Coordinates {
+        self.cursor
+    }
+
+    /// Board size, which may come from a layout instead of the options
+    #[must_use]
+    pub fn map_size(&self) -> (u16, u16) {
+        let tile_map = &self.board.board().tile_map;
+        (tile_map.width(), tile_map.height())
+    }
+
+    /// Time since the first uncover, frozen when the game ends
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        match (self.finished, self.started) {
+            (Some(d), _) => d,
+            (None, Some(start)) => start.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    /// Bomb count minus the marked tiles count
+    #[must_use]
+    pub fn remaining_mines(&self) -> i32 {
+        let board = self.board.board();
+        i32::from(board.tile_map.bomb_count()) - board.marked_count() as i32
+    }
+
+    /// Mine counter, timer and game status line
+    #[must_use]
+    pub fn render_status(&self) -> String {
+        let seconds = self.elapsed().as_secs();
+        let status = match self.board.status() {
+            GameStatus::Playing => "",
+            GameStatus::Won => "You win!",
+            GameStatus::Lost => "Boom!",
+        };
+        format!(
+            "Mines: {:>3}  Time: {}:{:02}  {}",
+            self.remaining_mines(),
+            seconds / 60,
+            seconds % 60,
+            status
+        )
+    }
+
+    /// Renders the board, top line first, the cursor tile surrounded by brackets
+    #[must_use]
+    pub fn render_grid(&self) -> Vec<String> {
+        let (width, height) = self.map_size();
+        let topology = self.board.board().tile_map.topology();
+        (0..height)
+            .rev()
+            .map(|y| {
+                // Shifted hex rows start with a space
+                let shift = if topology == Topology::Hex && y % 2 == 1 {
+                    " "
+                } else {
+                    ""
+                };
+                let cells: String = (0..width)
+                    .map(|x| {
+                        let coords = Coordinates { x, y };
+                        let tile = self.render_tile(coords);
+                        if coords == self.cursor {
+                            format!("[{}]", tile)
+                        } else {
+                            format!(" {} ", tile)
+                        }
+                    })
+                    .collect();
+                format!("{}{}", shift, cells)
+            })
+            .collect()
+    }
+
+    /// Diagram of the tiles counted by the numbers, empty for the usual adjacent tiles
+    #[must_use]
+    pub fn render_neighborhood(&self) -> Vec<String> {
+        let neighborhood = self.board.board().tile_map.neighborhood();
+        if *neighborhood == Neighborhood::Adjacent {
+            return Vec::new();
+        }
+        let mut lines = vec![format!("Numbers count {} neighbors:", neighborhood.name())];
+        lines.extend(neighborhood.diagram());
+        lines.push(String::new());
+        lines
+    }
+
+    /// Full screen: status line, neighborhood, board and key help
+    #[must_use]
+    pub fn render(&self) -> Vec<String> {
+        let mut lines = vec![self.render_status(), String::new()];
+        lines.extend(self.render_neighborhood());
+        lines.extend(self.render_grid());
+        lines.push(String::new());
+        lines.push("arrows: move  space: uncover  f: mark  n: new game  q: quit".to_string());
+        lines
+    }
+
+    fn render_tile(&self, coords: Coordinates) -> String {
+        let board = self.board.board();
+        let tile = board.tile_map.get_tile(coords);
+        let lost = self.board.status() == GameStatus::Lost;
+        if let Some(count) = board.marked_tiles.get(&coords) {
+            if lost && !tile.is_bomb() {
+                "x".red().to_string()
+            } else if *count > 1 {
+                // Multi-mine flags show their count
+                count.to_string().bright_red().bold().to_string()
+            } else {
+                "F".bright_red().bold().to_string()
+            }
+        } else if board.is_questioned(&coords) && !(lost && tile.is_bomb()) {
+            "?".bright_yellow().to_string()
+        } else if board.covered_tiles.contains(&coords) && !(lost && tile.is_bomb()) {
+            "#".dimmed().to_string()
+        } else {
+            tile.console_output()
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use board_plugin::{
+    AssistLevel, BoardLayout, BoardOptions, BoardShape, LayoutFormat, Neighborhood, Topology,
+};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use minesweeper_tui::TuiGame;
+use std::error::Error;
+use std::fs;
+use std::io::{stdout, Stdout, Write};
+use std::path::Path;
+use std::time::Duration;
+
+fn main() -> crossterm::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let arg = args.iter().find(|a| !a.starts_with("--")).cloned();
+    let assist = match args.iter().find_map(|a| a.strip_prefix("--assist=")) {
+        Some(level) => level.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => AssistLevel::None,
+    };
+    let neighborhood = match args.iter().find_map(|a| a.strip_prefix("--neighborhood=")) {
+        Some(neighborhood) => neighborhood.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => Neighborhood::Adjacent,
+    };
+    let shape = match args.iter().find_map(|a| a.strip_prefix("--shape=")) {
+        Some(shape) => BoardShape::load(shape).unwrap_or_else(|e| {
+            eprintln!("Failed to load shape {}: {}", shape, e);
+            std::process::exit(1);
+        }),
+        None => BoardShape::Rectangle,
+    };
+    let max_mines_per_cell = match args.iter().find_map(|a| a.strip_prefix("--multi-mine=")) {
+        Some(max) => match max.parse::<u8>() {
+            Ok(max @ 1..=3) => max,
+            _ => {
+                eprintln!("invalid mine count {:?}, expected 1 to 3", max);
+                std::process::exit(1);
+            }
+        },
+        None => 1,
+    };
+    let rating_band = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--rating="))
+        .map(|band| {
+            parse_band(band).unwrap_or_else(|| {
+                eprintln!("invalid rating band {:?}, expected MIN-MAX", band);
+                std::process::exit(1);
+            })
+        });
+    let (map_size, bomb_count) = match arg.as_deref() {
+        Some("beginner") => ((9, 9), 10),
+        Some("expert") => ((30, 16), 99),
+        _ => ((16, 16), 40),
+    };
+    let layout = match arg {
+        Some(path) if Path::new(&path).is_file() => match load_layout(Path::new(&path)) {
+            Ok(layout) => Some(layout),
+            Err(e) => {
+                eprintln!("Failed to load layout {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+    let topology = if args.iter().any(|a| a == "--hex") {
+        Topology::Hex
+    } else {
+        Topology::Square
+    };
+    let wrap = args.iter().any(|a| a == "--wrap");
+    let question_marks = args.iter().any(|a| a == "--question-marks");
+    let mut game = TuiGame::new(BoardOptions {
+        map_size,
+        topology,
+        shape,
+        wrap,
+        neighborhood,
+        bomb_count,
+        max_mines_per_cell,
+        safe_start: true,
+        layout,
+        assist,
+        question_marks,
+        rating_band,
+        ..Default::default()
+    });
+
+    let mut stdout = stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+    let res = run(&mut stdout, &mut game);
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    res
+}
+
+/// Parses a `MIN-MAX` rating band
+fn parse_band(band: &str) -> Option<(f32, f32)> {
+    let (min, max) = band.split_once('-')?;
+    Some((min.parse().ok()?, max.parse().ok()?))
+}
+
+/// Loads a layout file, guessing its format from the extension
+fn load_layout(path: &Path) -> Result<BoardLayout, Box<dyn Error>> {
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(LayoutFormat::from_extension)
+        .ok_or("unknown layout format, expected .txt, .json or .mbf")?;
+    Ok(BoardLayout::read(format, &fs::read(path)?)?)
+}
+
+fn run(stdout: &mut Stdout, game: &mut TuiGame) -> crossterm::Result<()> {
+    loop {
+        queue!(stdout, Clear(ClearType::All))?;
+        for (i, line) in game.render().iter().enumerate() {
+            queue!(stdout, MoveTo(0, i as u16), Print(line))?;
+        }
+        stdout.flush()?;
+        // We redraw at least every 250ms to keep the timer running
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !game.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
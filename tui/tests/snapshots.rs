@@ -0,0 +1,256 @@
+use board_plugin::headless::GameStatus;
+use board_plugin::{
+    AssistLevel, BoardLayout, BoardOptions, BoardShape, Neighborhood, ShapeMask, Topology,
+};
+use crossterm::event::KeyCode;
+use minesweeper_tui::TuiGame;
+
+/// Plays `keys` on a new board and returns the game
+fn play(map_size: (u16, u16), bomb_count: u16, keys: &[KeyCode]) -> TuiGame {
+    play_with(
+        BoardOptions {
+            map_size,
+            bomb_count,
+            ..Default::default()
+        },
+        keys,
+    )
+}
+
+/// Plays `keys` on the text `layout` with the `assist` level and returns the game
+fn play_layout(layout: &str, assist: AssistLevel, keys: &[KeyCode]) -> TuiGame {
+    play_with(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text(layout).unwrap()),
+            assist,
+            ..Default::default()
+        },
+        keys,
+    )
+}
+
+fn play_with(options: BoardOptions, keys: &[KeyCode]) -> TuiGame {
+    // Snapshots are compared without color codes
+    colored::control::set_override(false);
+    let mut game = TuiGame::new(options);
+    for key in keys {
+        assert!(game.handle_key(*key));
+    }
+    game
+}
+
+#[test]
+fn starts_covered() {
+    let game = play((4, 3), 0, &[]);
+    assert_eq!(
+        game.render_grid(),
+        vec![" #  #  #  # ", " #  #  #  # ", "[#] #  #  # "]
+    );
+    assert_eq!(game.board().status(), GameStatus::Playing);
+}
+
+#[test]
+fn cursor_stays_on_the_board() {
+    let keys = [
+        KeyCode::Left,
+        KeyCode::Down,
+        KeyCode::Up,
+        KeyCode::Up,
+        KeyCode::Up,
+    ];
+    let game = play((3, 2), 0, &keys);
+    assert_eq!(game.render_grid(), vec!["[#] #  # ", " #  #  # "]);
+}
+
+#[test]
+fn marks_and_unmarks() {
+    let keys = [KeyCode::Right, KeyCode::Up, KeyCode::Char('f')];
+    let game = play((3, 2), 0, &keys);
+    assert_eq!(game.render_grid(), vec![" # [F] # ", " #  #  # "]);
+    assert!(game.render_status().starts_with("Mines:  -1"));
+
+    let keys = [KeyCode::Char('f'), KeyCode::Char('f')];
+    let game = play((3, 2), 0, &keys);
+    assert_eq!(game.render_grid(), vec![" #  #  # ", "[#] #  # "]);
+}
+
+#[test]
+fn flood_fill_wins_empty_board() {
+    let keys = [KeyCode::Right, KeyCode::Char(' ')];
+    let game = play((3, 2), 0, &keys);
+    assert_eq!(game.render_grid(), vec!["         ", "   [ ]   "]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn explosion_shows_bombs() {
+    let keys = [
+        KeyCode::Right,
+        KeyCode::Char('f'),
+        KeyCode::Left,
+        KeyCode::Enter,
+    ];
+    let game = play((2, 2), 4, &keys);
+    assert_eq!(game.render_grid(), vec![" *  * ", "[*] F "]);
+    assert_eq!(game.board().status(), GameStatus::Lost);
+    assert!(game.render_status().ends_with("Boom!"));
+}
+
+#[test]
+fn auto_flag_marks_obvious_bombs() {
+    let keys = [
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Char(' '),
+    ];
+    let game = play_layout("*...", AssistLevel::None, &keys);
+    assert_eq!(game.render_grid(), vec![" #  1    [ ]"]);
+
+    let game = play_layout("*...", AssistLevel::AutoFlag, &keys);
+    assert_eq!(game.render_grid(), vec![" F  1    [ ]"]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn auto_reveal_opens_satisfied_numbers() {
+    let keys = [
+        KeyCode::Right,
+        KeyCode::Char(' '),
+        KeyCode::Left,
+        KeyCode::Char('f'),
+    ];
+    let game = play_layout("*..*", AssistLevel::None, &keys);
+    assert_eq!(game.render_grid(), vec!["[F] 1  #  # "]);
+
+    // The satisfied 1 opens the last safe tile
+    let game = play_layout("*..*", AssistLevel::AutoReveal, &keys);
+    assert_eq!(game.render_grid(), vec!["[F] 1  1  # "]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn hex_rows_are_shifted() {
+    // The bomb at (2, 1) isn't a neighbor of (1, 0) on hex boards, so the flood fill goes on
+    let keys = [KeyCode::Char(' ')];
+    let game = play_with(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text("..*\n...").unwrap()),
+            topology: Topology::Hex,
+            ..Default::default()
+        },
+        &keys,
+    );
+    assert_eq!(game.render_grid(), vec!["     1  # ", "[ ]    1 "]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn wrapping_edges_touch() {
+    // (0, 0) is next to the bomb at (4, 0) through the left edge
+    let keys = [KeyCode::Right, KeyCode::Right, KeyCode::Char(' ')];
+    let game = play_with(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text("....*").unwrap()),
+            wrap: true,
+            ..Default::default()
+        },
+        &keys,
+    );
+    assert_eq!(game.render_grid(), vec![" 1    [ ] 1  # "]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn knight_numbers_count_knight_moves() {
+    // (0, 0) and (2, 0) are a knight's move away from the bomb at (1, 2), and the flood fill
+    // never reaches (1, 1) which has no neighbor
+    let keys = [KeyCode::Right, KeyCode::Char(' ')];
+    let game = play_with(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text(".*.\n...\n...").unwrap()),
+            neighborhood: Neighborhood::Knight,
+            ..Default::default()
+        },
+        &keys,
+    );
+    assert_eq!(
+        game.render_neighborhood(),
+        vec![
+            "Numbers count knight neighbors:",
+            ".x.x.",
+            "x...x",
+            "..o..",
+            "x...x",
+            ".x.x.",
+            ""
+        ]
+    );
+    assert_eq!(
+        game.render_grid(),
+        vec!["    #    ", "    #    ", " 1 [ ] 1 "]
+    );
+}
+
+#[test]
+fn void_tiles_are_skipped() {
+    // (1, 0) is void, so (2, 0) doesn't touch the bomb at (0, 0) and the board is won once the
+    // real tiles are uncovered
+    let keys = [
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Right,
+        KeyCode::Char(' '),
+    ];
+    let game = play_with(
+        BoardOptions {
+            layout: Some(BoardLayout::from_text("*...").unwrap()),
+            shape: BoardShape::Mask(ShapeMask::from_text("#.##").unwrap()),
+            ..Default::default()
+        },
+        &keys,
+    );
+    assert_eq!(game.render_grid(), vec![" #       [ ]"]);
+    assert_eq!(game.board().status(), GameStatus::Won);
+}
+
+#[test]
+fn multi_mine_flags_cycle() {
+    let options = BoardOptions {
+        layout: Some(BoardLayout::from_text("*.").unwrap()),
+        max_mines_per_cell: 2,
+        ..Default::default()
+    };
+    let game = play_with(options.clone(), &[KeyCode::Char('f')]);
+    assert_eq!(game.render_grid(), vec!["[F] # "]);
+    let game = play_with(options.clone(), &[KeyCode::Char('f'), KeyCode::Char('f')]);
+    assert_eq!(game.render_grid(), vec!["[2] # "]);
+    assert!(game.render_status().starts_with("Mines:  -1"));
+    let keys = [KeyCode::Char('f'), KeyCode::Char('f'), KeyCode::Char('f')];
+    let game = play_with(options, &keys);
+    assert_eq!(game.render_grid(), vec!["[#] # "]);
+}
+
+#[test]
+fn question_marks_cycle() {
+    let options = BoardOptions {
+        layout: Some(BoardLayout::from_text("*..").unwrap()),
+        question_marks: true,
+        ..Default::default()
+    };
+    let game = play_with(options.clone(), &[KeyCode::Char('f'), KeyCode::Char('f')]);
+    assert_eq!(game.render_grid(), vec!["[?] #  # "]);
+    assert!(game.render_status().starts_with("Mines:   1"));
+    let keys = [KeyCode::Char('f'), KeyCode::Char('f'), KeyCode::Char('f')];
+    let game = play_with(options.clone(), &keys);
+    assert_eq!(game.render_grid(), vec!["[#] #  # "]);
+    // Question marks don't keep the tile from being uncovered
+    let keys = [
+        KeyCode::Right,
+        KeyCode::Char('f'),
+        KeyCode::Char('f'),
+        KeyCode::Char(' '),
+    ];
+    let game = play_with(options, &keys);
+    assert_eq!(game.render_grid(), vec![" # [1] # "]);
+}